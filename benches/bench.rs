@@ -5,7 +5,7 @@
 extern crate test;
 use test::Bencher;
 
-use der_parser::ber::{BerClass, BerObjectHeader, BerTag};
+use der_parser::ber::{BerClass, BerObjectHeader, BerSize, BerTag};
 use der_parser::der::{
     der_read_element_header, parse_der, parse_der_integer, parse_der_u32, DerObject,
 };
@@ -13,6 +13,7 @@ use der_parser::error::DerResult;
 use der_parser::*;
 use hex_literal::hex;
 use nom::{map, map_res};
+use std::borrow::Cow;
 
 #[bench]
 fn bench_der_read_element_header(b: &mut Bencher) {
@@ -99,3 +100,159 @@ fn bench_parse_der_seq_macros(b: &mut Bencher) {
         }
     });
 }
+
+/// Build an object with the structure of a certificate chain (3 certificates)
+#[cfg(feature = "serialize")]
+fn build_cert_chain<'a>(key: &'a [u8]) -> DerObject<'a> {
+    use der_parser::ber::{BerObjectContent, BitStringObject};
+    use der_parser::oid::Oid;
+    let oid = || Oid::from(&[1, 2, 840, 113_549, 1, 1, 11]).unwrap();
+    let alg_id = || {
+        DerObject::from_seq(vec![
+            DerObject::from(oid()),
+            DerObject::from_obj(BerObjectContent::Null),
+        ])
+    };
+    let name = || {
+        let rdns = (0..5)
+            .map(|_| {
+                DerObject::from_set(vec![DerObject::from_seq(vec![
+                    DerObject::from(oid()),
+                    DerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed(
+                        "Some-State",
+                    ))),
+                ])])
+            })
+            .collect();
+        DerObject::from_seq(rdns)
+    };
+    let tagged = |tag, obj| {
+        let hdr = BerObjectHeader::new(
+            BerClass::ContextSpecific,
+            1,
+            BerTag(tag),
+            BerSize::Definite(0),
+        );
+        DerObject::from_header_and_content(
+            hdr,
+            BerObjectContent::Tagged(BerClass::ContextSpecific, BerTag(tag), Box::new(obj)),
+        )
+    };
+    let bitstring = || {
+        DerObject::from_obj(BerObjectContent::BitString(
+            BitStringObject::new(0, key).unwrap(),
+        ))
+    };
+    let cert = || {
+        let extensions = (0..8)
+            .map(|_| {
+                DerObject::from_seq(vec![
+                    DerObject::from(oid()),
+                    DerObject::from_obj(BerObjectContent::Boolean(true)),
+                    DerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(&key[..32]))),
+                ])
+            })
+            .collect();
+        let tbs = DerObject::from_seq(vec![
+            tagged(0, DerObject::from_int_slice(b"\x02")),
+            DerObject::from_int_slice(&key[..16]),
+            alg_id(),
+            name(),
+            DerObject::from_seq(vec![
+                DerObject::from_obj(BerObjectContent::UTCTime(Cow::Borrowed("190101000000Z"))),
+                DerObject::from_obj(BerObjectContent::UTCTime(Cow::Borrowed("290101000000Z"))),
+            ]),
+            name(),
+            DerObject::from_seq(vec![alg_id(), bitstring()]),
+            tagged(3, DerObject::from_seq(extensions)),
+        ]);
+        DerObject::from_seq(vec![tbs, alg_id(), bitstring()])
+    };
+    DerObject::from_seq(vec![cert(), cert(), cert()])
+}
+
+#[cfg(feature = "serialize")]
+#[bench]
+fn bench_write_der_cert_chain(b: &mut Bencher) {
+    let key = vec![0x5a; 256];
+    let obj = build_cert_chain(&key);
+    let mut v = Vec::with_capacity(obj.der_total_len());
+    b.iter(|| {
+        v.clear();
+        let n = obj.write_der(&mut v).expect("could not write");
+        assert_eq!(n, v.len());
+    });
+}
+
+#[cfg(feature = "serialize")]
+#[bench]
+fn bench_write_der_nested(b: &mut Bencher) {
+    let mut obj = DerObject::from_int_slice(b"\x01");
+    for _ in 0..100 {
+        obj = DerObject::from_seq(vec![DerObject::from_int_slice(b"\x01"), obj]);
+    }
+    let mut v = Vec::with_capacity(obj.der_total_len());
+    b.iter(|| {
+        v.clear();
+        let n = obj.write_der(&mut v).expect("could not write");
+        assert_eq!(n, v.len());
+    });
+}
+
+#[cfg(feature = "serialize")]
+#[bench]
+fn bench_parse_ber_cert_chain(b: &mut Bencher) {
+    let key = vec![0x5a; 256];
+    let bytes = build_cert_chain(&key)
+        .to_der_vec()
+        .expect("could not encode");
+    b.iter(|| {
+        let (rem, obj) = der_parser::parse_ber(&bytes).expect("parsing failed");
+        assert!(rem.is_empty());
+        assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(3));
+    });
+}
+
+#[cfg(feature = "serialize")]
+#[bench]
+fn bench_parse_der_cert_chain(b: &mut Bencher) {
+    let key = vec![0x5a; 256];
+    let bytes = build_cert_chain(&key)
+        .to_der_vec()
+        .expect("could not encode");
+    b.iter(|| {
+        let (rem, obj) = parse_der(&bytes).expect("parsing failed");
+        assert!(rem.is_empty());
+        assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(3));
+    });
+}
+
+/// Encoding of a SEQUENCE OF 1000 INTEGER
+fn build_sequence_of_integers() -> Vec<u8> {
+    let mut bytes = vec![0x30, 0x82, 0x13, 0x88];
+    for i in 0..1000u32 {
+        bytes.extend_from_slice(&[0x02, 0x03, 0x01, (i >> 8) as u8, i as u8]);
+    }
+    bytes
+}
+
+#[bench]
+fn bench_parse_ber_large_sequence(b: &mut Bencher) {
+    let bytes = build_sequence_of_integers();
+    b.iter(|| {
+        let (rem, obj) = der_parser::parse_ber(&bytes).expect("parsing failed");
+        assert!(rem.is_empty());
+        assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(1000));
+    });
+}
+
+#[bench]
+fn bench_parse_ber_large_sequence_of(b: &mut Bencher) {
+    use der_parser::ber::{parse_ber_integer, parse_ber_sequence_of};
+    let bytes = build_sequence_of_integers();
+    b.iter(|| {
+        let (rem, obj) = parse_ber_sequence_of(parse_ber_integer)(&bytes).expect("parsing failed");
+        assert!(rem.is_empty());
+        assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(1000));
+    });
+}
@@ -0,0 +1,215 @@
+//! Derive macros for the [der-parser](https://crates.io/crates/der-parser) crate
+//!
+//! This crate should not be used directly: enable the `derive` feature of `der-parser`, and use
+//! the re-exported macros.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Fields, GenericArgument, GenericParam, Lifetime, Lit,
+    Meta, NestedMeta, PathArguments, Type,
+};
+
+/// Attributes of a field: `#[ber(tag = N, explicit, optional)]`
+#[derive(Default)]
+struct FieldAttributes {
+    tag: Option<u32>,
+    explicit: bool,
+    optional: bool,
+}
+
+fn parse_field_attributes(attrs: &[syn::Attribute]) -> Result<FieldAttributes, syn::Error> {
+    let mut result = FieldAttributes::default();
+    for attr in attrs.iter().filter(|a| a.path.is_ident("ber")) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => return Err(syn::Error::new_spanned(meta, "expected #[ber(...)]")),
+        };
+        for item in list.nested.iter() {
+            match item {
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("tag") => match nv.lit {
+                    Lit::Int(ref i) => result.tag = Some(i.base10_parse()?),
+                    ref lit => return Err(syn::Error::new_spanned(lit, "tag must be an integer")),
+                },
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("explicit") => result.explicit = true,
+                NestedMeta::Meta(Meta::Path(p)) if p.is_ident("optional") => result.optional = true,
+                item => return Err(syn::Error::new_spanned(item, "unknown ber attribute")),
+            }
+        }
+    }
+    if result.explicit && result.tag.is_none() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "explicit requires a tag attribute",
+        ));
+    }
+    Ok(result)
+}
+
+/// Return `T` if `ty` is `Option<T>`
+fn option_inner_type(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(p) if p.qself.is_none() => &p.path,
+        _ => return None,
+    };
+    let segment = path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    match segment.arguments {
+        PathArguments::AngleBracketed(ref args) if args.args.len() == 1 => match args.args[0] {
+            GenericArgument::Type(ref t) => Some(t),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn field_parser(
+    ty: &Type,
+    attrs: &FieldAttributes,
+    lt: &Lifetime,
+) -> Result<TokenStream2, syn::Error> {
+    let ty = if attrs.optional {
+        option_inner_type(ty).ok_or_else(|| {
+            syn::Error::new_spanned(ty, "optional fields must have type Option<T>")
+        })?
+    } else {
+        ty
+    };
+    let parser = match attrs.tag {
+        None => quote! { <#ty as ::der_parser::ber::FromBer<#lt>>::from_ber },
+        Some(tag) if attrs.explicit => quote! {
+            ::der_parser::ber::parse_ber_tagged_explicit_g(#tag, |_, content: &#lt [u8]| {
+                <#ty as ::der_parser::ber::FromBer<#lt>>::from_ber(content)
+            })
+        },
+        Some(tag) => quote! {
+            ::der_parser::ber::parse_ber_tagged_implicit_g(#tag, |content: &#lt [u8], hdr, depth| {
+                <#ty as ::der_parser::ber::FromBer<#lt>>::from_ber_content(content, &hdr, depth)
+            })
+        },
+    };
+    let parser = match (attrs.optional, attrs.tag) {
+        (false, _) => parser,
+        (true, Some(tag)) => quote! {
+            ::der_parser::ber::parse_ber_optional_with_tag(
+                ::der_parser::ber::BerClassTag::context(#tag),
+                #parser,
+            )
+        },
+        (true, None) => quote! {
+            |i: &#lt [u8]| {
+                if i.is_empty() {
+                    return Ok((i, None));
+                }
+                match (#parser)(i) {
+                    Ok((rem, value)) => Ok((rem, Some(value))),
+                    Err(::der_parser::nom::Err::Error(_)) => Ok((i, None)),
+                    Err(e) => Err(e),
+                }
+            }
+        },
+    };
+    Ok(parser)
+}
+
+fn derive_ber_sequence_impl(input: DeriveInput) -> Result<TokenStream2, syn::Error> {
+    let name = &input.ident;
+    let fields = match input.data {
+        Data::Struct(ref s) => match s.fields {
+            Fields::Named(ref fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "BerSequence can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "BerSequence can only be derived for structs",
+            ))
+        }
+    };
+    // the struct can have at most one lifetime, used for the input
+    let mut lifetimes = Vec::new();
+    for param in input.generics.params.iter() {
+        match param {
+            GenericParam::Lifetime(l) => lifetimes.push(l.lifetime.clone()),
+            param => {
+                return Err(syn::Error::new_spanned(
+                    param,
+                    "BerSequence does not support type or const parameters",
+                ))
+            }
+        }
+    }
+    let (lt, ty_generics) = match lifetimes.len() {
+        0 => (Lifetime::new("'a", Span::call_site()), quote! {}),
+        1 => {
+            let lt = lifetimes[0].clone();
+            (lt.clone(), quote! { <#lt> })
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.generics,
+                "BerSequence supports at most one lifetime parameter",
+            ))
+        }
+    };
+
+    let mut parse_fields = Vec::new();
+    let mut field_names = Vec::new();
+    for field in fields.iter() {
+        let ident = field.ident.as_ref().expect("named field");
+        let attrs = parse_field_attributes(&field.attrs)?;
+        let parser = field_parser(&field.ty, &attrs, &lt)?;
+        parse_fields.push(quote! {
+            let (i, #ident) = (#parser)(i)?;
+        });
+        field_names.push(ident);
+    }
+
+    Ok(quote! {
+        impl<#lt> ::der_parser::ber::FromBer<#lt> for #name #ty_generics {
+            fn from_ber(i: &#lt [u8]) -> ::der_parser::error::BerResult<#lt, Self> {
+                ::der_parser::ber::parse_ber_sequence_defined_g(
+                    |hdr: ::der_parser::ber::BerObjectHeader<#lt>, i: &#lt [u8]| {
+                        Self::from_ber_content(i, &hdr, ::der_parser::ber::MAX_RECURSION)
+                    },
+                )(i)
+            }
+
+            fn from_ber_content(
+                i: &#lt [u8],
+                _hdr: &::der_parser::ber::BerObjectHeader<#lt>,
+                _max_depth: usize,
+            ) -> ::der_parser::error::BerResult<#lt, Self> {
+                #(#parse_fields)*
+                Ok((i, #name { #(#field_names),* }))
+            }
+        }
+    })
+}
+
+/// Derive a BER parser for a structure, parsed from a SEQUENCE
+///
+/// Each field is parsed in order, using its implementation of the `FromBer` trait. The
+/// following attributes can be used on fields:
+/// - `#[ber(tag = N)]`: the field is tagged with the context-specific tag `N` (IMPLICIT)
+/// - `#[ber(tag = N, explicit)]`: the field is tagged with the context-specific tag `N` (EXPLICIT)
+/// - `#[ber(optional)]`: the field has type `Option<T>`, and is `None` if the next object is not
+///   a `T` (or does not have the expected tag)
+#[proc_macro_derive(BerSequence, attributes(ber))]
+pub fn derive_ber_sequence(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match derive_ber_sequence_impl(input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
@@ -1,13 +1,22 @@
-use crate::ber::{bitstring_to_u64, bytes_to_u64};
-use crate::error::BerError;
+use crate::ber::{
+    bitstring_to_u64, bytes_to_i128, bytes_to_i64, bytes_to_u64, decode_generalizedtime,
+    decode_utctime, is_numeric_char, is_printable_char, is_visible_char, parse_ber, BerEmbeddedPdv,
+    BerExternal, GeneralizedTime, UtcTime,
+};
+use crate::der::parse_der;
+use crate::error::{BerError, BerResult};
 use crate::oid::Oid;
-use rusticata_macros::newtype_enum;
-use std::convert::AsRef;
-use std::convert::From;
-use std::convert::TryFrom;
-use std::fmt;
-use std::ops::Index;
-use std::vec::Vec;
+use alloc::borrow::{Cow, ToOwned};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "bitvec")]
+use bitvec::{order::Msb0, slice::BitSlice};
+use core::convert::AsRef;
+use core::convert::From;
+use core::convert::TryFrom;
+use core::fmt;
+use core::ops::Index;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct BerClassFromIntError(pub(crate) ());
@@ -25,6 +34,9 @@ pub enum BerClass {
     Private = 0b11,
 }
 
+/// Class of a tag (alias for [`BerClass`](enum.BerClass.html))
+pub type TagClass = BerClass;
+
 /// Ber Object Length
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum BerSize {
@@ -41,6 +53,74 @@ pub enum BerSize {
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub struct BerTag(pub u32);
 
+/// Tag of an object (alias for [`BerTag`](struct.BerTag.html))
+///
+/// Universal tags have named constants, and other tags (for ex. context-specific) keep their
+/// number:
+///
+/// ```rust
+/// use der_parser::ber::*;
+///
+/// let (_, obj) = parse_ber(&[0x02, 0x01, 0x05]).expect("parsing failed");
+/// assert_eq!(obj.header.tag, Tag::Integer);
+///
+/// let (_, hdr) = ber_read_element_header(&[0xbf, 0x43, 0x00]).expect("parsing failed");
+/// assert_eq!(hdr.tag, Tag::from(67));
+/// assert_eq!(u32::from(hdr.tag), 67);
+/// ```
+pub type Tag = BerTag;
+
+/// Tag with its class, for ex. `[0]` (context-specific) or `[APPLICATION 5]`
+///
+/// This can be used to build or match tags without dealing with the encoding of the class. To
+/// parse tagged values, use the class and the tag number with
+/// [`parse_ber_tagged_explicit_with_class`](fn.parse_ber_tagged_explicit_with_class.html) or
+/// [`parse_ber_tagged_implicit_with_class`](fn.parse_ber_tagged_implicit_with_class.html).
+///
+/// ```rust
+/// use der_parser::ber::*;
+///
+/// let tag = BerClassTag::context(2);
+/// assert_eq!(tag.to_string(), "[2]");
+///
+/// let bytes = &[0xa2, 0x03, 0x02, 0x01, 0x05];
+/// let (_, hdr) = ber_read_element_header(bytes).expect("parsing failed");
+/// assert_eq!(hdr.class_tag(), tag);
+/// let parser = parse_ber_tagged_explicit_with_class(tag.class, tag.tag, parse_ber_integer);
+/// let (_, obj) = parser(bytes).expect("parsing failed");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BerClassTag {
+    pub class: TagClass,
+    pub tag: BerTag,
+}
+
+// same as `rusticata_macros::newtype_enum` (`impl debug` variant), but using `core` so it can
+// be used without std
+macro_rules! newtype_enum (
+    (impl debug $name:ident {$($key:ident = $val:expr),* $(,)*}) => (
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $( pub const $key : $name = $name($val); )*
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match self.0 {
+                    $( $val => write!(f, stringify!{$key}), )*
+                    n => write!(f, "{}({} / 0x{:x})", stringify!{$name}, n, n)
+                }
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}", self)
+            }
+        }
+    );
+);
+
 newtype_enum! {
 impl debug BerTag {
     EndOfContent = 0x0,
@@ -87,6 +167,10 @@ impl debug BerTag {
 ///
 /// Note that the content may sometimes not match the header tag (for ex when parsing IMPLICIT
 /// tagged values).
+///
+/// Parsing is zero-copy: the content borrows slices of the input (as `Cow::Borrowed`), so the
+/// object cannot outlive the parsed buffer. Use [`into_owned`](#method.into_owned) to copy the
+/// content and get a `BerObject<'static>`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct BerObject<'a> {
     pub header: BerObjectHeader<'a>,
@@ -109,7 +193,14 @@ pub struct BerObjectHeader<'a> {
     ///
     /// This is useful in some cases, where different representations of the same
     /// BER tags have different meanings (BER only)
-    pub raw_tag: Option<&'a [u8]>,
+    pub raw_tag: Option<Cow<'a, [u8]>>,
+
+    /// Optionally, the raw encoding of the header (identifier and length octets), as parsed
+    pub raw_header: Option<Cow<'a, [u8]>>,
+    /// Optionally, the raw encoding of the content, as parsed
+    ///
+    /// For the indefinite length form, this includes the end-of-content octets.
+    pub raw_content: Option<Cow<'a, [u8]>>,
 }
 
 /// BER object content
@@ -117,38 +208,51 @@ pub struct BerObjectHeader<'a> {
 pub enum BerObjectContent<'a> {
     EndOfContent,
     Boolean(bool),
-    Integer(&'a [u8]),
-    BitString(u8, BitStringObject<'a>),
-    OctetString(&'a [u8]),
+    Integer(Cow<'a, [u8]>),
+    /// BitString, with the number of unused bits
+    BitString(BitStringObject<'a>),
+    /// Constructed BitString (BER only): number of unused bits, and concatenated segments
+    BitStringConstructed(u8, Vec<u8>),
+    OctetString(Cow<'a, [u8]>),
+    /// Constructed OctetString (BER only), the segments are concatenated
+    OctetStringConstructed(Vec<u8>),
     Null,
+    Real(f64),
+    /// Enumerated value, stored as the two's complement representation of a signed value (use
+    /// `as_i64` to get negative values)
     Enum(u64),
     OID(Oid<'a>),
     RelativeOID(Oid<'a>),
-    NumericString(&'a str),
-    VisibleString(&'a str),
-    PrintableString(&'a str),
-    IA5String(&'a str),
-    UTF8String(&'a str),
-    T61String(&'a [u8]),
-    VideotexString(&'a [u8]),
+    NumericString(Cow<'a, str>),
+    VisibleString(Cow<'a, str>),
+    PrintableString(Cow<'a, str>),
+    IA5String(Cow<'a, str>),
+    UTF8String(Cow<'a, str>),
+    T61String(Cow<'a, [u8]>),
+    VideotexString(Cow<'a, [u8]>),
 
-    BmpString(&'a [u8]),
-    UniversalString(&'a [u8]),
+    BmpString(Cow<'a, [u8]>),
+    UniversalString(Cow<'a, [u8]>),
 
     Sequence(Vec<BerObject<'a>>),
     Set(Vec<BerObject<'a>>),
 
-    UTCTime(&'a str),
-    GeneralizedTime(&'a str),
+    UTCTime(Cow<'a, str>),
+    GeneralizedTime(Cow<'a, str>),
+
+    ObjectDescriptor(Cow<'a, [u8]>),
+    GraphicString(Cow<'a, [u8]>),
+    GeneralString(Cow<'a, [u8]>),
 
-    ObjectDescriptor(&'a [u8]),
-    GraphicString(&'a [u8]),
-    GeneralString(&'a [u8]),
+    /// EXTERNAL value, with its components decoded
+    External(BerExternal<'a>),
+    /// EMBEDDED PDV value, with its components decoded
+    EmbeddedPdv(BerEmbeddedPdv<'a>),
 
     Optional(Option<Box<BerObject<'a>>>),
     Tagged(BerClass, BerTag, Box<BerObject<'a>>),
 
-    Unknown(BerTag, &'a [u8]),
+    Unknown(BerTag, Cow<'a, [u8]>),
 }
 
 impl fmt::Display for BerClass {
@@ -169,12 +273,61 @@ impl From<u32> for BerTag {
     }
 }
 
+impl From<BerTag> for u32 {
+    fn from(tag: BerTag) -> Self {
+        tag.0
+    }
+}
+
+impl BerClassTag {
+    /// Build a tag from its class and number
+    pub const fn new(class: TagClass, tag: BerTag) -> Self {
+        BerClassTag { class, tag }
+    }
+
+    /// Build a universal tag, for ex. `BerClassTag::universal(BerTag::Integer)`
+    pub const fn universal(tag: BerTag) -> Self {
+        BerClassTag::new(BerClass::Universal, tag)
+    }
+
+    /// Build an application tag `[APPLICATION n]`
+    pub const fn application(n: u32) -> Self {
+        BerClassTag::new(BerClass::Application, BerTag(n))
+    }
+
+    /// Build a context-specific tag `[n]`
+    pub const fn context(n: u32) -> Self {
+        BerClassTag::new(BerClass::ContextSpecific, BerTag(n))
+    }
+
+    /// Build a private tag `[PRIVATE n]`
+    pub const fn private(n: u32) -> Self {
+        BerClassTag::new(BerClass::Private, BerTag(n))
+    }
+}
+
+/// Display the tag using the ASN.1 notation, for ex. `[APPLICATION 5]`
+impl fmt::Display for BerClassTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.class {
+            BerClass::ContextSpecific => write!(f, "[{}]", self.tag.0),
+            class => write!(f, "[{} {}]", class, self.tag.0),
+        }
+    }
+}
+
 impl BerSize {
     /// Return true if length is definite and equal to 0
     pub fn is_null(&self) -> bool {
         *self == BerSize::Definite(0)
     }
 
+    /// Return true if length uses the indefinite form (BER only)
+    #[inline]
+    pub fn is_indefinite(&self) -> bool {
+        *self == BerSize::Indefinite
+    }
+
     /// Get length of primitive object
     #[inline]
     pub fn primitive(&self) -> Result<usize, BerError> {
@@ -236,6 +389,8 @@ impl<'a> BerObjectHeader<'a> {
             class,
             len: len.into(),
             raw_tag: None,
+            raw_header: None,
+            raw_content: None,
         }
     }
 
@@ -260,7 +415,26 @@ impl<'a> BerObjectHeader<'a> {
     /// Update header to add reference to raw tag
     #[inline]
     pub fn with_raw_tag(self, raw_tag: Option<&'a [u8]>) -> Self {
-        BerObjectHeader { raw_tag, ..self }
+        BerObjectHeader {
+            raw_tag: raw_tag.map(Cow::Borrowed),
+            ..self
+        }
+    }
+
+    /// Update header to add references to the raw encoding of the header and content
+    #[inline]
+    pub(crate) fn with_raw_encoding(self, header: &'a [u8], content: &'a [u8]) -> Self {
+        BerObjectHeader {
+            raw_header: Some(Cow::Borrowed(header)),
+            raw_content: Some(Cow::Borrowed(content)),
+            ..self
+        }
+    }
+
+    /// Get the class and number of the tag
+    #[inline]
+    pub fn class_tag(&self) -> BerClassTag {
+        BerClassTag::new(self.class, self.tag)
     }
 
     /// Test if object class is Universal
@@ -294,6 +468,30 @@ impl<'a> BerObjectHeader<'a> {
     pub fn is_constructed(&self) -> bool {
         self.structured == 1
     }
+
+    /// Test if object length uses the indefinite form (BER only)
+    #[inline]
+    pub fn is_indefinite(&self) -> bool {
+        self.len.is_indefinite()
+    }
+
+    /// Convert the header to an owned value, copying the raw encodings if they are borrowed
+    pub fn into_owned(self) -> BerObjectHeader<'static> {
+        BerObjectHeader {
+            class: self.class,
+            structured: self.structured,
+            tag: self.tag,
+            len: self.len,
+            raw_tag: self.raw_tag.map(into_owned_cow),
+            raw_header: self.raw_header.map(into_owned_cow),
+            raw_content: self.raw_content.map(into_owned_cow),
+        }
+    }
+}
+
+/// Convert a `Cow` to an owned value, copying the data if it is borrowed
+pub(crate) fn into_owned_cow<T: ToOwned + ?Sized + 'static>(c: Cow<'_, T>) -> Cow<'static, T> {
+    Cow::Owned(c.into_owned())
 }
 
 impl<'a> BerObject<'a> {
@@ -313,8 +511,12 @@ impl<'a> BerObject<'a> {
     pub fn from_obj(c: BerObjectContent) -> BerObject {
         let class = BerClass::Universal;
         let tag = c.tag();
-        let structured = match tag {
-            BerTag::Sequence | BerTag::Set => 1,
+        let structured = match (tag, &c) {
+            (BerTag::Sequence, _) | (BerTag::Set, _) => 1,
+            (_, BerObjectContent::OctetStringConstructed(_))
+            | (_, BerObjectContent::BitStringConstructed(_, _))
+            | (_, BerObjectContent::External(_))
+            | (_, BerObjectContent::EmbeddedPdv(_)) => 1,
             _ => 0,
         };
         let header = BerObjectHeader::new(class, structured, tag, BerSize::Definite(0));
@@ -331,19 +533,100 @@ impl<'a> BerObject<'a> {
         );
         BerObject {
             header,
-            content: BerObjectContent::Integer(i),
+            content: BerObjectContent::Integer(Cow::Borrowed(i)),
+        }
+    }
+
+    /// Build a DER boolean object
+    pub fn from_bool(b: bool) -> BerObject<'a> {
+        BerObject::from_obj(BerObjectContent::Boolean(b))
+    }
+
+    /// Build a DER OID object
+    pub fn from_oid(oid: Oid<'a>) -> BerObject<'a> {
+        BerObject::from_obj(BerObjectContent::OID(oid))
+    }
+
+    /// Build a DER OctetString object from a slice
+    pub fn from_octetstring(s: &'a [u8]) -> BerObject<'a> {
+        BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(s)))
+    }
+
+    /// Return the raw encoding of the header (identifier and length octets), as parsed
+    ///
+    /// Returns `None` if the object was not built by a parsing function.
+    pub fn raw_header(&self) -> Option<&[u8]> {
+        self.header.raw_header.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Return the raw encoding of the content, as parsed
+    ///
+    /// This can be used to hash the original encoding (for ex. the `TBSCertificate` of a X.509
+    /// certificate, with the header), which can be different from a re-encoded object.
+    ///
+    /// For the indefinite length form, this includes the end-of-content octets. Returns `None` if
+    /// the object was not built by a parsing function.
+    pub fn content_bytes(&self) -> Option<&[u8]> {
+        self.header.raw_content.as_ref().map(|s| s.as_ref())
+    }
+
+    /// Return the length of the content, in bytes
+    ///
+    /// This is the length declared in the header, for ex. 20 for an OCTET STRING containing a
+    /// SHA-1 hash. For the indefinite length form, this is the length of the content read by the
+    /// parser, excluding the end-of-content octets (or 0 if the object was not built by a parsing
+    /// function).
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber;
+    /// let (_, obj) = parse_ber(&[0x30, 0x80, 0x02, 0x01, 0x01, 0x00, 0x00]).expect("parsing failed");
+    /// assert_eq!(obj.content_len(), 3);
+    /// ```
+    pub fn content_len(&self) -> usize {
+        match self.header.len {
+            BerSize::Definite(l) => l,
+            BerSize::Indefinite => self
+                .header
+                .raw_content
+                .as_ref()
+                .map_or(0, |s| s.len().saturating_sub(2)),
         }
     }
 
     /// Set a tag for the BER object
     pub fn set_raw_tag(self, raw_tag: Option<&'a [u8]>) -> BerObject {
         let header = BerObjectHeader {
-            raw_tag,
+            raw_tag: raw_tag.map(Cow::Borrowed),
             ..self.header
         };
         BerObject { header, ..self }
     }
 
+    /// Convert the object to an owned value, which does not borrow the parsed input
+    ///
+    /// The borrowed content (including the raw encodings of the header and content) is copied,
+    /// recursively for constructed objects. This can be used to return an object parsed from a
+    /// temporary buffer.
+    ///
+    /// ```rust
+    /// use der_parser::ber::{parse_ber, BerObject};
+    ///
+    /// fn parse_owned() -> BerObject<'static> {
+    ///     let bytes = vec![0x30, 0x03, 0x02, 0x01, 0x05];
+    ///     let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    ///     obj.into_owned()
+    /// }
+    ///
+    /// let obj = parse_owned();
+    /// assert_eq!(obj[0].as_u32(), Ok(5));
+    /// ```
+    pub fn into_owned(self) -> BerObject<'static> {
+        BerObject {
+            header: self.header.into_owned(),
+            content: self.content.into_owned(),
+        }
+    }
+
     /// Build a DER sequence object from a vector of DER objects
     pub fn from_seq(l: Vec<BerObject>) -> BerObject {
         BerObject::from_obj(BerObjectContent::Sequence(l))
@@ -366,6 +649,8 @@ impl<'a> BerObject<'a> {
     /// Attempt to read integer value from DER object.
     /// This can fail if the object is not an integer, or if it is too large.
     ///
+    /// For ENUMERATED objects, `BerError::BerValueError` is returned if the value is negative.
+    ///
     /// ```rust
     /// # use der_parser::ber::BerObject;
     /// let der_int  = BerObject::from_int_slice(b"\x01\x00\x01");
@@ -384,7 +669,8 @@ impl<'a> BerObject<'a> {
     /// ```rust
     /// # extern crate der_parser;
     /// # use der_parser::ber::{BerObject,BerObjectContent};
-    /// let der_int  = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+    /// # use std::borrow::Cow;
+    /// let der_int  = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
     /// assert_eq!(
     ///     der_int.as_u32(),
     ///     Ok(0x10001)
@@ -394,12 +680,84 @@ impl<'a> BerObject<'a> {
         self.content.as_u32()
     }
 
-    /// Attempt to read integer value from DER object.
+    /// Attempt to read a signed integer value from DER object.
+    /// This can fail if the object is not an integer, or if it is too large.
+    ///
+    /// The content is decoded as a two's complement, big-endian value.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::BerObject;
+    /// let der_int  = BerObject::from_int_slice(b"\xfe\xff");
+    /// assert_eq!(der_int.as_i64(), Ok(-257));
+    /// ```
+    pub fn as_i64(&self) -> Result<i64, BerError> {
+        self.content.as_i64()
+    }
+
+    /// Attempt to read a signed integer value from DER object.
+    /// This can fail if the object is not an integer, or if it is too large.
+    ///
+    /// The content is decoded as a two's complement, big-endian value.
+    pub fn as_i128(&self) -> Result<i128, BerError> {
+        self.content.as_i128()
+    }
+
+    /// Attempt to read a real value from DER object.
+    /// This can fail if the object is not a real, or an integer that cannot be represented exactly
+    /// as a `f64`.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::{BerObject,BerObjectContent};
+    /// let der_int  = BerObject::from_int_slice(b"\x01\x00\x01");
+    /// assert_eq!(der_int.as_f64(), Ok(65537.0));
+    /// let der_real = BerObject::from_obj(BerObjectContent::Real(0.15625));
+    /// assert_eq!(der_real.as_f64(), Ok(0.15625));
+    /// ```
+    pub fn as_f64(&self) -> Result<f64, BerError> {
+        self.content.as_f64()
+    }
+
+    /// Attempt to read a boolean value from DER object.
     /// This can fail if the object is not a boolean.
+    ///
+    /// Following BER rules, any non-zero value is `true`. See
+    /// [`as_bool_strict`](#method.as_bool_strict) to check the encoding.
     pub fn as_bool(&self) -> Result<bool, BerError> {
         self.content.as_bool()
     }
 
+    /// Attempt to read a boolean value, encoded as `0x00` (false) or `0xff` (true).
+    /// This can fail if the object is not a boolean.
+    ///
+    /// Other non-zero values (valid in BER, but not in DER) are rejected with
+    /// `BerError::DerConstraintFailed`. The encoding can only be checked if the object was built by
+    /// a parsing function: otherwise, this is the same as [`as_bool`](#method.as_bool).
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_bool;
+    /// # use der_parser::error::BerError;
+    /// let (_, obj) = parse_ber_bool(&[0x01, 0x01, 0xff]).expect("parsing failed");
+    /// assert_eq!(obj.as_bool_strict(), Ok(true));
+    ///
+    /// let (_, obj) = parse_ber_bool(&[0x01, 0x01, 0x01]).expect("parsing failed");
+    /// assert_eq!(obj.as_bool(), Ok(true));
+    /// assert_eq!(obj.as_bool_strict(), Err(BerError::DerConstraintFailed));
+    /// ```
+    pub fn as_bool_strict(&self) -> Result<bool, BerError> {
+        let b = self.content.as_bool()?;
+        match self.content_bytes() {
+            Some(&[0x00]) | Some(&[0xff]) | None => Ok(b),
+            Some(_) => Err(BerError::DerConstraintFailed),
+        }
+    }
+
+    /// Test if the object is a NULL value.
+    ///
+    /// This is common for the `parameters` field of an `AlgorithmIdentifier`.
+    pub fn is_null(&self) -> bool {
+        self.content == BerObjectContent::Null
+    }
+
     /// Attempt to read an OID value from DER object.
     /// This can fail if the object is not an OID.
     pub fn as_oid(&self) -> Result<&Oid<'a>, BerError> {
@@ -412,6 +770,21 @@ impl<'a> BerObject<'a> {
         self.content.as_oid_val()
     }
 
+    /// Attempt to read an OID value from DER object, and return it as a string
+    ///
+    /// The string contains the arcs separated by dots, for ex. "1.2.840.113549.1.1.5" (see
+    /// `Oid::to_id_string`). This can fail if the object is not an OID.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::BerObject;
+    /// # use der_parser::oid;
+    /// let obj = BerObject::from_oid(oid!(1.2.840.113549.1.1.5));
+    /// assert_eq!(obj.as_oid_str().as_ref().map(|s| s.as_str()), Ok("1.2.840.113549.1.1.5"));
+    /// ```
+    pub fn as_oid_str(&self) -> Result<String, BerError> {
+        self.content.as_oid_str()
+    }
+
     /// Attempt to get a reference on the content from an optional object.
     /// This can fail if the object is not optional.
     pub fn as_optional(&'a self) -> Result<Option<&'_ BerObject<'a>>, BerError> {
@@ -429,6 +802,9 @@ impl<'a> BerObject<'a> {
     ///
     /// Note that this function returns a reference to the BitString. To get an owned value,
     /// use [`as_bitstring`](struct.BerObject.html#method.as_bitstring)
+    ///
+    /// Constructed BitString objects (BER only) do not store a `BitStringObject`, so this function
+    /// will fail. Use [`as_bitstring`](struct.BerObject.html#method.as_bitstring) instead.
     pub fn as_bitstring_ref(&self) -> Result<&BitStringObject, BerError> {
         self.content.as_bitstring_ref()
     }
@@ -439,6 +815,71 @@ impl<'a> BerObject<'a> {
         self.content.as_bitstring()
     }
 
+    /// Attempt to get a reference on the components of an EXTERNAL object.
+    /// This can fail if the object is not an EXTERNAL.
+    pub fn as_external(&self) -> Result<&BerExternal<'a>, BerError> {
+        self.content.as_external()
+    }
+
+    /// Attempt to get a reference on the components of an EMBEDDED PDV object.
+    /// This can fail if the object is not an EMBEDDED PDV.
+    pub fn as_embedded_pdv(&self) -> Result<&BerEmbeddedPdv<'a>, BerError> {
+        self.content.as_embedded_pdv()
+    }
+
+    /// Return an iterator over the indices of the bits set in a BitString object.
+    /// This can fail if the object is not a BitString.
+    ///
+    /// Bits are numbered as in ASN.1 (bit 0 is the most significant bit of the first byte), and
+    /// the trailing unused bits are excluded. This is useful to interpret named bit strings, like
+    /// the KeyUsage X.509 extension.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_bitstring;
+    /// // digitalSignature (0) and keyCertSign (5), last bit is unused
+    /// let bytes = &[0x03, 0x02, 0x01, 0x85];
+    /// let (_, obj) = parse_ber_bitstring(bytes).expect("parsing failed");
+    /// let bits: Vec<_> = obj.iter_set_bits().expect("not a bitstring").collect();
+    /// assert_eq!(bits, vec![0, 5]);
+    /// ```
+    pub fn iter_set_bits(&self) -> Result<impl Iterator<Item = usize> + '_, BerError> {
+        let b = self.content.as_bitstring()?;
+        Ok((0..b.bit_len()).filter(move |&n| b.is_set(n)))
+    }
+
+    /// Attempt to get the content of a BitString object as a slice of bits.
+    /// This can fail if the object is not a BitString.
+    ///
+    /// Bits are numbered as in ASN.1 (bit 0 is the most significant bit of the first byte), and
+    /// the trailing unused bits are excluded.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_bitstring;
+    /// // digitalSignature (0) and keyCertSign (5), last bit is unused
+    /// let bytes = &[0x03, 0x02, 0x01, 0x85];
+    /// let (_, obj) = parse_ber_bitstring(bytes).expect("parsing failed");
+    /// let bits = obj.as_bitslice().expect("not a bitstring");
+    /// assert_eq!(bits.len(), 7);
+    /// assert!(bits[0] && bits[5]);
+    /// assert_eq!(bits.count_ones(), 2);
+    /// ```
+    ///
+    /// *This function is only available if the `bitvec` feature is enabled.*
+    #[cfg(feature = "bitvec")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitvec")))]
+    pub fn as_bitslice(&self) -> Result<&BitSlice<u8, Msb0>, BerError> {
+        let (ignored_bits, data) = match self.content {
+            BerObjectContent::BitString(ref b) => (b.unused_bits(), b.data.as_ref()),
+            BerObjectContent::BitStringConstructed(ignored_bits, ref data) => {
+                (ignored_bits, data.as_ref())
+            }
+            _ => return Err(BerError::BerTypeError),
+        };
+        let bits = BitSlice::from_slice(data);
+        let nbits = bits.len().saturating_sub(ignored_bits as usize);
+        Ok(&bits[..nbits])
+    }
+
     /// Attempt to extract the list of objects from a DER sequence.
     /// This can fail if the object is not a sequence.
     pub fn as_sequence(&self) -> Result<&Vec<BerObject<'a>>, BerError> {
@@ -455,20 +896,265 @@ impl<'a> BerObject<'a> {
     /// This can fail if the object does not contain a type directly equivalent to a slice (e.g a
     /// sequence).
     /// This function mostly concerns string types, integers, or unknown DER objects.
-    pub fn as_slice(&self) -> Result<&'a [u8], BerError> {
+    pub fn as_slice(&self) -> Result<&[u8], BerError> {
         self.content.as_slice()
     }
 
+    /// Attempt to get the content from a DER object, as a slice or as owned bytes.
+    /// Contrary to `as_slice`, this also works for constructed OctetString and BitString
+    /// objects, for which the concatenated segments are copied.
+    pub fn as_cow_slice(&self) -> Result<Cow<'a, [u8]>, BerError> {
+        self.content.as_cow_slice()
+    }
+
+    /// Attempt to get the content of an INTEGER object as an unsigned magnitude.
+    ///
+    /// Leading zero bytes (used for sign padding) are removed, which is useful to extract
+    /// fixed-width key material such as a RSA modulus. This function does not check the sign:
+    /// negative values are returned as their two's complement representation, so the sign is
+    /// lost.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_integer;
+    /// let (_, obj) = parse_ber_integer(&[0x02, 0x03, 0x00, 0x80, 0x01]).expect("parsing failed");
+    /// assert_eq!(obj.as_slice(), Ok(&[0x00, 0x80, 0x01][..]));
+    /// assert_eq!(obj.as_raw_integer(), Ok(&[0x80, 0x01][..]));
+    /// ```
+    pub fn as_raw_integer(&self) -> Result<&[u8], BerError> {
+        self.content.as_raw_integer()
+    }
+
+    /// Compare the content of the object to `other`, in constant time.
+    ///
+    /// This can be used to compare secret values (for ex. a MAC tag stored in an OCTET STRING)
+    /// without leaking timing information. The content is read as for `as_slice`: if it cannot be
+    /// read as a slice, `false` is returned. Only the values are protected: the comparison returns
+    /// early if the lengths are different.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_octetstring;
+    /// let (_, obj) = parse_ber_octetstring(&[0x04, 0x03, 0x01, 0x02, 0x03]).expect("parsing failed");
+    /// assert!(obj.ct_eq_content(&[0x01, 0x02, 0x03]));
+    /// assert!(!obj.ct_eq_content(&[0x01, 0x02, 0x04]));
+    /// ```
+    ///
+    /// *This function is only available if the `subtle` feature is enabled.*
+    #[cfg(feature = "subtle")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "subtle")))]
+    pub fn ct_eq_content(&self, other: &[u8]) -> bool {
+        use subtle::ConstantTimeEq;
+        match self.content.as_cow_slice() {
+            Ok(s) => s.ct_eq(other).into(),
+            Err(_) => false,
+        }
+    }
+
+    /// Attempt to parse the content of an OCTET STRING object as an encapsulated DER object.
+    /// This can fail if the object is not an OCTET STRING, if the content is not a valid DER
+    /// object, or if data remains after the encapsulated object.
+    ///
+    /// This is common in X.509 extensions, where `extnValue` contains the DER encoding of the
+    /// extension value.
+    ///
+    /// The encapsulated object borrows the same input as this object, so it can outlive it. If
+    /// the content is not borrowed (for ex. a constructed OCTET STRING), the encapsulated object
+    /// is copied using `into_owned`.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_octetstring;
+    /// let bytes = &[0x04, 0x05, 0x30, 0x03, 0x01, 0x01, 0xff];
+    /// let (_, obj) = parse_ber_octetstring(bytes).expect("parsing failed");
+    /// let inner = obj.as_nested_der().expect("invalid nested object");
+    /// assert_eq!(inner[0].as_bool(), Ok(true));
+    /// ```
+    pub fn as_nested_der(&self) -> Result<BerObject<'a>, BerError> {
+        self.parse_nested(parse_der)
+    }
+
+    /// Attempt to parse the content of an OCTET STRING object as an encapsulated BER object.
+    /// This can fail if the object is not an OCTET STRING, if the content is not a valid BER
+    /// object, or if data remains after the encapsulated object.
+    ///
+    /// See [`as_nested_der`](#method.as_nested_der) for the lifetime of the returned object.
+    pub fn as_nested_ber(&self) -> Result<BerObject<'a>, BerError> {
+        self.parse_nested(parse_ber)
+    }
+
+    fn parse_nested<F>(&self, f: F) -> Result<BerObject<'a>, BerError>
+    where
+        F: for<'b> Fn(&'b [u8]) -> BerResult<'b>,
+    {
+        match self.content {
+            BerObjectContent::OctetString(Cow::Borrowed(s)) => parse_nested_content(s, f),
+            BerObjectContent::OctetString(Cow::Owned(ref v))
+            | BerObjectContent::OctetStringConstructed(ref v) => {
+                parse_nested_content(v, f).map(BerObject::into_owned)
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Compare the values of two objects, ignoring differences in their encodings
+    ///
+    /// Unlike `PartialEq` (which compares the objects as decoded), this function compares the
+    /// semantic values: for ex. an INTEGER with a redundant leading zero byte (allowed in BER) is
+    /// equal to its minimal encoding, a constructed OCTET STRING is equal to the primitive OCTET
+    /// STRING with the same bytes, and elements of a SET are compared regardless of their order.
+    /// The length form (definite or indefinite), and the value of the unused bits of a BIT STRING,
+    /// are ignored.
+    ///
+    /// This is useful to check that a re-encoded (for ex. DER) object is equivalent to the
+    /// original.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_integer;
+    /// let (_, a) = parse_ber_integer(&[0x02, 0x02, 0x00, 0x01]).expect("parsing failed");
+    /// let (_, b) = parse_ber_integer(&[0x02, 0x01, 0x01]).expect("parsing failed");
+    /// assert_ne!(a, b);
+    /// assert!(a.value_eq(&b));
+    /// ```
+    pub fn value_eq(&self, other: &BerObject) -> bool {
+        self.header.class == other.header.class
+            && self.header.tag == other.header.tag
+            && self.content.value_eq(&other.content)
+    }
+
     /// Attempt to get the content from a DER object, as a str.
     /// This can fail if the object does not contain a string type.
     ///
     /// Only NumericString, VisibleString, UTCTime, GeneralizedTime,
-    /// PrintableString, UTF8String and IA5String are considered here. Other
+    /// PrintableString, UTF8String and IA5String are considered here, as well as T61String,
+    /// GraphicString, ObjectDescriptor and GeneralString if they contain only ASCII characters
+    /// (GraphicString and ObjectDescriptor cannot contain control characters). Other
     /// string types can be read using `as_slice`.
-    pub fn as_str(&self) -> Result<&'a str, BerError> {
+    ///
+    /// This can also fail with `StringInvalidCharset` if an IA5String is not 7-bit ASCII, if a
+    /// VisibleString contains control characters, or if a NumericString or PrintableString
+    /// contains characters outside of its charset.
+    pub fn as_str(&self) -> Result<&str, BerError> {
         self.content.as_str()
     }
 
+    /// Get the content of an UTF8String object as a `str`, without copying it
+    ///
+    /// The content is validated as UTF-8 when parsing, so this only fails (with `BerTypeError`)
+    /// if the object is not an UTF8String.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_utf8string;
+    /// let (_, obj) = parse_ber_utf8string(b"\x0c\x03abc").expect("parsing failed");
+    /// assert_eq!(obj.as_utf8_str(), Ok("abc"));
+    /// ```
+    pub fn as_utf8_str(&self) -> Result<&str, BerError> {
+        self.content.as_utf8_str()
+    }
+
+    /// Get the content of a string object as text, copying it only if it must be decoded
+    ///
+    /// BmpString, UniversalString and T61String objects (if not ASCII) are decoded to an owned
+    /// `String`. Other string types are borrowed, using the same rules as `as_str`.
+    pub fn as_cow_str(&self) -> Result<Cow<'a, str>, BerError> {
+        self.content.as_cow_str()
+    }
+
+    /// Attempt to decode the content of an UTCTime object.
+    /// This can fail if the object is not an UTCTime, or if the value is not a valid time.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::{parse_ber_utctime, ASN1TimeZone};
+    /// let bytes = b"\x17\x0d991231235959Z";
+    /// let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    /// let t = obj.as_utctime().expect("invalid time");
+    /// assert_eq!(t.year, 1999);
+    /// assert_eq!(t.second, Some(59));
+    /// assert_eq!(t.tz, ASN1TimeZone::Z);
+    /// ```
+    pub fn as_utctime(&self) -> Result<UtcTime, BerError> {
+        self.content.as_utctime()
+    }
+
+    /// Attempt to decode the content of a GeneralizedTime object.
+    /// This can fail if the object is not a GeneralizedTime, or if the value is not a valid time.
+    ///
+    /// Fractional seconds are returned as an integer numerator and a number of digits, so no
+    /// precision is lost.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::{parse_ber_generalizedtime, ASN1TimeZone};
+    /// let bytes = b"\x18\x1320201231235959.125Z";
+    /// let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    /// let t = obj.as_generalizedtime().expect("invalid time");
+    /// assert_eq!(t.year, 2020);
+    /// assert_eq!(t.second, Some(59));
+    /// assert_eq!((t.fraction, t.fraction_digits), (125, 3));
+    /// assert_eq!(t.tz, ASN1TimeZone::Z);
+    /// ```
+    pub fn as_generalizedtime(&self) -> Result<GeneralizedTime, BerError> {
+        self.content.as_generalizedtime()
+    }
+
+    /// Attempt to decode the content of a BmpString object to a `String`.
+    /// This can fail if the object is not a BmpString, or if the content is not valid UCS-2
+    /// (odd length, or invalid surrogates).
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_bmpstring;
+    /// let bytes = b"\x1e\x0a\x00H\x00e\x00l\x00l\x00o";
+    /// let (_, obj) = parse_ber_bmpstring(bytes).expect("parsing failed");
+    /// assert_eq!(obj.as_bmpstring(), Ok("Hello".to_string()));
+    /// ```
+    pub fn as_bmpstring(&self) -> Result<String, BerError> {
+        self.content.as_bmpstring()
+    }
+
+    /// Attempt to decode the content of a T61String (TeletexString) object to a `String`.
+    /// This can fail if the object is not a T61String.
+    ///
+    /// The T61 charset is not fully supported: as commonly done, bytes are decoded as Latin-1.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_t61string;
+    /// let bytes = b"\x14\x04Caf\xe9";
+    /// let (_, obj) = parse_ber_t61string(bytes).expect("parsing failed");
+    /// assert_eq!(obj.as_t61string(), Ok("Caf\u{e9}".to_string()));
+    /// ```
+    pub fn as_t61string(&self) -> Result<String, BerError> {
+        self.content.as_t61string()
+    }
+
+    /// Attempt to decode the content of a UniversalString object to a `String`.
+    /// This can fail if the object is not a UniversalString, or if the content is not valid UCS-4
+    /// (length not a multiple of 4, or invalid code points).
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_universalstring;
+    /// let bytes = b"\x1c\x08\x00\x00\x00H\x00\x00\x00i";
+    /// let (_, obj) = parse_ber_universalstring(bytes).expect("parsing failed");
+    /// assert_eq!(obj.as_universalstring(), Ok("Hi".to_string()));
+    /// ```
+    pub fn as_universalstring(&self) -> Result<String, BerError> {
+        self.content.as_universalstring()
+    }
+
+    /// Return the tag number, as declared in the header
+    ///
+    /// The class and the constructed bit are not included, so the value must be interpreted
+    /// according to the class (for ex. using [`is_contextspecific`](#method.is_contextspecific)).
+    /// High tag numbers (encoded on several bytes) are supported.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber;
+    /// // [APPLICATION 1000] INTEGER (IMPLICIT)
+    /// let bytes = &[0x5f, 0x87, 0x68, 0x01, 0x05];
+    /// let (_, obj) = parse_ber(bytes).expect("parsing failed");
+    /// assert!(obj.is_application());
+    /// assert_eq!(obj.tag(), 1000);
+    /// ```
+    #[inline]
+    pub fn tag(&self) -> u32 {
+        self.header.tag.0
+    }
+
     /// Test if object class is Universal
     pub fn is_universal(&self) -> bool {
         self.header.class == BerClass::Universal
@@ -491,11 +1177,35 @@ impl<'a> BerObject<'a> {
         self.header.structured == 0
     }
     /// Test if object is constructed
+    ///
+    /// Combined with the type, this can be used to detect encodings that are valid in BER but not
+    /// in DER, for ex. a constructed OCTET STRING.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::{parse_ber, BerTag};
+    /// let bytes = &[0x24, 0x80, 0x04, 0x01, 0xaa, 0x00, 0x00];
+    /// let (_, obj) = parse_ber(bytes).expect("parsing failed");
+    /// assert_eq!(obj.header.tag, BerTag::OctetString);
+    /// assert!(obj.is_constructed());
+    /// ```
     pub fn is_constructed(&self) -> bool {
         self.header.structured == 1
     }
 }
 
+/// Parse `data` as a single encapsulated object
+fn parse_nested_content<'b, F>(data: &'b [u8], f: F) -> Result<BerObject<'b>, BerError>
+where
+    F: Fn(&'b [u8]) -> BerResult<'b>,
+{
+    match f(data) {
+        Ok((rem, _)) if !rem.is_empty() => Err(BerError::InvalidLength),
+        Ok((_, obj)) => Ok(obj),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(e),
+        Err(nom::Err::Incomplete(_)) => Err(BerError::ObjectTooShort),
+    }
+}
+
 /// Build a DER object from an OID.
 impl<'a> From<Oid<'a>> for BerObject<'a> {
     fn from(oid: Oid<'a>) -> BerObject<'a> {
@@ -510,6 +1220,48 @@ impl<'a> From<BerObjectContent<'a>> for BerObject<'a> {
     }
 }
 
+impl<'a, 'b> TryFrom<&'b BerObject<'a>> for u64 {
+    type Error = BerError;
+
+    fn try_from(obj: &'b BerObject<'a>) -> Result<u64, BerError> {
+        obj.as_u64()
+    }
+}
+
+impl<'a, 'b> TryFrom<&'b BerObject<'a>> for u32 {
+    type Error = BerError;
+
+    fn try_from(obj: &'b BerObject<'a>) -> Result<u32, BerError> {
+        obj.as_u32()
+    }
+}
+
+impl<'a, 'b> TryFrom<&'b BerObject<'a>> for i64 {
+    type Error = BerError;
+
+    fn try_from(obj: &'b BerObject<'a>) -> Result<i64, BerError> {
+        obj.as_i64()
+    }
+}
+
+// Smaller types are converted from the larger type with the same signedness
+macro_rules! impl_tryfrom_berobject {
+    ($t:ty, $from:ty) => {
+        impl<'a, 'b> TryFrom<&'b BerObject<'a>> for $t {
+            type Error = BerError;
+
+            fn try_from(obj: &'b BerObject<'a>) -> Result<$t, BerError> {
+                let x = <$from>::try_from(obj)?;
+                <$t>::try_from(x).or(Err(BerError::IntegerTooLarge))
+            }
+        }
+    };
+}
+
+impl_tryfrom_berobject!(u16, u32);
+impl_tryfrom_berobject!(u8, u32);
+impl_tryfrom_berobject!(i32, i64);
+
 /// Replacement function for Option.xor (>= 1.37)
 #[inline]
 pub(crate) fn xor_option<T>(opta: Option<T>, optb: Option<T>) -> Option<T> {
@@ -535,7 +1287,7 @@ impl<'a> PartialEq<BerObjectHeader<'a>> for BerObjectHeader<'a> {
             }
             && {
                 // it tag is present for both, compare it
-                if xor_option(self.raw_tag, other.raw_tag).is_none() {
+                if xor_option(self.raw_tag.as_ref(), other.raw_tag.as_ref()).is_none() {
                     self.raw_tag == other.raw_tag
                 } else {
                     true
@@ -545,13 +1297,146 @@ impl<'a> PartialEq<BerObjectHeader<'a>> for BerObjectHeader<'a> {
 }
 
 impl<'a> BerObjectContent<'a> {
+    /// Convert the content to an owned value, copying the borrowed data (see
+    /// [`BerObject::into_owned`](struct.BerObject.html#method.into_owned))
+    #[rustfmt::skip]
+    pub fn into_owned(self) -> BerObjectContent<'static> {
+        match self {
+            BerObjectContent::EndOfContent         => BerObjectContent::EndOfContent,
+            BerObjectContent::Boolean(b)           => BerObjectContent::Boolean(b),
+            BerObjectContent::Integer(s)           => BerObjectContent::Integer(into_owned_cow(s)),
+            BerObjectContent::BitString(b)         => BerObjectContent::BitString(b.into_owned()),
+            BerObjectContent::BitStringConstructed(u, v) => BerObjectContent::BitStringConstructed(u, v),
+            BerObjectContent::OctetString(s)       => BerObjectContent::OctetString(into_owned_cow(s)),
+            BerObjectContent::OctetStringConstructed(v) => BerObjectContent::OctetStringConstructed(v),
+            BerObjectContent::Null                 => BerObjectContent::Null,
+            BerObjectContent::Real(f)              => BerObjectContent::Real(f),
+            BerObjectContent::Enum(i)              => BerObjectContent::Enum(i),
+            BerObjectContent::OID(o)               => BerObjectContent::OID(o.to_owned()),
+            BerObjectContent::RelativeOID(o)       => BerObjectContent::RelativeOID(o.to_owned()),
+            BerObjectContent::NumericString(s)     => BerObjectContent::NumericString(into_owned_cow(s)),
+            BerObjectContent::VisibleString(s)     => BerObjectContent::VisibleString(into_owned_cow(s)),
+            BerObjectContent::PrintableString(s)   => BerObjectContent::PrintableString(into_owned_cow(s)),
+            BerObjectContent::IA5String(s)         => BerObjectContent::IA5String(into_owned_cow(s)),
+            BerObjectContent::UTF8String(s)        => BerObjectContent::UTF8String(into_owned_cow(s)),
+            BerObjectContent::T61String(s)         => BerObjectContent::T61String(into_owned_cow(s)),
+            BerObjectContent::VideotexString(s)    => BerObjectContent::VideotexString(into_owned_cow(s)),
+            BerObjectContent::BmpString(s)         => BerObjectContent::BmpString(into_owned_cow(s)),
+            BerObjectContent::UniversalString(s)   => BerObjectContent::UniversalString(into_owned_cow(s)),
+            BerObjectContent::Sequence(v)          => BerObjectContent::Sequence(v.into_iter().map(BerObject::into_owned).collect()),
+            BerObjectContent::Set(v)               => BerObjectContent::Set(v.into_iter().map(BerObject::into_owned).collect()),
+            BerObjectContent::UTCTime(s)           => BerObjectContent::UTCTime(into_owned_cow(s)),
+            BerObjectContent::GeneralizedTime(s)   => BerObjectContent::GeneralizedTime(into_owned_cow(s)),
+            BerObjectContent::ObjectDescriptor(s)  => BerObjectContent::ObjectDescriptor(into_owned_cow(s)),
+            BerObjectContent::GraphicString(s)     => BerObjectContent::GraphicString(into_owned_cow(s)),
+            BerObjectContent::GeneralString(s)     => BerObjectContent::GeneralString(into_owned_cow(s)),
+            BerObjectContent::External(e)          => BerObjectContent::External(e.into_owned()),
+            BerObjectContent::EmbeddedPdv(p)       => BerObjectContent::EmbeddedPdv(p.into_owned()),
+            BerObjectContent::Optional(o)          => BerObjectContent::Optional(o.map(|obj| Box::new(obj.into_owned()))),
+            BerObjectContent::Tagged(c, t, obj)    => BerObjectContent::Tagged(c, t, Box::new(obj.into_owned())),
+            BerObjectContent::Unknown(t, s)        => BerObjectContent::Unknown(t, into_owned_cow(s)),
+        }
+    }
+
+    fn value_eq(&self, other: &BerObjectContent) -> bool {
+        // remove redundant sign bytes
+        fn trim_integer(mut s: &[u8]) -> &[u8] {
+            while s.len() > 1
+                && ((s[0] == 0 && s[1] & 0x80 == 0) || (s[0] == 0xff && s[1] & 0x80 != 0))
+            {
+                s = &s[1..];
+            }
+            s
+        }
+        // the unused bits are not part of the value
+        fn bitstring_eq(ignored_a: u8, a: &[u8], ignored_b: u8, b: &[u8]) -> bool {
+            if ignored_a != ignored_b || a.len() != b.len() {
+                return false;
+            }
+            match (a.split_last(), b.split_last()) {
+                (Some((last_a, a)), Some((last_b, b))) => {
+                    let mask = 0xffu8.checked_shl(u32::from(ignored_a)).unwrap_or(0);
+                    a == b && last_a & mask == last_b & mask
+                }
+                _ => true,
+            }
+        }
+        fn bitstring_data<'b>(c: &'b BerObjectContent) -> Option<(u8, &'b [u8])> {
+            match *c {
+                BerObjectContent::BitString(ref b) => Some((b.unused_bits(), &b.data)),
+                BerObjectContent::BitStringConstructed(ignored, ref v) => Some((ignored, v)),
+                _ => None,
+            }
+        }
+        fn octetstring_data<'b>(c: &'b BerObjectContent) -> Option<&'b [u8]> {
+            match *c {
+                BerObjectContent::OctetString(ref s) => Some(s),
+                BerObjectContent::OctetStringConstructed(ref v) => Some(v),
+                _ => None,
+            }
+        }
+        match (self, other) {
+            (BerObjectContent::Integer(a), BerObjectContent::Integer(b)) => {
+                trim_integer(a) == trim_integer(b)
+            }
+            (BerObjectContent::Sequence(a), BerObjectContent::Sequence(b)) => {
+                a.len() == b.len() && a.iter().zip(b.iter()).all(|(x, y)| x.value_eq(y))
+            }
+            (BerObjectContent::Set(a), BerObjectContent::Set(b)) => {
+                if a.len() != b.len() {
+                    return false;
+                }
+                // each element must match a distinct element of the other set
+                let mut used = vec![false; b.len()];
+                a.iter().all(|x| {
+                    match (0..b.len()).find(|&idx| !used[idx] && x.value_eq(&b[idx])) {
+                        Some(idx) => {
+                            used[idx] = true;
+                            true
+                        }
+                        None => false,
+                    }
+                })
+            }
+            (BerObjectContent::Optional(a), BerObjectContent::Optional(b)) => match (a, b) {
+                (Some(x), Some(y)) => x.value_eq(y),
+                (None, None) => true,
+                _ => false,
+            },
+            (BerObjectContent::Tagged(c1, t1, a), BerObjectContent::Tagged(c2, t2, b)) => {
+                c1 == c2 && t1 == t2 && a.value_eq(b)
+            }
+            _ => {
+                if let (Some((ia, a)), Some((ib, b))) =
+                    (bitstring_data(self), bitstring_data(other))
+                {
+                    return bitstring_eq(ia, a, ib, b);
+                }
+                if let (Some(a), Some(b)) = (octetstring_data(self), octetstring_data(other)) {
+                    return a == b;
+                }
+                self == other
+            }
+        }
+    }
+
     pub fn as_u64(&self) -> Result<u64, BerError> {
         match self {
             BerObjectContent::Integer(i) => bytes_to_u64(i),
-            BerObjectContent::BitString(ignored_bits, data) => {
-                bitstring_to_u64(*ignored_bits as usize, data)
+            BerObjectContent::BitString(data) => {
+                bitstring_to_u64(data.unused_bits() as usize, data)
+            }
+            BerObjectContent::BitStringConstructed(ignored_bits, data) => bitstring_to_u64(
+                *ignored_bits as usize,
+                &BitStringObject::new(*ignored_bits, data)?,
+            ),
+            BerObjectContent::Enum(i) => {
+                if (*i as i64) < 0 {
+                    Err(BerError::BerValueError)
+                } else {
+                    Ok(*i)
+                }
             }
-            BerObjectContent::Enum(i) => Ok(*i as u64),
             _ => Err(BerError::BerTypeError),
         }
     }
@@ -559,28 +1444,60 @@ impl<'a> BerObjectContent<'a> {
     pub fn as_u32(&self) -> Result<u32, BerError> {
         match self {
             BerObjectContent::Integer(i) => bytes_to_u64(i).and_then(|x| {
-                if x > u64::from(std::u32::MAX) {
+                if x > u64::from(core::u32::MAX) {
                     Err(BerError::IntegerTooLarge)
                 } else {
                     Ok(x as u32)
                 }
             }),
-            BerObjectContent::BitString(ignored_bits, data) => {
-                bitstring_to_u64(*ignored_bits as usize, data).and_then(|x| {
-                    if x > u64::from(std::u32::MAX) {
+            BerObjectContent::BitString(_) | BerObjectContent::BitStringConstructed(_, _) => {
+                self.as_u64().and_then(|x| {
+                    if x > u64::from(core::u32::MAX) {
                         Err(BerError::IntegerTooLarge)
                     } else {
                         Ok(x as u32)
                     }
                 })
             }
-            BerObjectContent::Enum(i) => {
-                if *i > u64::from(std::u32::MAX) {
+            BerObjectContent::Enum(_) => self.as_u64().and_then(|x| {
+                if x > u64::from(core::u32::MAX) {
                     Err(BerError::IntegerTooLarge)
                 } else {
-                    Ok(*i as u32)
+                    Ok(x as u32)
                 }
-            }
+            }),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_i64(&self) -> Result<i64, BerError> {
+        match *self {
+            BerObjectContent::Integer(ref i) => bytes_to_i64(i),
+            BerObjectContent::Enum(i) => Ok(i as i64),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_i128(&self) -> Result<i128, BerError> {
+        match *self {
+            BerObjectContent::Integer(ref i) => bytes_to_i128(i),
+            BerObjectContent::Enum(i) => Ok(i128::from(i as i64)),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_f64(&self) -> Result<f64, BerError> {
+        match *self {
+            BerObjectContent::Real(f) => Ok(f),
+            BerObjectContent::Integer(ref i) => bytes_to_i128(i).and_then(|x| {
+                // f64 has a 53 bits mantissa, values with more significant bits would be rounded
+                let f = x as f64;
+                if f as i128 != x {
+                    Err(BerError::IntegerTooLarge)
+                } else {
+                    Ok(f)
+                }
+            }),
             _ => Err(BerError::BerTypeError),
         }
     }
@@ -604,6 +1521,10 @@ impl<'a> BerObjectContent<'a> {
         self.as_oid().map(|o| o.clone())
     }
 
+    pub fn as_oid_str(&self) -> Result<String, BerError> {
+        self.as_oid().map(|o| o.to_id_string())
+    }
+
     pub fn as_optional(&'a self) -> Result<Option<&'_ BerObject<'a>>, BerError> {
         match *self {
             BerObjectContent::Optional(Some(ref o)) => Ok(Some(&o)),
@@ -621,14 +1542,31 @@ impl<'a> BerObjectContent<'a> {
 
     pub fn as_bitstring_ref(&self) -> Result<&BitStringObject, BerError> {
         match *self {
-            BerObjectContent::BitString(_, ref b) => Ok(b),
+            BerObjectContent::BitString(ref b) => Ok(b),
             _ => Err(BerError::BerTypeError),
         }
     }
 
     pub fn as_bitstring(&'a self) -> Result<BitStringObject<'a>, BerError> {
         match *self {
-            BerObjectContent::BitString(_, ref b) => Ok(b.to_owned()),
+            BerObjectContent::BitString(ref b) => Ok(b.to_owned()),
+            BerObjectContent::BitStringConstructed(unused_bits, ref data) => {
+                BitStringObject::new(unused_bits, data)
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_external(&self) -> Result<&BerExternal<'a>, BerError> {
+        match *self {
+            BerObjectContent::External(ref e) => Ok(e),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_embedded_pdv(&self) -> Result<&BerEmbeddedPdv<'a>, BerError> {
+        match *self {
+            BerObjectContent::EmbeddedPdv(ref p) => Ok(p),
             _ => Err(BerError::BerTypeError),
         }
     }
@@ -647,54 +1585,235 @@ impl<'a> BerObjectContent<'a> {
         }
     }
 
+    pub fn as_utctime(&self) -> Result<UtcTime, BerError> {
+        match *self {
+            BerObjectContent::UTCTime(ref s) => decode_utctime(s.as_bytes()),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_generalizedtime(&self) -> Result<GeneralizedTime, BerError> {
+        match *self {
+            BerObjectContent::GeneralizedTime(ref s) => decode_generalizedtime(s.as_bytes()),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
     #[rustfmt::skip]
-    pub fn as_slice(&self) -> Result<&'a [u8],BerError> {
+    pub fn as_slice(&self) -> Result<&[u8],BerError> {
         match *self {
-            BerObjectContent::NumericString(s) |
-            BerObjectContent::GeneralizedTime(s) |
-            BerObjectContent::UTCTime(s) |
-            BerObjectContent::VisibleString(s) |
-            BerObjectContent::PrintableString(s) |
-            BerObjectContent::UTF8String(s) |
-            BerObjectContent::IA5String(s) => Ok(s.as_ref()),
-            BerObjectContent::Integer(s) |
-            BerObjectContent::BitString(_,BitStringObject{data:s}) |
-            BerObjectContent::OctetString(s) |
-            BerObjectContent::T61String(s) |
-            BerObjectContent::VideotexString(s) |
-            BerObjectContent::BmpString(s) |
-            BerObjectContent::UniversalString(s) |
-            BerObjectContent::ObjectDescriptor(s) |
-            BerObjectContent::GraphicString(s) |
-            BerObjectContent::GeneralString(s) |
-            BerObjectContent::Unknown(_,s) => Ok(s),
+            BerObjectContent::NumericString(ref s) |
+            BerObjectContent::GeneralizedTime(ref s) |
+            BerObjectContent::UTCTime(ref s) |
+            BerObjectContent::VisibleString(ref s) |
+            BerObjectContent::PrintableString(ref s) |
+            BerObjectContent::UTF8String(ref s) |
+            BerObjectContent::IA5String(ref s) => Ok(s.as_bytes()),
+            BerObjectContent::Integer(ref s) |
+            BerObjectContent::BitString(BitStringObject{data:ref s,..}) |
+            BerObjectContent::OctetString(ref s) |
+            BerObjectContent::T61String(ref s) |
+            BerObjectContent::VideotexString(ref s) |
+            BerObjectContent::BmpString(ref s) |
+            BerObjectContent::UniversalString(ref s) |
+            BerObjectContent::ObjectDescriptor(ref s) |
+            BerObjectContent::GraphicString(ref s) |
+            BerObjectContent::GeneralString(ref s) |
+            BerObjectContent::Unknown(_,ref s) => Ok(s),
             _ => Err(BerError::BerTypeError),
         }
     }
 
+    /// Get the content as a slice or as owned bytes (see `as_slice`)
+    ///
+    /// Borrowed content is not copied, so the result can outlive `self`.
     #[rustfmt::skip]
-    pub fn as_str(&self) -> Result<&'a str,BerError> {
+    pub fn as_cow_slice(&self) -> Result<Cow<'a, [u8]>, BerError> {
+        match *self {
+            BerObjectContent::OctetStringConstructed(ref v) |
+            BerObjectContent::BitStringConstructed(_, ref v) => Ok(Cow::Owned(v.clone())),
+            BerObjectContent::NumericString(ref s) |
+            BerObjectContent::GeneralizedTime(ref s) |
+            BerObjectContent::UTCTime(ref s) |
+            BerObjectContent::VisibleString(ref s) |
+            BerObjectContent::PrintableString(ref s) |
+            BerObjectContent::UTF8String(ref s) |
+            BerObjectContent::IA5String(ref s) => Ok(match s {
+                Cow::Borrowed(s) => Cow::Borrowed(s.as_bytes()),
+                Cow::Owned(s) => Cow::Owned(s.as_bytes().to_vec()),
+            }),
+            BerObjectContent::Integer(ref s) |
+            BerObjectContent::BitString(BitStringObject{data:ref s,..}) |
+            BerObjectContent::OctetString(ref s) |
+            BerObjectContent::T61String(ref s) |
+            BerObjectContent::VideotexString(ref s) |
+            BerObjectContent::BmpString(ref s) |
+            BerObjectContent::UniversalString(ref s) |
+            BerObjectContent::ObjectDescriptor(ref s) |
+            BerObjectContent::GraphicString(ref s) |
+            BerObjectContent::GeneralString(ref s) |
+            BerObjectContent::Unknown(_,ref s) => Ok(s.clone()),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    pub fn as_raw_integer(&self) -> Result<&[u8], BerError> {
+        match *self {
+            BerObjectContent::Integer(ref s) => {
+                // keep at least one byte, so zero is not an empty slice
+                let skip = s
+                    .iter()
+                    .take_while(|&&b| b == 0)
+                    .count()
+                    .min(s.len().saturating_sub(1));
+                Ok(&s[skip..])
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Get the content of a string object as a `str`
+    ///
+    /// IA5String must contain only 7-bit ASCII, VisibleString must not contain control
+    /// characters, and NumericString and PrintableString must only contain characters of their
+    /// charset, otherwise `StringInvalidCharset` is returned. These constraints are also checked
+    /// when parsing, but objects can be built manually.
+    #[rustfmt::skip]
+    pub fn as_str(&self) -> Result<&str,BerError> {
+        match *self {
+            BerObjectContent::IA5String(ref s) => {
+                if !s.is_ascii() {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                Ok(s)
+            }
+            BerObjectContent::VisibleString(ref s) => {
+                if !s.as_bytes().iter().all(is_visible_char) {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                Ok(s)
+            }
+            BerObjectContent::NumericString(ref s) => {
+                if !s.as_bytes().iter().all(is_numeric_char) {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                Ok(s)
+            }
+            BerObjectContent::PrintableString(ref s) => {
+                if !s.as_bytes().iter().all(is_printable_char) {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                Ok(s)
+            }
+            BerObjectContent::GeneralizedTime(ref s) |
+            BerObjectContent::UTCTime(ref s) |
+            BerObjectContent::UTF8String(ref s) => Ok(s),
+            // best-effort: only the ASCII subset of these charsets is supported
+            // GraphicString (and ObjectDescriptor) excludes control characters (X.680 41.4)
+            BerObjectContent::GraphicString(ref s) |
+            BerObjectContent::ObjectDescriptor(ref s) => {
+                if !s.iter().all(|&b| 0x20 <= b && b <= 0x7e) {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                core::str::from_utf8(s).or(Err(BerError::StringInvalidCharset))
+            }
+            BerObjectContent::T61String(ref s) |
+            BerObjectContent::GeneralString(ref s) => {
+                if !s.is_ascii() {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                core::str::from_utf8(s).or(Err(BerError::StringInvalidCharset))
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Decode the content of a T61String (TeletexString) to a `String`, as Latin-1
+    pub fn as_t61string(&self) -> Result<String, BerError> {
+        match *self {
+            BerObjectContent::T61String(ref s) => Ok(s.iter().map(|&b| char::from(b)).collect()),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Decode the content of a BmpString (UCS-2 big-endian) to a `String`
+    pub fn as_bmpstring(&self) -> Result<String, BerError> {
+        match *self {
+            BerObjectContent::BmpString(ref s) => {
+                if s.len() % 2 != 0 {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                let units = s.chunks(2).map(|c| u16::from(c[0]) << 8 | u16::from(c[1]));
+                core::char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .or(Err(BerError::StringInvalidCharset))
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Decode the content of a UniversalString (UCS-4 big-endian) to a `String`
+    pub fn as_universalstring(&self) -> Result<String, BerError> {
         match *self {
-            BerObjectContent::NumericString(s) |
-            BerObjectContent::GeneralizedTime(s) |
-            BerObjectContent::UTCTime(s) |
-            BerObjectContent::VisibleString(s) |
-            BerObjectContent::PrintableString(s) |
-            BerObjectContent::UTF8String(s) |
-            BerObjectContent::IA5String(s) => Ok(s),
+            BerObjectContent::UniversalString(ref s) => {
+                if s.len() % 4 != 0 {
+                    return Err(BerError::StringInvalidCharset);
+                }
+                s.chunks(4)
+                    .map(|c| {
+                        let u = u32::from(c[0]) << 24
+                            | u32::from(c[1]) << 16
+                            | u32::from(c[2]) << 8
+                            | u32::from(c[3]);
+                        core::char::from_u32(u).ok_or(BerError::StringInvalidCharset)
+                    })
+                    .collect()
+            }
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+
+    /// Get the content of an UTF8String as a `str`, without copying it
+    pub fn as_utf8_str(&self) -> Result<&str, BerError> {
+        match *self {
+            BerObjectContent::UTF8String(ref s) => Ok(s),
             _ => Err(BerError::BerTypeError),
         }
     }
 
+    /// Get the content of a string object as text, copying it only if it must be decoded
+    pub fn as_cow_str(&self) -> Result<Cow<'a, str>, BerError> {
+        match *self {
+            BerObjectContent::BmpString(_) => self.as_bmpstring().map(Cow::Owned),
+            BerObjectContent::UniversalString(_) => self.as_universalstring().map(Cow::Owned),
+            BerObjectContent::T61String(ref s) if !s.is_ascii() => {
+                self.as_t61string().map(Cow::Owned)
+            }
+            _ => {
+                let s = self.as_str()?;
+                // keep the borrow of the input, if any
+                match self.as_cow_slice()? {
+                    Cow::Borrowed(b) => core::str::from_utf8(b)
+                        .map(Cow::Borrowed)
+                        .or(Err(BerError::StringInvalidCharset)),
+                    Cow::Owned(_) => Ok(Cow::Owned(s.to_owned())),
+                }
+            }
+        }
+    }
+
     #[rustfmt::skip]
     fn tag(&self) -> BerTag {
         match self {
             BerObjectContent::EndOfContent         => BerTag::EndOfContent,
             BerObjectContent::Boolean(_)           => BerTag::Boolean,
             BerObjectContent::Integer(_)           => BerTag::Integer,
-            BerObjectContent::BitString(_,_)       => BerTag::BitString,
+            BerObjectContent::BitString(_)         => BerTag::BitString,
+            BerObjectContent::BitStringConstructed(_,_) => BerTag::BitString,
             BerObjectContent::OctetString(_)       => BerTag::OctetString,
+            BerObjectContent::OctetStringConstructed(_) => BerTag::OctetString,
             BerObjectContent::Null                 => BerTag::Null,
+            BerObjectContent::Real(_)              => BerTag::RealType,
             BerObjectContent::Enum(_)              => BerTag::Enumerated,
             BerObjectContent::OID(_)               => BerTag::Oid,
             BerObjectContent::NumericString(_)     => BerTag::NumericString,
@@ -714,6 +1833,8 @@ impl<'a> BerObjectContent<'a> {
             BerObjectContent::ObjectDescriptor(_)  => BerTag::ObjDescriptor,
             BerObjectContent::GraphicString(_)     => BerTag::GraphicString,
             BerObjectContent::GeneralString(_)     => BerTag::GeneralString,
+            BerObjectContent::External(_)          => BerTag::External,
+            BerObjectContent::EmbeddedPdv(_)       => BerTag::EmbeddedPdv,
             BerObjectContent::Tagged(_,x,_) |
             BerObjectContent::Unknown(x,_)         => *x,
             BerObjectContent::Optional(Some(obj))  => obj.content.tag(),
@@ -724,22 +1845,67 @@ impl<'a> BerObjectContent<'a> {
 
 #[cfg(feature = "bigint")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bigint")))]
-use num_bigint::{BigInt, BigUint, Sign};
+use num_bigint::{BigInt, BigUint};
+
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+impl<'a> BerObject<'a> {
+    /// Attempt to decode the content of an UTCTime or GeneralizedTime object, and convert it
+    /// to a `chrono::DateTime<Utc>`.
+    ///
+    /// Timezone offsets are applied to get the time in UTC. This can fail if the object is not
+    /// a time object, if the value is not valid, or if it cannot be converted to UTC (for ex.
+    /// GeneralizedTime in local time).
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_utctime;
+    /// use chrono::NaiveDate;
+    ///
+    /// let bytes = b"\x17\x11991231235959+0100";
+    /// let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    /// let t = obj.as_chrono_utc().expect("invalid time");
+    /// let expected = NaiveDate::from_ymd_opt(1999, 12, 31).and_then(|d| d.and_hms_opt(22, 59, 59));
+    /// assert_eq!(Some(t.naive_utc()), expected);
+    /// ```
+    pub fn as_chrono_utc(&self) -> Result<chrono::DateTime<chrono::Utc>, BerError> {
+        match self.content {
+            BerObjectContent::UTCTime(_) => self.as_utctime()?.to_chrono_utc(),
+            BerObjectContent::GeneralizedTime(_) => self.as_generalizedtime()?.to_chrono_utc(),
+            _ => Err(BerError::BerTypeError),
+        }
+    }
+}
 
 #[cfg(feature = "bigint")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bigint")))]
 impl<'a> BerObject<'a> {
+    /// Attempt to read a signed integer value (two's complement) from an INTEGER object
+    ///
+    /// Returns `None` if the object is not an INTEGER.
+    ///
+    /// *This function is only available if the `bigint` feature is enabled.*
     pub fn as_bigint(&self) -> Option<BigInt> {
         match self.content {
-            BerObjectContent::Integer(s) => Some(BigInt::from_bytes_be(Sign::Plus, s)),
+            BerObjectContent::Integer(ref s) => Some(BigInt::from_signed_bytes_be(s)),
             _ => None,
         }
     }
 
-    pub fn as_biguint(&self) -> Option<BigUint> {
+    /// Attempt to read an unsigned integer value from an INTEGER object
+    ///
+    /// Returns `BerError::BerTypeError` if the object is not an INTEGER, and
+    /// `BerError::BerValueError` if the value is negative.
+    ///
+    /// *This function is only available if the `bigint` feature is enabled.*
+    pub fn as_biguint(&self) -> Result<BigUint, BerError> {
         match self.content {
-            BerObjectContent::Integer(s) => Some(BigUint::from_bytes_be(s)),
-            _ => None,
+            BerObjectContent::Integer(ref s) => {
+                if s.first().map_or(false, |&b| b & 0x80 != 0) {
+                    return Err(BerError::BerValueError);
+                }
+                Ok(BigUint::from_bytes_be(s))
+            }
+            _ => Err(BerError::BerTypeError),
         }
     }
 }
@@ -819,6 +1985,37 @@ impl<'a> BerObject<'a> {
     pub fn ref_iter(&'a self) -> BerObjectRefIterator<'a> {
         BerObjectRefIterator { obj: self, idx: 0 }
     }
+
+    /// Return an iterator over the children of a Sequence or Set object.
+    ///
+    /// For any other object, the iterator is empty.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::BerObject;
+    /// let seq = BerObject::from_seq(vec![
+    ///     BerObject::from_int_slice(b"\x01"),
+    ///     BerObject::from_int_slice(b"\x02"),
+    ///     BerObject::from_int_slice(b"\x03"),
+    /// ]);
+    /// assert_eq!(seq.iter().nth(2).map(|o| o.as_u32()), Some(Ok(3)));
+    /// assert_eq!(seq.len(), 3);
+    /// ```
+    pub fn iter(&self) -> core::slice::Iter<'_, BerObject<'a>> {
+        match self.content {
+            BerObjectContent::Sequence(ref v) | BerObjectContent::Set(ref v) => v.iter(),
+            _ => [].iter(),
+        }
+    }
+
+    /// Return the number of children of a Sequence or Set object, or 0 for any other object
+    pub fn len(&self) -> usize {
+        self.iter().len()
+    }
+
+    /// Test if the object has no children (see [`BerObject::len`](#method.len))
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
 impl<'a> Index<usize> for BerObject<'a> {
@@ -839,12 +2036,66 @@ impl<'a> Index<usize> for BerObject<'a> {
 }
 
 /// BitString wrapper
+///
+/// The number of unused bits can only be set using [`new`](#method.new), which checks that it
+/// is valid.
 #[derive(Clone, Debug, PartialEq)]
 pub struct BitStringObject<'a> {
-    pub data: &'a [u8],
+    // number of unused bits in the last byte of `data` (0 to 7)
+    unused_bits: u8,
+    pub data: Cow<'a, [u8]>,
 }
 
 impl<'a> BitStringObject<'a> {
+    /// Build a new `BitStringObject`, with `unused_bits` unused bits in the last byte of `data`
+    ///
+    /// Returns `BerError::BerValueError` if `unused_bits` is not in the range 0 to 7.
+    ///
+    /// ```rust
+    /// use der_parser::ber::BitStringObject;
+    /// use der_parser::error::BerError;
+    ///
+    /// let b = BitStringObject::new(3, &[0x6e, 0x58]).expect("invalid bitstring");
+    /// assert_eq!(b.bit_len(), 13);
+    /// assert_eq!(BitStringObject::new(8, &[0x00]), Err(BerError::BerValueError));
+    /// ```
+    pub fn new(unused_bits: u8, data: &'a [u8]) -> Result<Self, BerError> {
+        if unused_bits > 7 {
+            return Err(BerError::BerValueError);
+        }
+        Ok(BitStringObject {
+            unused_bits,
+            data: Cow::Borrowed(data),
+        })
+    }
+
+    /// Return the number of unused bits in the last byte of the data
+    pub fn unused_bits(&self) -> u8 {
+        self.unused_bits
+    }
+
+    /// Convert the object to an owned value, copying the data if it is borrowed
+    pub fn into_owned(self) -> BitStringObject<'static> {
+        BitStringObject {
+            unused_bits: self.unused_bits,
+            data: into_owned_cow(self.data),
+        }
+    }
+
+    /// Return the number of bits, excluding the unused bits
+    ///
+    /// ```rust
+    /// # use der_parser::ber::parse_ber_bitstring;
+    /// // 3 unused bits
+    /// let (_, obj) = parse_ber_bitstring(&[0x03, 0x03, 0x03, 0x6e, 0x58]).expect("parsing failed");
+    /// let b = obj.as_bitstring().expect("not a bitstring");
+    /// assert_eq!(b.unused_bits(), 3);
+    /// assert_eq!(b.bit_len(), 13);
+    /// ```
+    pub fn bit_len(&self) -> usize {
+        (self.data.len() * 8).saturating_sub(self.unused_bits as usize)
+    }
+
     /// Test if bit `bitnum` is set
     pub fn is_set(&self, bitnum: usize) -> bool {
         let byte_pos = bitnum / 8;
@@ -854,18 +2105,82 @@ impl<'a> BitStringObject<'a> {
         let b = 7 - (bitnum % 8);
         (self.data[byte_pos] & (1 << b)) != 0
     }
+
+    /// Return an iterator over the indices of the bits that are set
+    ///
+    /// Bits are numbered as in ASN.1: bit 0 is the most significant bit of the first byte.
+    ///
+    /// Note that all bits of the data are considered, including the unused bits. Use
+    /// [`BerObject::iter_set_bits`](struct.BerObject.html#method.iter_set_bits) to exclude the
+    /// trailing unused bits.
+    pub fn iter_set_bits(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.data.len() * 8).filter(move |&n| self.is_set(n))
+    }
+
+    /// Return the names of the flags that are set
+    ///
+    /// `flags` associates bit numbers (as in `is_set`) to names, for ex. for the `KeyUsage` X.509
+    /// extension. Names are returned in the order of `flags`, and bits beyond the length of the
+    /// data are considered not set.
+    ///
+    /// ```rust
+    /// use der_parser::ber::BitStringObject;
+    ///
+    /// const FLAGS: &[(usize, &str)] = &[(0, "digitalSignature"), (5, "keyCertSign"), (6, "cRLSign")];
+    /// let obj = BitStringObject::new(1, &[0x82]).expect("invalid bitstring");
+    /// assert_eq!(obj.to_flag_names(FLAGS), vec!["digitalSignature", "cRLSign"]);
+    /// ```
+    pub fn to_flag_names<'b>(&self, flags: &[(usize, &'b str)]) -> Vec<&'b str> {
+        flags
+            .iter()
+            .filter(|&&(bitnum, _)| self.is_set(bitnum))
+            .map(|&(_, name)| name)
+            .collect()
+    }
+
+    /// Get a view of the data as a slice of bits
+    ///
+    /// Bits are numbered as in ASN.1: bit 0 is the most significant bit of the first byte. As
+    /// for `iter_set_bits`, the unused bits are included: use
+    /// [`BerObject::as_bitslice`](struct.BerObject.html#method.as_bitslice) to exclude them.
+    ///
+    /// *This function is only available if the `bitvec` feature is enabled.*
+    #[cfg(feature = "bitvec")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitvec")))]
+    pub fn as_bitslice(&self) -> &BitSlice<u8, Msb0> {
+        BitSlice::from_slice(&self.data)
+    }
 }
 
 impl<'a> AsRef<[u8]> for BitStringObject<'a> {
     fn as_ref(&self) -> &[u8] {
-        self.data
+        &self.data
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::ber::*;
+    use crate::error::BerError;
     use crate::oid::*;
+    use alloc::borrow::Cow;
+
+    #[test]
+    fn test_class_tag() {
+        let tag = BerClassTag::context(3);
+        assert_eq!(tag.class, TagClass::ContextSpecific);
+        assert_eq!(tag.tag, BerTag(3));
+        assert_eq!(tag.to_string(), "[3]");
+        assert_eq!(BerClassTag::application(5).to_string(), "[APPLICATION 5]");
+        assert_eq!(BerClassTag::private(1).to_string(), "[PRIVATE 1]");
+        assert_eq!(
+            BerClassTag::universal(BerTag::Integer).to_string(),
+            "[UNIVERSAL 2]"
+        );
+        let hdr = BerObjectHeader::new(BerClass::Application, 1, BerTag(5), 0);
+        assert_eq!(hdr.class_tag(), BerClassTag::application(5));
+        assert_ne!(hdr.class_tag(), BerClassTag::context(5));
+    }
 
     #[test]
     fn test_der_as_u64() {
@@ -873,6 +2188,25 @@ mod tests {
         assert_eq!(der_obj.as_u64(), Ok(0x10002));
     }
 
+    #[cfg(feature = "bitvec")]
+    #[test]
+    fn test_as_bitslice() {
+        let (_, obj) = parse_ber_bitstring(b"\x03\x03\x04\xa0\x30").unwrap();
+        let bits = obj.as_bitslice().expect("not a bitstring");
+        assert_eq!(bits.len(), 12);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![0, 2, 10, 11]);
+        // constructed bitstrings, unused bits are excluded
+        let (_, obj) =
+            parse_ber_bitstring(b"\x23\x80\x03\x02\x00\x0a\x03\x02\x06\xc0\x00\x00").unwrap();
+        let bits = obj.as_bitslice().expect("not a bitstring");
+        assert_eq!(bits.len(), 10);
+        assert_eq!(bits.iter_ones().collect::<Vec<_>>(), vec![4, 6, 8, 9]);
+        let b = BitStringObject::new(0, b"\x01").unwrap();
+        assert_eq!(b.as_bitslice().len(), 8);
+        assert!(b.as_bitslice()[7]);
+        assert!(BerObject::from_int_slice(b"\x01").as_bitslice().is_err());
+    }
+
     #[test]
     fn test_ber_as_u64_bitstring() {
         let (_, ber_obj) = parse_ber_bitstring(b"\x03\x04\x06\x6e\x5d\xc0").unwrap();
@@ -911,42 +2245,119 @@ mod tests {
         assert_eq!(obj, expected);
     }
 
+    #[test]
+    fn test_ber_as_bool_strict() {
+        let (_, obj) = parse_ber_bool(b"\x01\x01\x00").unwrap();
+        assert_eq!(obj.as_bool_strict(), Ok(false));
+        let (_, obj) = parse_ber_bool(b"\x01\x01\x80").unwrap();
+        assert_eq!(obj.as_bool(), Ok(true));
+        assert_eq!(obj.as_bool_strict(), Err(BerError::DerConstraintFailed));
+        // not parsed: the encoding is always valid
+        let obj = BerObject::from_obj(BerObjectContent::Boolean(true));
+        assert_eq!(obj.as_bool_strict(), Ok(true));
+        let obj = BerObject::from_int_slice(b"\xff");
+        assert_eq!(obj.as_bool_strict(), Err(BerError::BerTypeError));
+    }
+
+    #[test]
+    fn test_ber_tag_number() {
+        let obj = BerObject::from_int_slice(b"\x01");
+        assert_eq!(obj.tag(), 2);
+        let (_, obj) = parse_ber(b"\xbf\x81\x80\x01\x03\x02\x01\x01").unwrap();
+        assert!(obj.is_contextspecific());
+        assert_eq!(obj.tag(), 0x4001);
+    }
+
     #[test]
     fn test_der_bistringobject() {
-        let obj = BitStringObject {
-            data: &[0x0f, 0x00, 0x40],
-        };
+        let obj = BitStringObject::new(0, &[0x0f, 0x00, 0x40]).unwrap();
         assert!(!obj.is_set(0));
         assert!(obj.is_set(7));
         assert!(!obj.is_set(9));
         assert!(obj.is_set(17));
     }
 
+    #[test]
+    fn test_der_bistringobject_unused_bits() {
+        let (_, obj) = parse_ber_bitstring(b"\x03\x04\x06\x6e\x5d\xc0").unwrap();
+        let b = obj.as_bitstring_ref().expect("not a bitstring");
+        assert_eq!(b.unused_bits(), 6);
+        assert_eq!(b.bit_len(), 18);
+        // constructed bitstrings
+        let (_, obj) =
+            parse_ber_bitstring(b"\x23\x80\x03\x02\x00\x0a\x03\x02\x06\xc0\x00\x00").unwrap();
+        let b = obj.as_bitstring().expect("not a bitstring");
+        assert_eq!((b.unused_bits(), b.bit_len()), (6, 10));
+        // empty
+        let (_, obj) = parse_ber_bitstring(b"\x03\x01\x00").unwrap();
+        assert_eq!(obj.as_bitstring().map(|b| b.bit_len()), Ok(0));
+        // invalid number of unused bits
+        assert_eq!(
+            parse_ber_bitstring(b"\x03\x02\x08\x00"),
+            Err(nom::Err::Error(BerError::BerValueError))
+        );
+        let obj = BerObject::from_obj(BerObjectContent::BitStringConstructed(8, vec![0]));
+        assert_eq!(obj.as_bitstring(), Err(BerError::BerValueError));
+    }
+
+    #[test]
+    fn test_der_bistringobject_iter_set_bits() {
+        let obj = BitStringObject::new(1, &[0x0f, 0x00, 0x41]).unwrap();
+        let bits: Vec<_> = obj.iter_set_bits().collect();
+        assert_eq!(bits, vec![4, 5, 6, 7, 17, 23]);
+        // unused bits are excluded
+        let ber_obj = BerObject::from_obj(BerObjectContent::BitString(obj));
+        let bits: Vec<_> = ber_obj.iter_set_bits().unwrap().collect();
+        assert_eq!(bits, vec![4, 5, 6, 7, 17]);
+        let ber_obj = BerObject::from_int_slice(b"\x01");
+        assert!(ber_obj.iter_set_bits().is_err());
+    }
+
+    #[test]
+    fn test_der_bistringobject_to_flag_names() {
+        let obj = BitStringObject::new(0, &[0x05, 0x80]).unwrap();
+        let flags = &[(0, "a"), (5, "b"), (7, "c"), (8, "d"), (9, "e"), (42, "f")];
+        assert_eq!(obj.to_flag_names(flags), vec!["b", "c", "d"]);
+        assert!(obj.to_flag_names(&[]).is_empty());
+    }
+
     #[test]
     fn test_der_bistringobject_asref() {
         fn assert_equal<T: AsRef<[u8]>>(s: T, b: &[u8]) {
             assert_eq!(s.as_ref(), b);
         }
         let b: &[u8] = &[0x0f, 0x00, 0x40];
-        let obj = BitStringObject { data: b };
+        let obj = BitStringObject::new(0, b).unwrap();
         assert_equal(obj, b);
     }
 
     #[cfg(feature = "bigint")]
     #[test]
     fn test_der_to_bigint() {
-        let obj = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+        let obj = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
         let expected = ::num_bigint::BigInt::from(0x10001);
 
         assert_eq!(obj.as_bigint(), Some(expected));
+        let obj = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\xff\x7f")));
+        let expected = ::num_bigint::BigInt::from(-129);
+        assert_eq!(obj.as_bigint(), Some(expected));
     }
 
     #[cfg(feature = "bigint")]
     #[test]
     fn test_der_to_biguint() {
-        let obj = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+        let obj = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
         let expected = ::num_bigint::BigUint::from(0x10001 as u32);
 
-        assert_eq!(obj.as_biguint(), Some(expected));
+        assert_eq!(obj.as_biguint(), Ok(expected));
+        // sign padding
+        let obj = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x00\x80")));
+        let expected = ::num_bigint::BigUint::from(0x80 as u32);
+        assert_eq!(obj.as_biguint(), Ok(expected));
+        // negative value
+        let obj = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x80")));
+        assert_eq!(obj.as_biguint(), Err(BerError::BerValueError));
+        let obj = BerObject::from_obj(BerObjectContent::Boolean(true));
+        assert_eq!(obj.as_biguint(), Err(BerError::BerTypeError));
     }
 }
@@ -0,0 +1,197 @@
+use crate::ber::*;
+use crate::oid::Oid;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, PartialEq)]
+enum BuilderItem<'a> {
+    Object(BerObject<'a>),
+    /// Encoded integer, owned by the builder
+    Integer(Vec<u8>),
+    Constructed(BerObjectBuilder<'a>),
+}
+
+/// Builder for constructed objects (Sequence and Set)
+///
+/// Values are added in order, and nested objects can be created using other builders.
+///
+/// Integers created from native values are stored in the builder, so the object returned by
+/// `build` borrows the builder:
+///
+/// ```rust
+/// use der_parser::ber::BerObjectBuilder;
+/// use der_parser::oid::Oid;
+///
+/// let oid = Oid::from(&[1, 2, 840, 113_549, 1, 1, 5]).unwrap();
+/// let builder = BerObjectBuilder::sequence()
+///     .add_integer(5)
+///     .add_oid(oid)
+///     .add_builder(BerObjectBuilder::set().add_bool(true).add_null());
+/// let obj = builder.build();
+/// assert_eq!(obj.len(), 3);
+/// assert_eq!(obj[0].as_u32(), Ok(5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BerObjectBuilder<'a> {
+    tag: BerTag,
+    items: Vec<BuilderItem<'a>>,
+}
+
+/// Encode integer using the minimal number of bytes, in two's complement
+// we do not use .copied() for compatibility with 1.34
+#[allow(clippy::map_clone)]
+fn encode_i64(i: i64) -> Vec<u8> {
+    let bytes = i.to_be_bytes();
+    // skip redundant sign bytes (X.690 8.3.2)
+    let mut start = 0;
+    while start < bytes.len() - 1 {
+        let (b0, b1) = (bytes[start], bytes[start + 1]);
+        if (b0 == 0 && b1 & 0x80 == 0) || (b0 == 0xff && b1 & 0x80 != 0) {
+            start += 1;
+        } else {
+            break;
+        }
+    }
+    bytes[start..].iter().map(|&x| x).collect()
+}
+
+impl<'a> BerObjectBuilder<'a> {
+    /// Create a builder for a `SEQUENCE`
+    pub fn sequence() -> Self {
+        BerObjectBuilder {
+            tag: BerTag::Sequence,
+            items: Vec::new(),
+        }
+    }
+
+    /// Create a builder for a `SET`
+    pub fn set() -> Self {
+        BerObjectBuilder {
+            tag: BerTag::Set,
+            items: Vec::new(),
+        }
+    }
+
+    /// Add an object
+    pub fn add_object(mut self, obj: BerObject<'a>) -> Self {
+        self.items.push(BuilderItem::Object(obj));
+        self
+    }
+
+    /// Add a constructed object, built from another builder
+    pub fn add_builder(mut self, builder: BerObjectBuilder<'a>) -> Self {
+        self.items.push(BuilderItem::Constructed(builder));
+        self
+    }
+
+    /// Add an INTEGER object
+    pub fn add_integer(mut self, i: i64) -> Self {
+        self.items.push(BuilderItem::Integer(encode_i64(i)));
+        self
+    }
+
+    /// Add an INTEGER object, from a slice containing an encoded integer
+    pub fn add_int_slice(self, i: &'a [u8]) -> Self {
+        self.add_object(BerObject::from_int_slice(i))
+    }
+
+    /// Add a BOOLEAN object
+    pub fn add_bool(self, b: bool) -> Self {
+        self.add_object(BerObject::from_bool(b))
+    }
+
+    /// Add a NULL object
+    pub fn add_null(self) -> Self {
+        self.add_object(BerObject::from_obj(BerObjectContent::Null))
+    }
+
+    /// Add an OID object
+    pub fn add_oid(self, oid: Oid<'a>) -> Self {
+        self.add_object(BerObject::from_oid(oid))
+    }
+
+    /// Add an OCTET STRING object
+    pub fn add_octetstring(self, s: &'a [u8]) -> Self {
+        self.add_object(BerObject::from_octetstring(s))
+    }
+
+    /// Add an UTF8String object
+    pub fn add_utf8string(self, s: &'a str) -> Self {
+        self.add_object(BerObject::from_obj(BerObjectContent::UTF8String(
+            Cow::Borrowed(s),
+        )))
+    }
+
+    /// Build the constructed object
+    ///
+    /// Lengths are not set in the headers, they are computed during serialization.
+    pub fn build(&self) -> BerObject<'_> {
+        let v = self
+            .items
+            .iter()
+            .map(|item| match item {
+                BuilderItem::Object(obj) => obj.clone(),
+                BuilderItem::Integer(i) => BerObject::from_int_slice(i),
+                BuilderItem::Constructed(builder) => builder.build(),
+            })
+            .collect();
+        if self.tag == BerTag::Set {
+            BerObject::from_set(v)
+        } else {
+            BerObject::from_seq(v)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    #[cfg(feature = "serialize")]
+    use crate::der::parse_der;
+
+    #[test]
+    fn test_encode_i64() {
+        assert_eq!(encode_i64(0), vec![0]);
+        assert_eq!(encode_i64(5), vec![5]);
+        assert_eq!(encode_i64(127), vec![0x7f]);
+        assert_eq!(encode_i64(128), vec![0x00, 0x80]);
+        assert_eq!(encode_i64(-1), vec![0xff]);
+        assert_eq!(encode_i64(-128), vec![0x80]);
+        assert_eq!(encode_i64(-129), vec![0xff, 0x7f]);
+        assert_eq!(encode_i64(0x1_0000), vec![0x01, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn test_builder() {
+        let builder = BerObjectBuilder::sequence()
+            .add_integer(-129)
+            .add_octetstring(b"abc")
+            .add_builder(BerObjectBuilder::set().add_utf8string("x"));
+        let obj = builder.build();
+        assert!(obj.header.is_constructed());
+        assert_eq!(obj.header.tag, BerTag::Sequence);
+        assert_eq!(obj[0].as_i64(), Ok(-129));
+        assert_eq!(obj[1].as_slice(), Ok(&b"abc"[..]));
+        assert_eq!(obj[2].header.tag, BerTag::Set);
+        assert_eq!(obj[2][0].as_str(), Ok("x"));
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_builder_serialize() {
+        let oid = Oid::from(&[1, 2, 840, 113_549, 1, 1, 5]).unwrap();
+        let builder = BerObjectBuilder::sequence()
+            .add_integer(5)
+            .add_oid(oid)
+            .add_bool(true)
+            .add_null();
+        let v = builder.build().to_vec().expect("could not encode");
+        assert_eq!(
+            &v[..],
+            &hex_literal::hex!("30 13 02 01 05 06 09 2a 86 48 86 f7 0d 01 01 05 01 01 ff 05 00")[..]
+        );
+        let (rem, obj) = parse_der(&v).expect("could not parse");
+        assert!(rem.is_empty());
+        assert_eq!(obj, builder.build());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::ber::*;
+use crate::error::*;
+use crate::oid::Oid;
+use nom::Err;
+
+/// Parse a CMS (or PKCS#7) `ContentInfo` object, returning the content type and the content
+///
+/// ```text
+/// ContentInfo ::= SEQUENCE {
+///     contentType ContentType,
+///     content [0] EXPLICIT ANY DEFINED BY contentType }
+///
+/// ContentType ::= OBJECT IDENTIFIER
+/// ```
+///
+/// The content is parsed as a BER object, and is not interpreted: use the content type to select
+/// a parser for it. Data after the content, inside the `[0]` tag or the SEQUENCE, is rejected
+/// with `BerError::UnexpectedData`.
+///
+/// This is only the outer envelope of CMS (RFC 5652) messages, not a full CMS parser.
+///
+/// ### Example
+///
+/// ```rust
+/// # use der_parser::ber::parse_ber_content_info;
+/// # use der_parser::oid;
+/// // id-data, with an OCTET STRING
+/// let bytes = &[
+///     0x30, 0x12, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x01,
+///     0xa0, 0x05, 0x04, 0x03, 0x61, 0x62, 0x63,
+/// ];
+/// let (_, (content_type, content)) = parse_ber_content_info(bytes).expect("parsing failed");
+/// assert_eq!(content_type, oid!(1.2.840.113549.1.7.1));
+/// assert_eq!(content.as_slice(), Ok(&b"abc"[..]));
+/// ```
+pub fn parse_ber_content_info(i: &[u8]) -> BerResult<(Oid, BerObject)> {
+    parse_ber_sequence_defined_g(|_, i| {
+        let (i, content_type) = Oid::from_ber(i)?;
+        let (i, content) = parse_ber_tagged_explicit_g(0, |_, content| {
+            let (rem, content) = parse_ber(content)?;
+            custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+            Ok((rem, content))
+        })(i)?;
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (content_type, content)))
+    })(i)
+}
+
+/// Parse an `AlgorithmIdentifier` object, returning the algorithm and the optional parameters
+///
+/// ```text
+/// AlgorithmIdentifier ::= SEQUENCE {
+///     algorithm  OBJECT IDENTIFIER,
+///     parameters ANY DEFINED BY algorithm OPTIONAL }
+/// ```
+///
+/// The parameters are returned as a raw `BerObject`, which can be a `NULL` object (note that
+/// `NULL` parameters and absent parameters are not the same, and are both used in practice).
+/// Data after the parameters, inside the SEQUENCE, is rejected with `BerError::UnexpectedData`.
+///
+/// ### Example
+///
+/// ```rust
+/// # use der_parser::ber::parse_ber_algorithm_identifier;
+/// # use der_parser::oid;
+/// // sha256WithRSAEncryption, with NULL parameters
+/// let bytes = &[
+///     0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b,
+///     0x05, 0x00,
+/// ];
+/// let (_, (algorithm, params)) = parse_ber_algorithm_identifier(bytes).expect("parsing failed");
+/// assert_eq!(algorithm, oid!(1.2.840.113549.1.1.11));
+/// assert!(params.map(|p| p.is_null()).unwrap_or(false));
+/// ```
+pub fn parse_ber_algorithm_identifier(i: &[u8]) -> BerResult<(Oid, Option<BerObject>)> {
+    parse_ber_sequence_defined_g(|_, i| {
+        let (i, algorithm) = Oid::from_ber(i)?;
+        let (i, params) = if i.is_empty() {
+            (i, None)
+        } else {
+            let (i, params) = parse_ber(i)?;
+            (i, Some(params))
+        };
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (algorithm, params)))
+    })(i)
+}
+
+/// Fields of a PKCS#8 `PrivateKeyInfo`: version, algorithm (and parameters), private key and
+/// optional attributes
+///
+/// See [`parse_ber_private_key_info`](fn.parse_ber_private_key_info.html).
+pub type PrivateKeyInfo<'a> = (
+    u32,
+    (Oid<'a>, Option<BerObject<'a>>),
+    BerObject<'a>,
+    Option<BerObject<'a>>,
+);
+
+/// Parse a PKCS#8 `PrivateKeyInfo` object
+///
+/// ```text
+/// PrivateKeyInfo ::= SEQUENCE {
+///     version                   Version,
+///     privateKeyAlgorithm       AlgorithmIdentifier,
+///     privateKey                OCTET STRING,
+///     attributes           [0]  IMPLICIT Attributes OPTIONAL }
+///
+/// Version ::= INTEGER
+/// Attributes ::= SET OF Attribute
+/// ```
+///
+/// Returns the version, the algorithm (see
+/// [`parse_ber_algorithm_identifier`](fn.parse_ber_algorithm_identifier.html)), the
+/// `OCTET STRING` containing the private key, and the optional attributes (as a `Set` object,
+/// with the `[0]` header). The private key is not decoded, its encoding depends on the
+/// algorithm.
+///
+/// Data after the attributes, inside the SEQUENCE, is rejected with `BerError::UnexpectedData`.
+///
+/// ### Example
+///
+/// ```rust
+/// # use der_parser::ber::parse_ber_private_key_info;
+/// # use der_parser::oid;
+/// // ed25519 private key (RFC 8410)
+/// let bytes = &[
+///     0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04,
+///     0x20, 0xd4, 0xee, 0x72, 0xdb, 0xf9, 0x13, 0x58, 0x4a, 0xd5, 0xb6, 0xd8, 0xf1, 0xf7, 0x69,
+///     0xf8, 0xad, 0x3a, 0xfe, 0x7c, 0x28, 0xcb, 0xf1, 0xd4, 0xfb, 0xe0, 0x97, 0xa8, 0x8f, 0x44,
+///     0x75, 0x58, 0x42,
+/// ];
+/// let (_, (version, (algorithm, _), private_key, attributes)) =
+///     parse_ber_private_key_info(bytes).expect("parsing failed");
+/// assert_eq!(version, 0);
+/// assert_eq!(algorithm, oid!(1.3.101.112));
+/// assert_eq!(private_key.as_slice().map(|s| s.len()), Ok(34));
+/// assert!(attributes.is_none());
+/// ```
+pub fn parse_ber_private_key_info(i: &[u8]) -> BerResult<PrivateKeyInfo> {
+    parse_ber_sequence_defined_g(|_, i| {
+        let (i, version) = parse_ber_u32(i)?;
+        let (i, algorithm) = parse_ber_algorithm_identifier(i)?;
+        let (i, private_key) = parse_ber_octetstring(i)?;
+        let (i, attributes) = if i.is_empty() {
+            (i, None)
+        } else {
+            let (i, attributes) = parse_ber_tagged_implicit(0, parse_ber_content(BerTag::Set))(i)?;
+            (i, Some(attributes))
+        };
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (version, algorithm, private_key, attributes)))
+    })(i)
+}
+
+/// Parse an OID, and then the following object using the parser associated to this OID
+///
+/// This models the `ANY DEFINED BY` pattern, for ex:
+///
+/// ```text
+/// AlgorithmIdentifier ::= SEQUENCE {
+///     algorithm  OBJECT IDENTIFIER,
+///     parameters ANY DEFINED BY algorithm OPTIONAL }
+/// ```
+///
+/// `table` associates OIDs with parsers (all of the same type, usually functions). If the OID
+/// is not found in `table`, `BerError::BerValueError` is returned. The parsers can be DER
+/// parsers, and can return optional values (for ex. using
+/// [`parse_ber_optional`](fn.parse_ber_optional.html)).
+///
+/// ### Example
+///
+/// ```rust
+/// # use der_parser::ber::*;
+/// # use der_parser::error::BerResult;
+/// # use der_parser::oid;
+/// # use der_parser::oid::Oid;
+/// #
+/// // rsaEncryption has NULL parameters, and ecPublicKey has a curve OID
+/// let table: &[(Oid, fn(&[u8]) -> BerResult<'_>)] = &[
+///     (oid!(1.2.840.113549.1.1.1), parse_ber_null),
+///     (oid!(1.2.840.10045.2.1), parse_ber_oid),
+/// ];
+/// let bytes = &[
+///     0x30, 0x13, 0x06, 0x07, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01, 0x06, 0x08, 0x2a, 0x86,
+///     0x48, 0xce, 0x3d, 0x03, 0x01, 0x07,
+/// ];
+/// let (_, (algorithm, params)) =
+///     parse_ber_sequence_defined_g(|_, i| parse_ber_any_defined_by(table)(i))(bytes)
+///         .expect("parsing failed");
+/// assert_eq!(algorithm, oid!(1.2.840.10045.2.1));
+/// assert_eq!(params.as_oid_val(), Ok(oid!(1.2.840.10045.3.1.7)));
+/// ```
+pub fn parse_ber_any_defined_by<'a, 'b, T, F>(
+    table: &'b [(Oid<'b>, F)],
+) -> impl Fn(&'a [u8]) -> BerResult<'a, (Oid<'a>, T)> + 'b
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, T>,
+{
+    move |i| {
+        let (i, oid) = Oid::from_ber(i)?;
+        let f = table
+            .iter()
+            .find(|(o, _)| *o == oid)
+            .map(|(_, f)| f)
+            .ok_or(Err::Error(BerError::BerValueError))?;
+        let (i, value) = f(i)?;
+        Ok((i, (oid, value)))
+    }
+}
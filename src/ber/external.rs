@@ -0,0 +1,383 @@
+use crate::ber::*;
+use crate::error::*;
+use crate::oid::Oid;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use nom::Err;
+
+/// Encoding of the data value of an `EXTERNAL` object
+///
+/// ```text
+/// encoding CHOICE {
+///     single-ASN1-type  [0] ABSTRACT-SYNTAX.&Type,
+///     octet-aligned     [1] IMPLICIT OCTET STRING,
+///     arbitrary         [2] IMPLICIT BIT STRING }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum BerExternalEncoding<'a> {
+    /// A single BER object (the value is explicitly tagged)
+    SingleAsn1Type(Box<BerObject<'a>>),
+    /// An encoding which is an integral number of octets
+    OctetAligned(Cow<'a, [u8]>),
+    /// Constructed `octet-aligned` encoding (BER only), the segments are concatenated
+    OctetAlignedConstructed(Vec<u8>),
+    /// Any other encoding
+    Arbitrary(BitStringObject<'a>),
+    /// Constructed `arbitrary` encoding (BER only): number of unused bits, and the concatenated
+    /// segments
+    ArbitraryConstructed(u8, Vec<u8>),
+}
+
+/// Content of an `EXTERNAL` object (X.690 8.18)
+///
+/// ```text
+/// EXTERNAL ::= [UNIVERSAL 8] IMPLICIT SEQUENCE {
+///     direct-reference       OBJECT IDENTIFIER OPTIONAL,
+///     indirect-reference     INTEGER OPTIONAL,
+///     data-value-descriptor  ObjectDescriptor OPTIONAL,
+///     encoding               CHOICE { ... } }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BerExternal<'a> {
+    /// Abstract syntax (and transfer syntax) of the value
+    pub direct_reference: Option<Oid<'a>>,
+    /// Presentation context identifier, as the content octets of the INTEGER
+    pub indirect_reference: Option<Cow<'a, [u8]>>,
+    /// Description of the value, as the content octets of the ObjectDescriptor
+    pub data_value_descriptor: Option<Cow<'a, [u8]>>,
+    /// The encoded value
+    pub encoding: BerExternalEncoding<'a>,
+}
+
+/// Identification of the abstract and transfer syntaxes of an `EMBEDDED PDV` object
+///
+/// ```text
+/// identification CHOICE {
+///     syntaxes                 [0] SEQUENCE {
+///                                  abstract  [0] OBJECT IDENTIFIER,
+///                                  transfer  [1] OBJECT IDENTIFIER },
+///     syntax                   [1] OBJECT IDENTIFIER,
+///     presentation-context-id  [2] INTEGER,
+///     context-negotiation      [3] SEQUENCE {
+///                                  presentation-context-id  [0] INTEGER,
+///                                  transfer-syntax          [1] OBJECT IDENTIFIER },
+///     transfer-syntax          [4] OBJECT IDENTIFIER,
+///     fixed                    [5] NULL }
+/// ```
+///
+/// INTEGER values are stored as their content octets.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BerPdvIdentification<'a> {
+    /// Abstract syntax and transfer syntax
+    Syntaxes(Oid<'a>, Oid<'a>),
+    /// Single OID for the abstract and transfer syntaxes
+    Syntax(Oid<'a>),
+    /// Presentation context identifier
+    PresentationContextId(Cow<'a, [u8]>),
+    /// Presentation context identifier and transfer syntax
+    ContextNegotiation(Cow<'a, [u8]>, Oid<'a>),
+    /// Transfer syntax (the abstract syntax is known by the application)
+    TransferSyntax(Oid<'a>),
+    /// The syntaxes are fixed by the application
+    Fixed,
+}
+
+/// Content of an `EMBEDDED PDV` object (X.690 8.19)
+///
+/// ```text
+/// EmbeddedPDV ::= [UNIVERSAL 11] IMPLICIT SEQUENCE {
+///     identification  [0] CHOICE { ... },
+///     data-value      [2] OCTET STRING }
+/// ```
+///
+/// The `data-value-descriptor` component of the associated type is always absent in encodings
+/// (X.680 36.5).
+#[derive(Debug, Clone, PartialEq)]
+pub struct BerEmbeddedPdv<'a> {
+    /// Identification of the syntaxes of the value
+    pub identification: BerPdvIdentification<'a>,
+    /// The encoded value
+    pub data_value: Cow<'a, [u8]>,
+}
+
+impl<'a> BerExternalEncoding<'a> {
+    /// Convert the encoding to an owned value, copying the borrowed data
+    pub fn into_owned(self) -> BerExternalEncoding<'static> {
+        match self {
+            BerExternalEncoding::SingleAsn1Type(obj) => {
+                BerExternalEncoding::SingleAsn1Type(Box::new(obj.into_owned()))
+            }
+            BerExternalEncoding::OctetAligned(s) => {
+                BerExternalEncoding::OctetAligned(into_owned_cow(s))
+            }
+            BerExternalEncoding::OctetAlignedConstructed(v) => {
+                BerExternalEncoding::OctetAlignedConstructed(v)
+            }
+            BerExternalEncoding::Arbitrary(b) => BerExternalEncoding::Arbitrary(b.into_owned()),
+            BerExternalEncoding::ArbitraryConstructed(u, v) => {
+                BerExternalEncoding::ArbitraryConstructed(u, v)
+            }
+        }
+    }
+}
+
+impl<'a> BerExternal<'a> {
+    /// Convert the components to owned values, copying the borrowed data
+    pub fn into_owned(self) -> BerExternal<'static> {
+        BerExternal {
+            direct_reference: self.direct_reference.map(|oid| oid.to_owned()),
+            indirect_reference: self.indirect_reference.map(into_owned_cow),
+            data_value_descriptor: self.data_value_descriptor.map(into_owned_cow),
+            encoding: self.encoding.into_owned(),
+        }
+    }
+}
+
+impl<'a> BerPdvIdentification<'a> {
+    /// Convert the identification to an owned value, copying the borrowed data
+    pub fn into_owned(self) -> BerPdvIdentification<'static> {
+        match self {
+            BerPdvIdentification::Syntaxes(a, t) => {
+                BerPdvIdentification::Syntaxes(a.to_owned(), t.to_owned())
+            }
+            BerPdvIdentification::Syntax(oid) => BerPdvIdentification::Syntax(oid.to_owned()),
+            BerPdvIdentification::PresentationContextId(s) => {
+                BerPdvIdentification::PresentationContextId(into_owned_cow(s))
+            }
+            BerPdvIdentification::ContextNegotiation(s, oid) => {
+                BerPdvIdentification::ContextNegotiation(into_owned_cow(s), oid.to_owned())
+            }
+            BerPdvIdentification::TransferSyntax(oid) => {
+                BerPdvIdentification::TransferSyntax(oid.to_owned())
+            }
+            BerPdvIdentification::Fixed => BerPdvIdentification::Fixed,
+        }
+    }
+}
+
+impl<'a> BerEmbeddedPdv<'a> {
+    /// Convert the components to owned values, copying the borrowed data
+    pub fn into_owned(self) -> BerEmbeddedPdv<'static> {
+        BerEmbeddedPdv {
+            identification: self.identification.into_owned(),
+            data_value: into_owned_cow(self.data_value),
+        }
+    }
+}
+
+/// Return the content of a primitive context-specific object, or `None` for other objects
+fn context_primitive<'a>(obj: &BerObject<'a>) -> Option<(u32, &'a [u8])> {
+    match obj.content {
+        BerObjectContent::Unknown(tag, Cow::Borrowed(s))
+            if obj.header.class == BerClass::ContextSpecific && obj.header.is_primitive() =>
+        {
+            Some((tag.0, s))
+        }
+        _ => None,
+    }
+}
+
+/// Read the content of `data` as a value of universal type `tag`
+fn read_content_as<'a>(data: &'a [u8], tag: BerTag) -> Result<BerObjectContent<'a>, Err<BerError>> {
+    let (rem, content) = parse_ber_content_with_len(tag)(data, data.len())?;
+    custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+    Ok(content)
+}
+
+/// Read the content of a constructed object, using the constructed encoding of universal type
+/// `tag`
+fn read_constructed_content_as(
+    data: &[u8],
+    tag: BerTag,
+    max_depth: usize,
+) -> Result<BerObjectContent, Err<BerError>> {
+    let len = BerSize::Definite(data.len());
+    let (rem, content) = ber_read_element_content_as(data, tag, len, true, max_depth)?;
+    custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+    Ok(content)
+}
+
+fn read_oid(data: &[u8]) -> Result<Oid, Err<BerError>> {
+    match read_content_as(data, BerTag::Oid)? {
+        BerObjectContent::OID(oid) => Ok(oid),
+        _ => Err(Err::Error(BerError::BerTypeError)),
+    }
+}
+
+fn read_integer(data: &[u8]) -> Result<Cow<[u8]>, Err<BerError>> {
+    custom_check!(data, data.is_empty(), BerError::InvalidLength)?;
+    Ok(Cow::Borrowed(data))
+}
+
+pub(crate) fn ber_read_content_external(
+    i: &[u8],
+    len: BerSize,
+    max_depth: usize,
+) -> BerResult<BerObjectContent> {
+    let (rem, content) = ber_read_content_sequence(i, len, max_depth)?;
+    let mut components = match content {
+        BerObjectContent::Sequence(l) => l.into_iter().peekable(),
+        _ => return Err(Err::Error(BerError::BerTypeError)),
+    };
+    let mut next_universal = |tag: BerTag| match components.peek() {
+        Some(obj) if obj.header.class == BerClass::Universal && obj.header.tag == tag => {
+            components.next().map(|obj| obj.content)
+        }
+        _ => None,
+    };
+    let direct_reference = match next_universal(BerTag::Oid) {
+        Some(BerObjectContent::OID(oid)) => Some(oid),
+        _ => None,
+    };
+    let indirect_reference = match next_universal(BerTag::Integer) {
+        Some(BerObjectContent::Integer(s)) => Some(s),
+        _ => None,
+    };
+    let data_value_descriptor = match next_universal(BerTag::ObjDescriptor) {
+        Some(BerObjectContent::ObjectDescriptor(s)) => Some(s),
+        _ => None,
+    };
+    let obj = components
+        .next()
+        .ok_or(Err::Error(BerError::ObjectTooShort))?;
+    if obj.header.class != BerClass::ContextSpecific || obj.header.tag.0 > 2 {
+        return Err(Err::Error(BerError::UnexpectedTag(obj.header.tag)));
+    }
+    let encoding = match (obj.header.tag.0, obj.content) {
+        (0, BerObjectContent::Unknown(_, Cow::Borrowed(data))) if obj.header.is_constructed() => {
+            // the element is parsed at max_depth - 1, so its content at max_depth - 2
+            let (data, value) = parse_ber_recursive(data, max_depth - 2)?;
+            custom_check!(data, !data.is_empty(), BerError::UnexpectedData(data.len()))?;
+            BerExternalEncoding::SingleAsn1Type(Box::new(value))
+        }
+        (1, BerObjectContent::Unknown(_, Cow::Borrowed(data))) if obj.header.is_primitive() => {
+            BerExternalEncoding::OctetAligned(Cow::Borrowed(data))
+        }
+        (1, BerObjectContent::Unknown(_, Cow::Borrowed(data))) => {
+            // the content of the element is parsed at max_depth - 1
+            match read_constructed_content_as(data, BerTag::OctetString, max_depth - 1)? {
+                BerObjectContent::OctetStringConstructed(v) => {
+                    BerExternalEncoding::OctetAlignedConstructed(v)
+                }
+                _ => return Err(Err::Error(BerError::BerTypeError)),
+            }
+        }
+        (2, BerObjectContent::Unknown(_, Cow::Borrowed(data))) if obj.header.is_primitive() => {
+            match read_content_as(data, BerTag::BitString)? {
+                BerObjectContent::BitString(b) => BerExternalEncoding::Arbitrary(b),
+                _ => return Err(Err::Error(BerError::BerTypeError)),
+            }
+        }
+        (2, BerObjectContent::Unknown(_, Cow::Borrowed(data))) => {
+            match read_constructed_content_as(data, BerTag::BitString, max_depth - 1)? {
+                BerObjectContent::BitStringConstructed(u, v) => {
+                    BerExternalEncoding::ArbitraryConstructed(u, v)
+                }
+                _ => return Err(Err::Error(BerError::BerTypeError)),
+            }
+        }
+        (0, _) => return Err(Err::Error(BerError::ConstructExpected)),
+        _ => return Err(Err::Error(BerError::BerTypeError)),
+    };
+    custom_check!(i, components.next().is_some(), BerError::BerValueError)?;
+    let external = BerExternal {
+        direct_reference,
+        indirect_reference,
+        data_value_descriptor,
+        encoding,
+    };
+    Ok((rem, BerObjectContent::External(external)))
+}
+
+/// Read the two components of the `syntaxes` or `context-negotiation` alternatives
+fn read_pdv_pair(i: &[u8]) -> Result<(&[u8], &[u8]), Err<BerError>> {
+    let mut components = [&b""[..]; 2];
+    let mut i = i;
+    for (tag, component) in components.iter_mut().enumerate() {
+        let (rem, obj) = parse_ber_recursive(i, 1)?;
+        match context_primitive(&obj) {
+            Some((t, data)) if t as usize == tag => *component = data,
+            _ => return Err(Err::Error(BerError::UnexpectedTag(obj.header.tag))),
+        }
+        i = rem;
+    }
+    custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+    Ok((components[0], components[1]))
+}
+
+fn read_pdv_identification(i: &[u8]) -> Result<BerPdvIdentification, Err<BerError>> {
+    let (rem, hdr) = ber_read_element_header(i)?;
+    custom_check!(
+        i,
+        hdr.class != BerClass::ContextSpecific,
+        BerError::InvalidClass
+    )?;
+    let (rem, data) = ber_get_object_content(rem, &hdr, MAX_RECURSION)?;
+    custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+    // syntaxes and context-negotiation are SEQUENCE values
+    if hdr.tag.0 == 0 || hdr.tag.0 == 3 {
+        custom_check!(i, !hdr.is_constructed(), BerError::ConstructExpected)?;
+    } else {
+        custom_check!(i, hdr.is_constructed(), BerError::ConstructUnexpected)?;
+    }
+    let identification = match hdr.tag.0 {
+        0 => {
+            let (abstract_syntax, transfer_syntax) = read_pdv_pair(data)?;
+            BerPdvIdentification::Syntaxes(read_oid(abstract_syntax)?, read_oid(transfer_syntax)?)
+        }
+        1 => BerPdvIdentification::Syntax(read_oid(data)?),
+        2 => BerPdvIdentification::PresentationContextId(read_integer(data)?),
+        3 => {
+            let (id, transfer_syntax) = read_pdv_pair(data)?;
+            BerPdvIdentification::ContextNegotiation(read_integer(id)?, read_oid(transfer_syntax)?)
+        }
+        4 => BerPdvIdentification::TransferSyntax(read_oid(data)?),
+        5 => {
+            custom_check!(data, !data.is_empty(), BerError::InvalidLength)?;
+            BerPdvIdentification::Fixed
+        }
+        _ => return Err(Err::Error(BerError::UnexpectedTag(hdr.tag))),
+    };
+    Ok(identification)
+}
+
+pub(crate) fn ber_read_content_embedded_pdv(
+    i: &[u8],
+    len: BerSize,
+    max_depth: usize,
+) -> BerResult<BerObjectContent> {
+    let (rem, content) = ber_read_content_sequence(i, len, max_depth)?;
+    let components = match content {
+        BerObjectContent::Sequence(ref l) => l,
+        _ => return Err(Err::Error(BerError::BerTypeError)),
+    };
+    custom_check!(i, components.len() < 2, BerError::ObjectTooShort)?;
+    custom_check!(i, components.len() > 2, BerError::BerValueError)?;
+    let identification = match components[0].content {
+        BerObjectContent::Unknown(BerTag(0), Cow::Borrowed(data))
+            if components[0].header.class == BerClass::ContextSpecific
+                && components[0].header.is_constructed() =>
+        {
+            read_pdv_identification(data)?
+        }
+        _ => {
+            return Err(Err::Error(BerError::UnexpectedTag(
+                components[0].header.tag,
+            )))
+        }
+    };
+    let data_value = match context_primitive(&components[1]) {
+        Some((2, data)) => Cow::Borrowed(data),
+        _ => {
+            return Err(Err::Error(BerError::UnexpectedTag(
+                components[1].header.tag,
+            )))
+        }
+    };
+    let pdv = BerEmbeddedPdv {
+        identification,
+        data_value,
+    };
+    Ok((rem, BerObjectContent::EmbeddedPdv(pdv)))
+}
@@ -0,0 +1,93 @@
+use crate::ber::*;
+use crate::error::*;
+use crate::oid::Oid;
+use alloc::borrow::Cow;
+
+/// Types that can be parsed from BER-encoded data
+///
+/// This trait is used by the `BerSequence` derive macro (see the `derive` feature): each field
+/// of the structure must implement `FromBer`. It is implemented for common primitive types, for
+/// `BerObject` (any object), and for derived structures.
+///
+/// ```rust
+/// use der_parser::ber::FromBer;
+///
+/// let bytes = &[0x02, 0x03, 0x01, 0x00, 0x01];
+/// let (_, value) = u32::from_ber(bytes).expect("parsing failed");
+/// assert_eq!(value, 0x10001);
+/// ```
+pub trait FromBer<'a>: Sized {
+    /// Parse an object of this type (header and content)
+    fn from_ber(i: &'a [u8]) -> BerResult<'a, Self>;
+
+    /// Parse the content of an object of this type, the header having already been read
+    ///
+    /// This is used for IMPLICIT tagged values, where the header contains the tag instead of the
+    /// type of the value.
+    fn from_ber_content(
+        i: &'a [u8],
+        hdr: &BerObjectHeader<'a>,
+        max_depth: usize,
+    ) -> BerResult<'a, Self>;
+}
+
+impl<'a> FromBer<'a> for BerObject<'a> {
+    fn from_ber(i: &'a [u8]) -> BerResult<'a, Self> {
+        parse_ber(i)
+    }
+
+    /// The content is not decoded, and is returned as `BerObjectContent::Unknown`
+    fn from_ber_content(
+        i: &'a [u8],
+        hdr: &BerObjectHeader<'a>,
+        _max_depth: usize,
+    ) -> BerResult<'a, Self> {
+        let content = BerObjectContent::Unknown(hdr.tag, Cow::Borrowed(i));
+        let obj = BerObject::from_header_and_content(hdr.clone(), content);
+        Ok((&i[i.len()..], obj))
+    }
+}
+
+macro_rules! impl_from_ber {
+    ($t:ty, $tag:expr, $conv:expr) => {
+        impl<'a> FromBer<'a> for $t {
+            fn from_ber(i: &'a [u8]) -> BerResult<'a, Self> {
+                let (rem, obj) = parse_ber_with_tag(i, $tag)?;
+                let value = $conv(obj.content)?;
+                Ok((rem, value))
+            }
+
+            fn from_ber_content(
+                i: &'a [u8],
+                hdr: &BerObjectHeader<'a>,
+                max_depth: usize,
+            ) -> BerResult<'a, Self> {
+                let (rem, content) = parse_ber_content($tag)(i, hdr, max_depth)?;
+                let value = $conv(content)?;
+                Ok((rem, value))
+            }
+        }
+    };
+}
+
+impl_from_ber!(bool, BerTag::Boolean, |c: BerObjectContent| c.as_bool());
+impl_from_ber!(u32, BerTag::Integer, |c: BerObjectContent| c.as_u32());
+impl_from_ber!(u64, BerTag::Integer, |c: BerObjectContent| c.as_u64());
+impl_from_ber!(i64, BerTag::Integer, |c: BerObjectContent| c.as_i64());
+impl_from_ber!(Oid<'a>, BerTag::Oid, |c: BerObjectContent<'a>| match c {
+    BerObjectContent::OID(oid) => Ok(oid),
+    _ => Err(BerError::BerTypeError),
+});
+impl_from_ber!(&'a [u8], BerTag::OctetString, |c: BerObjectContent<'a>| {
+    match c {
+        BerObjectContent::OctetString(Cow::Borrowed(s)) => Ok(s),
+        // constructed OCTET STRING cannot be borrowed
+        _ => Err(BerError::BerTypeError),
+    }
+});
+impl_from_ber!(&'a str, BerTag::Utf8String, |c: BerObjectContent<'a>| {
+    match c {
+        BerObjectContent::UTF8String(Cow::Borrowed(s)) => Ok(s),
+        _ => Err(BerError::BerTypeError),
+    }
+});
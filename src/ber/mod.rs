@@ -1,17 +1,37 @@
 //! Basic Encoding Rules (BER) objects and parser
 
 mod ber;
+mod builder;
+mod common;
+mod external;
+mod from_ber;
 mod multi;
 mod parser;
 mod print;
+mod registry;
+#[cfg(feature = "serde")]
+mod serde;
 #[cfg(feature = "serialize")]
 mod serialize;
+mod stream;
 mod tagged;
+mod template;
+mod time;
+mod visit;
 
 pub use crate::ber::ber::*;
+pub use crate::ber::builder::*;
+pub use crate::ber::common::*;
+pub use crate::ber::external::*;
+pub use crate::ber::from_ber::*;
 pub use crate::ber::multi::*;
 pub use crate::ber::parser::*;
 pub use crate::ber::print::*;
+pub use crate::ber::registry::*;
 #[cfg(feature = "serialize")]
 pub use crate::ber::serialize::*;
+pub use crate::ber::stream::*;
 pub use crate::ber::tagged::*;
+pub use crate::ber::template::*;
+pub use crate::ber::time::*;
+pub use crate::ber::visit::*;
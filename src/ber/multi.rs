@@ -1,8 +1,8 @@
 use crate::ber::*;
 use crate::error::*;
+use alloc::vec::Vec;
 use nom::bytes::complete::take;
-use nom::combinator::{all_consuming, complete, cut, map};
-use nom::multi::many0;
+use nom::combinator::map;
 use nom::{Err, IResult};
 
 /// Parse a SEQUENCE OF object
@@ -68,8 +68,34 @@ pub fn parse_ber_sequence_of_v<'a, T, F>(f: F) -> impl Fn(&'a [u8]) -> BerResult
 where
     F: Fn(&'a [u8]) -> BerResult<T>,
 {
-    let subparser = all_consuming(many0(complete(cut(f))));
-    parse_ber_sequence_defined_g(move |_, data| subparser(data))
+    parse_ber_sequence_defined_g(move |_, data| parse_elements(data, &f))
+}
+
+/// Apply `f` until `data` is empty, for the elements of a SEQUENCE OF or SET OF
+///
+/// Errors of the elements are returned as `Failure`. A truncated last element (or an element
+/// which does not consume input) returns `BerError::InvalidLength`.
+fn parse_elements<'a, T, F>(data: &'a [u8], f: &F) -> BerResult<'a, Vec<T>>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, T>,
+{
+    let mut v = Vec::new();
+    let mut i = data;
+    while !i.is_empty() {
+        match f(i) {
+            Ok((rem, _)) if rem.len() == i.len() => {
+                return Err(Err::Error(BerError::InvalidLength));
+            }
+            Ok((rem, obj)) => {
+                v.push(obj);
+                i = rem;
+            }
+            Err(Err::Error(e)) => return Err(Err::Failure(e)),
+            Err(Err::Incomplete(_)) => return Err(Err::Error(BerError::InvalidLength)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok((i, v))
 }
 
 /// Parse a defined sequence of DER elements (function version)
@@ -145,7 +171,7 @@ where
 /// # ];
 /// # let expected  = BerObject::from_seq(vec![
 /// #     BerObject::from_int_slice(b"\x01\x00\x01"),
-/// #     BerObject::from_obj(BerObjectContent::OctetString(b"\x01\x00\x00")),
+/// #     BerObject::from_octetstring(b"\x01\x00\x00"),
 /// # ]);
 /// # assert_eq!(localparse_seq(&bytes), Ok((empty, expected)));
 /// let (rem, v) = localparse_seq(&bytes).expect("parsing failed");
@@ -228,6 +254,117 @@ where
     })
 }
 
+/// Iterator over the children of a constructed object, parsing them lazily
+///
+/// Each call to `next` parses one child object (recursively), and returns the parsing result.
+/// The iteration stops when the content is exhausted, or after the first error.
+///
+/// See [`parse_ber_sequence_iter`](fn.parse_ber_sequence_iter.html).
+#[derive(Debug)]
+pub struct SequenceIterator<'a> {
+    data: &'a [u8],
+    // end-of-content octets terminating the content (empty for definite length)
+    eoc: &'a [u8],
+    end_of_content: bool,
+    max_depth: usize,
+}
+
+impl<'a> SequenceIterator<'a> {
+    /// Create an iterator over the objects encoded in `data` (the content of a definite-length
+    /// constructed object)
+    pub fn new(data: &'a [u8]) -> Self {
+        SequenceIterator {
+            data,
+            eoc: &[],
+            end_of_content: false,
+            max_depth: MAX_RECURSION - 1,
+        }
+    }
+
+    /// If `true`, also return the end-of-content object terminating indefinite-length content
+    ///
+    /// By default, the end-of-content marker is consumed silently. This can be used by tools
+    /// which must observe (or reproduce) the exact structure of the encoding.
+    ///
+    /// ```rust
+    /// # use der_parser::ber::{parse_ber_sequence_iter, BerObjectContent};
+    /// let bytes = [0x30, 0x80, 0x02, 0x01, 0x01, 0x00, 0x00];
+    /// let (_, iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    /// let v = iter.with_end_of_content(true).collect::<Result<Vec<_>, _>>().expect("parsing failed");
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v[1].1.content, BerObjectContent::EndOfContent);
+    /// ```
+    pub fn with_end_of_content(self, end_of_content: bool) -> Self {
+        SequenceIterator {
+            end_of_content,
+            ..self
+        }
+    }
+}
+
+impl<'a> Iterator for SequenceIterator<'a> {
+    type Item = BerResult<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            if self.end_of_content && !self.eoc.is_empty() {
+                let eoc = self.eoc;
+                self.eoc = &[];
+                return Some(parse_ber_endofcontent(eoc));
+            }
+            return None;
+        }
+        match parse_ber_recursive(self.data, self.max_depth) {
+            Ok((rem, obj)) => {
+                self.data = rem;
+                Some(Ok((rem, obj)))
+            }
+            Err(e) => {
+                self.data = &[];
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Parse the header of a SEQUENCE object, and return an iterator over its children
+///
+/// Unlike `parse_ber_sequence`, children are not parsed nor stored in a `Vec`: they are parsed
+/// one by one when calling `next` on the iterator. Errors in children are only reported by the
+/// iterator.
+///
+/// The remaining bytes point *after* the sequence.
+///
+/// ```rust
+/// # use der_parser::ber::parse_ber_sequence_iter;
+/// let bytes = [ 0x30, 0x0a,
+///               0x02, 0x03, 0x01, 0x00, 0x01,
+///               0x02, 0x03, 0x01, 0x00, 0x00,
+/// ];
+/// let (rem, mut iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+/// assert!(rem.is_empty());
+/// // only the first child is parsed
+/// let (_, first) = iter.next().expect("empty sequence").expect("parsing failed");
+/// assert_eq!(first.as_u32(), Ok(0x10001));
+/// ```
+pub fn parse_ber_sequence_iter(i: &[u8]) -> BerResult<SequenceIterator<'_>> {
+    let (content, hdr) = ber_read_element_header(i)?;
+    if hdr.tag != BerTag::Sequence {
+        return Err(Err::Error(BerError::BerTypeError));
+    }
+    if !hdr.is_constructed() {
+        return Err(Err::Error(BerError::ConstructExpected));
+    }
+    let (rem, data) = ber_get_object_content(content, &hdr, MAX_RECURSION)?;
+    // for indefinite length, the end-of-content octets follow the data
+    let eoc = &content[data.len()..content.len() - rem.len()];
+    let iter = SequenceIterator {
+        eoc,
+        ..SequenceIterator::new(data)
+    };
+    Ok((rem, iter))
+}
+
 /// Parse a SET OF object
 ///
 /// Given a subparser for a BER type, parse a set of identical objects.
@@ -291,8 +428,7 @@ pub fn parse_ber_set_of_v<'a, T, F>(f: F) -> impl Fn(&'a [u8]) -> BerResult<Vec<
 where
     F: Fn(&'a [u8]) -> BerResult<T>,
 {
-    let subparser = all_consuming(many0(complete(cut(f))));
-    parse_ber_set_defined_g(move |_, data| subparser(data))
+    parse_ber_set_defined_g(move |_, data| parse_elements(data, &f))
 }
 
 /// Parse a defined set of DER elements (function version)
@@ -368,7 +504,7 @@ where
 /// # ];
 /// # let expected  = BerObject::from_set(vec![
 /// #     BerObject::from_int_slice(b"\x01\x00\x01"),
-/// #     BerObject::from_obj(BerObjectContent::OctetString(b"\x01\x00\x00")),
+/// #     BerObject::from_octetstring(b"\x01\x00\x00"),
 /// # ]);
 /// # assert_eq!(localparse_set(&bytes), Ok((empty, expected)));
 /// let (rem, v) = localparse_set(&bytes).expect("parsing failed");
@@ -459,6 +595,8 @@ where
 /// parsed are ignored.
 /// The nom combinator `all_consuming` can be used to ensure all the content is parsed.
 ///
+/// If the object has an indefinite length, the end-of-content marker is not passed to the parser.
+///
 /// This function is mostly intended for structured objects, but can be used for any valid BER
 /// object.
 ///
@@ -512,17 +650,18 @@ where
     F: Fn(BerObjectHeader<'a>, &'a [u8]) -> IResult<&'a [u8], O, E>,
     E: nom::error::ParseError<&'a [u8]> + From<BerError>,
 {
-    move |i: &[u8]| {
-        let (i, hdr) = ber_read_element_header(i).map_err(nom::Err::convert)?;
-        let (i, data) = match hdr.len {
+    move |input: &[u8]| {
+        let (i, hdr) = ber_read_element_header(input).map_err(nom::Err::convert)?;
+        // the end-of-content marker is not part of the content
+        let (rem, data) = match hdr.len {
             BerSize::Definite(len) => take(len)(i)?,
             BerSize::Indefinite => {
-                let (_, len) =
-                    ber_skip_object_content_get_size(i, &hdr).map_err(nom::Err::convert)?;
-                take(len)(i)?
+                ber_get_object_content(i, &hdr, MAX_RECURSION).map_err(nom::Err::convert)?
             }
         };
+        let raw_header = &input[..input.len() - i.len()];
+        let hdr = hdr.with_raw_encoding(raw_header, &i[..i.len() - rem.len()]);
         let (_rest, v) = f(hdr, data)?;
-        Ok((i, v))
+        Ok((rem, v))
     }
 }
@@ -1,14 +1,15 @@
 use crate::ber::*;
 use crate::error::*;
 use crate::oid::*;
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
 use nom::bytes::streaming::take;
-use nom::combinator::{complete, map, map_res, verify};
-use nom::multi::{many0, many_till};
+use nom::combinator::map;
+use nom::multi::many_till;
 use nom::number::streaming::be_u8;
 use nom::*;
-use rusticata_macros::{custom_check, parse_hex_to_u64};
-use std::borrow::Cow;
-use std::convert::TryFrom;
 
 /// Default maximum recursion limit
 pub const MAX_RECURSION: usize = 50;
@@ -16,36 +17,127 @@ pub const MAX_RECURSION: usize = 50;
 /// Default maximum object size (2^32)
 pub const MAX_OBJECT_SIZE: usize = 4_294_967_295;
 
+/// Maximum number of arcs of an object identifier
+///
+/// Longer OIDs are rejected with `BerError::InvalidLength`. Real-world OIDs have less than 20
+/// arcs.
+pub const MAX_OID_ARCS: usize = 128;
+
+/// Options for BER/DER parsers
+///
+/// The default values are the same as the ones used by `parse_ber` and `parse_der`.
+///
+/// ```rust
+/// use der_parser::ber::BerParserOptions;
+///
+/// // lower the limits for untrusted input
+/// let options = BerParserOptions::default()
+///     .with_max_depth(8)
+///     .with_max_size(4096);
+/// assert_eq!(options.max_depth, 8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BerParserOptions {
+    /// Maximum recursion depth for constructed objects
+    pub max_depth: usize,
+    /// Maximum number of bytes that can be read to parse an object (header and content,
+    /// including all children)
+    pub max_size: usize,
+}
+
+impl BerParserOptions {
+    /// Update the maximum recursion depth
+    #[inline]
+    pub fn with_max_depth(self, max_depth: usize) -> Self {
+        BerParserOptions { max_depth, ..self }
+    }
+
+    /// Update the maximum size of objects
+    #[inline]
+    pub fn with_max_size(self, max_size: usize) -> Self {
+        BerParserOptions { max_size, ..self }
+    }
+}
+
+impl Default for BerParserOptions {
+    fn default() -> Self {
+        BerParserOptions {
+            max_depth: MAX_RECURSION,
+            max_size: MAX_OBJECT_SIZE,
+        }
+    }
+}
+
+/// Apply parser `f`, reading at most `max_size` bytes
+///
+/// Objects declaring a larger length are rejected before reading their content.
+pub(crate) fn parse_with_max_size<'a, F>(i: &'a [u8], max_size: usize, f: F) -> BerResult<'a>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a>,
+{
+    let (_, hdr) = ber_read_element_header(i)?;
+    if let BerSize::Definite(l) = hdr.len {
+        custom_check!(i, l > max_size, BerError::InvalidLength)?;
+    }
+    parse_ber_within(max_size, f)(i)
+}
+
+/// Return true if the header is an end-of-content marker (`00 00`)
+///
+/// Only the universal primitive tag 0, encoded on a single byte, is an end-of-content marker: an
+/// empty context-specific object (`80 00`) or the high tag number form of tag 0 (`1f 00 00`) are
+/// regular objects.
+pub(crate) fn is_end_of_content(hdr: &BerObjectHeader) -> bool {
+    hdr.class == BerClass::Universal
+        && hdr.is_primitive()
+        && hdr.tag == BerTag::EndOfContent
+        && hdr.len == BerSize::Definite(0)
+        && hdr.raw_tag.as_ref().map(|t| t.as_ref()) == Some(&[0x00][..])
+}
+
 /// Skip object content, and return true if object was End-Of-Content
+///
+/// Nested objects with indefinite length are skipped recursively, up to `max_depth` levels.
 pub(crate) fn ber_skip_object_content<'a>(
     i: &'a [u8],
     hdr: &BerObjectHeader,
+    max_depth: usize,
 ) -> BerResult<'a, bool> {
     match hdr.len {
         BerSize::Definite(l) => {
-            if l == 0 && hdr.tag == BerTag::EndOfContent {
+            if is_end_of_content(hdr) {
                 return Ok((i, true));
             }
-            let (i, _) = take(l)(i)?;
-            Ok((i, false))
+            if i.len() < l {
+                return Err(Err::Incomplete(Needed::Size(l - i.len())));
+            }
+            Ok((&i[l..], false))
         }
         BerSize::Indefinite => {
-            // read objects until EndOfContent (00 00)
-            // this is recursive
-            let mut i = i;
-            loop {
-                let (i2, header2) = ber_read_element_header(i)?;
-                let (i3, eoc) = ber_skip_object_content(i2, &header2)?;
-                if eoc {
-                    // return false, since top object was not EndOfContent
-                    return Ok((i3, false));
-                }
-                i = i3;
-            }
+            let (i, _) = ber_skip_indefinite_content(i, max_depth)?;
+            // return false, since top object was not EndOfContent
+            Ok((i, false))
         }
     }
 }
 
+/// Skip the content of an indefinite length object, and return the length of the content
+/// (before the end-of-content marker)
+fn ber_skip_indefinite_content(i: &[u8], max_depth: usize) -> BerResult<usize> {
+    // read objects until EndOfContent (00 00)
+    // this is recursive
+    custom_check!(i, max_depth == 0, BerError::BerMaxDepth)?;
+    let mut rem = i;
+    loop {
+        let (i2, header2) = ber_read_element_header(rem)?;
+        let (i3, eoc) = ber_skip_object_content(i2, &header2, max_depth - 1)?;
+        if eoc {
+            return Ok((i3, i.offset(rem)));
+        }
+        rem = i3;
+    }
+}
+
 // /// Skip object, and return true if object was End-Of-Content
 // pub(crate) fn ber_skip_object(i: &[u8]) -> BerResult<bool> {
 //     let (i, header) = ber_read_element_header(i)?;
@@ -64,23 +156,35 @@ pub(crate) fn ber_skip_object_content<'a>(
 pub(crate) fn ber_skip_object_content_get_size<'a>(
     i: &'a [u8],
     hdr: &BerObjectHeader,
+    max_depth: usize,
 ) -> BerResult<'a, usize> {
     let start_i = i;
-    let (i, _) = ber_skip_object_content(i, hdr)?;
+    let (i, _) = ber_skip_object_content(i, hdr, max_depth)?;
     let len = start_i.offset(i);
     Ok((i, len))
 }
 
 /// Read object raw content (bytes)
+///
+/// If length is indefinite, the end-of-content marker is consumed but not included in the content.
 pub(crate) fn ber_get_object_content<'a>(
     i: &'a [u8],
     hdr: &BerObjectHeader,
+    max_depth: usize,
 ) -> BerResult<'a, &'a [u8]> {
-    let start_i = i;
-    let (i, _) = ber_skip_object_content(i, hdr)?;
-    let len = start_i.offset(i);
-    let (content, i) = start_i.split_at(len);
-    Ok((i, content))
+    match hdr.len {
+        BerSize::Definite(_) => {
+            let start_i = i;
+            let (i, _) = ber_skip_object_content(i, hdr, max_depth)?;
+            let len = start_i.offset(i);
+            Ok((i, &start_i[..len]))
+        }
+        BerSize::Indefinite => {
+            // the content stops at the start of the end-of-content marker
+            let (rem, len) = ber_skip_indefinite_content(i, max_depth)?;
+            Ok((rem, &i[..len]))
+        }
+    }
 }
 
 /// Try to parse input bytes as u64
@@ -97,6 +201,146 @@ pub(crate) fn bytes_to_u64(s: &[u8]) -> Result<u64, BerError> {
     Ok(u)
 }
 
+/// Remove redundant leading sign octets (allowed in BER, but not in DER)
+#[inline]
+pub(crate) fn trim_integer_padding(s: &[u8]) -> &[u8] {
+    let mut s = s;
+    while s.len() > 1 && ((s[0] == 0 && s[1] & 0x80 == 0) || (s[0] == 0xff && s[1] & 0x80 != 0)) {
+        s = &s[1..];
+    }
+    s
+}
+
+/// Try to parse input bytes as a signed (two's complement) i64
+#[inline]
+pub(crate) fn bytes_to_i64(s: &[u8]) -> Result<i64, BerError> {
+    let s = trim_integer_padding(s);
+    if s.len() > 8 {
+        return Err(BerError::IntegerTooLarge);
+    }
+    let mut u: u64 = match s.first() {
+        Some(&b) if b & 0x80 != 0 => !0,
+        _ => 0,
+    };
+    for &c in s {
+        u <<= 8;
+        u |= u64::from(c);
+    }
+    Ok(u as i64)
+}
+
+/// Try to parse input bytes as a signed (two's complement) i128
+#[inline]
+pub(crate) fn bytes_to_i128(s: &[u8]) -> Result<i128, BerError> {
+    let s = trim_integer_padding(s);
+    if s.len() > 16 {
+        return Err(BerError::IntegerTooLarge);
+    }
+    let mut u: u128 = match s.first() {
+        Some(&b) if b & 0x80 != 0 => !0,
+        _ => 0,
+    };
+    for &c in s {
+        u <<= 8;
+        u |= u128::from(c);
+    }
+    Ok(u as i128)
+}
+
+/// Return 2^`e`, for `e` in the range of normal values (`powi` is not available without std)
+fn pow2(e: i32) -> f64 {
+    debug_assert!(e >= -1022 && e <= 1023);
+    f64::from_bits(((e + 1023) as u64) << 52)
+}
+
+/// Multiply `x` by 2^`e`, without overflowing intermediate values
+fn ldexp(mut x: f64, mut e: i64) -> f64 {
+    while e > 1000 && x.is_finite() {
+        x *= pow2(1000);
+        e -= 1000;
+    }
+    while e < -1000 && x != 0.0 {
+        x *= pow2(-1000);
+        e += 1000;
+    }
+    if e > 1000 || e < -1000 {
+        // x is either infinite or zero
+        return x;
+    }
+    x * pow2(e as i32)
+}
+
+/// Decode the contents octets of a REAL value (X.690 8.5)
+pub(crate) fn decode_real(s: &[u8]) -> Result<f64, BerError> {
+    // 8.5.2: if the real value is plus zero, there shall be no contents octets
+    let first = match s.first() {
+        Some(&b) => b,
+        None => return Ok(0.0),
+    };
+    if first & 0x80 != 0 {
+        // binary encoding (8.5.7)
+        let sign = if first & 0x40 != 0 { -1.0 } else { 1.0 };
+        let base_log2 = match (first >> 4) & 0b11 {
+            0b00 => 1,
+            0b01 => 3,
+            0b10 => 4,
+            _ => return Err(BerError::BerValueError), // reserved (8.5.7.2)
+        };
+        let scale = i64::from((first >> 2) & 0b11);
+        let (exp_len, rem) = match first & 0b11 {
+            0b11 => match s.get(1) {
+                Some(&l) => (usize::from(l), &s[2..]),
+                None => return Err(BerError::InvalidLength),
+            },
+            l => (usize::from(l) + 1, &s[1..]),
+        };
+        if exp_len == 0 || rem.len() <= exp_len {
+            return Err(BerError::InvalidLength);
+        }
+        let (exp, mantissa) = rem.split_at(exp_len);
+        let exp = bytes_to_i64(exp)?;
+        let n = mantissa
+            .iter()
+            .fold(0.0, |acc, &b| acc * 256.0 + f64::from(b));
+        let e = exp.saturating_mul(base_log2).saturating_add(scale);
+        Ok(sign * ldexp(n, e))
+    } else if first & 0x40 == 0 {
+        // decimal encoding (8.5.8), using ISO 6093 NR1, NR2 or NR3 forms
+        let s = core::str::from_utf8(&s[1..]).or(Err(BerError::BerValueError))?;
+        let valid_chars = s.bytes().all(|b| match b {
+            b'0'..=b'9' | b' ' | b'+' | b'-' | b'.' | b',' | b'e' | b'E' => true,
+            _ => false,
+        });
+        let has_mark = s.contains(|c| c == '.' || c == ',');
+        let has_exponent = s.contains(|c| c == 'e' || c == 'E');
+        let valid_form = match first & 0x3f {
+            1 => !has_mark && !has_exponent,
+            2 => !has_exponent,
+            3 => has_exponent,
+            _ => false,
+        };
+        if !valid_chars || !valid_form {
+            return Err(BerError::BerValueError);
+        }
+        s.trim_start_matches(' ')
+            .replace(',', ".")
+            .parse::<f64>()
+            .or(Err(BerError::BerValueError))
+    } else {
+        // special real values (8.5.9)
+        if s.len() != 1 {
+            return Err(BerError::InvalidLength);
+        }
+        match first {
+            0x40 => Ok(core::f64::INFINITY),
+            0x41 => Ok(core::f64::NEG_INFINITY),
+            0x42 => Ok(core::f64::NAN),
+            0x43 => Ok(-0.0),
+            _ => Err(BerError::BerValueError),
+        }
+    }
+}
+
 /// Try to parse an input bit string as u64.
 ///
 /// Note: this is for the primitive BER/DER encoding only, the
@@ -107,7 +351,7 @@ pub(crate) fn bitstring_to_u64(
     padding_bits: usize,
     data: &BitStringObject,
 ) -> Result<u64, BerError> {
-    let raw_bytes = data.data;
+    let raw_bytes: &[u8] = &data.data;
     let bit_size = raw_bytes.len() * 8 - padding_bits;
     if bit_size > 64 {
         return Err(BerError::IntegerTooLarge);
@@ -139,6 +383,8 @@ pub(crate) fn parse_identifier(i: &[u8]) -> BerResult<(u8, u8, u32, &[u8])> {
                 // With tag defined as u32 the most we can fit in is four tag bytes.
                 // (X.690 doesn't actually specify maximum tag width.)
                 custom_check!(i, tag_byte_count > 5, BerError::InvalidTag)?;
+                // the next shift must not drop bits
+                custom_check!(i, c >> 25 != 0, BerError::InvalidTag)?;
 
                 c = (c << 7) | (u32::from(i[tag_byte_count]) & 0x7f);
                 let done = i[tag_byte_count] & 0x80 == 0;
@@ -201,7 +447,11 @@ pub fn ber_read_element_header(i: &[u8]) -> BerResult<BerObjectHeader> {
             if l1 == 0b0111_1111 {
                 return Err(::nom::Err::Error(BerError::InvalidTag));
             }
-            let (i3, llen) = take(l1)(i2)?;
+            let l1 = usize::from(l1);
+            if i2.len() < l1 {
+                return Err(Err::Incomplete(Needed::Size(l1 - i2.len())));
+            }
+            let (llen, i3) = i2.split_at(l1);
             match bytes_to_u64(llen) {
                 Ok(l) => {
                     let l =
@@ -218,6 +468,78 @@ pub fn ber_read_element_header(i: &[u8]) -> BerResult<BerObjectHeader> {
     Ok((i3, hdr))
 }
 
+/// Read an object header, without consuming input
+///
+/// This is the same as `ber_read_element_header`, except that the returned input is the
+/// original input (not advanced past the header). This is useful to select a parser depending
+/// on the tag, for ex. for CHOICE or OPTIONAL values.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::{peek_ber_header, BerTag};
+/// #
+/// let bytes = &[0x02, 0x03, 0x01, 0x00, 0x01];
+/// let (i, hdr) = peek_ber_header(bytes).expect("could not read header");
+///
+/// assert_eq!(i, bytes);
+/// assert_eq!(hdr.tag, BerTag::Integer);
+/// ```
+pub fn peek_ber_header(i: &[u8]) -> BerResult<BerObjectHeader> {
+    let (_, hdr) = ber_read_element_header(i)?;
+    Ok((i, hdr))
+}
+
+/// Read the header of the next object, and return it with the raw content
+///
+/// The content is not parsed: this can be used to implement a custom decoding of the object,
+/// using the header logic of this crate (including high tag numbers and indefinite length).
+///
+/// If the length is indefinite, the end of the content is found by skipping the nested objects,
+/// and the end-of-content marker is consumed but not included in the returned content.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::{parse_ber_any, BerTag};
+/// #
+/// let bytes = &[0x30, 0x80, 0x02, 0x01, 0x05, 0x00, 0x00, 0xff];
+/// let (rem, (hdr, content)) = parse_ber_any(bytes).expect("parsing failed");
+///
+/// assert_eq!(hdr.tag, BerTag::Sequence);
+/// assert_eq!(content, &[0x02, 0x01, 0x05]);
+/// assert_eq!(rem, &[0xff]);
+/// ```
+pub fn parse_ber_any(i: &[u8]) -> BerResult<(BerObjectHeader, &[u8])> {
+    let (i, hdr) = ber_read_element_header(i)?;
+    let (i, content) = ber_get_object_content(i, &hdr, MAX_RECURSION)?;
+    Ok((i, (hdr, content)))
+}
+
+/// Skip the next object, without parsing its content
+///
+/// The header is read to find the end of the object. If the length is indefinite, the nested
+/// objects are skipped (with a maximum depth of `MAX_RECURSION`) until the end-of-content marker.
+/// Nothing is allocated: this is useful to skip uninteresting elements, for ex. when looking for
+/// a specific element in a SEQUENCE.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::{parse_ber_integer, skip_ber};
+/// #
+/// let bytes = &[0x30, 0x80, 0x04, 0x01, 0xaa, 0x00, 0x00, 0x02, 0x01, 0x05];
+/// let (rem, ()) = skip_ber(bytes).expect("parsing failed");
+/// let (_, obj) = parse_ber_integer(rem).expect("parsing failed");
+///
+/// assert_eq!(obj.as_u32(), Ok(5));
+/// ```
+pub fn skip_ber(i: &[u8]) -> BerResult<()> {
+    let (i, hdr) = ber_read_element_header(i)?;
+    let (i, _) = ber_skip_object_content(i, &hdr, MAX_RECURSION)?;
+    Ok((i, ()))
+}
+
 #[inline]
 fn ber_read_content_eoc(i: &[u8]) -> BerResult<BerObjectContent> {
     Ok((i, BerObjectContent::EndOfContent))
@@ -234,7 +556,7 @@ fn ber_read_content_bool(i: &[u8]) -> BerResult<BerObjectContent> {
 
 #[inline]
 fn ber_read_content_integer(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::Integer)(i)
+    map(take(len), |s| BerObjectContent::Integer(Cow::Borrowed(s)))(i)
 }
 
 #[inline]
@@ -242,16 +564,67 @@ fn ber_read_content_bitstring(i: &[u8], len: usize) -> BerResult<BerObjectConten
     custom_check!(i, len == 0, BerError::InvalidLength)?;
 
     let (i, ignored_bits) = be_u8(i)?;
+    custom_check!(i, ignored_bits > 7, BerError::BerValueError)?;
     let (i, data) = take(len - 1)(i)?;
-    Ok((
-        i,
-        BerObjectContent::BitString(ignored_bits, BitStringObject { data }),
-    ))
+    let b = BitStringObject::new(ignored_bits, data)?;
+    Ok((i, BerObjectContent::BitString(b)))
 }
 
 #[inline]
 fn ber_read_content_octetstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::OctetString)(i)
+    map(take(len), |s| {
+        BerObjectContent::OctetString(Cow::Borrowed(s))
+    })(i)
+}
+
+// X.690 8.6.4: segments of a constructed encoding are concatenated, only the last segment can
+// have unused bits
+fn ber_read_content_bitstring_constructed(
+    i: &[u8],
+    len: BerSize,
+    max_depth: usize,
+) -> BerResult<BerObjectContent> {
+    let (rem, content) = ber_read_content_sequence(i, len, max_depth)?;
+    let segments = content.as_sequence()?;
+    let mut data = Vec::new();
+    let mut ignored_bits = 0;
+    for obj in segments {
+        custom_check!(i, ignored_bits != 0, BerError::BerValueError)?;
+        match obj.content {
+            BerObjectContent::BitString(ref b) => {
+                data.extend_from_slice(&b.data);
+                ignored_bits = b.unused_bits();
+            }
+            BerObjectContent::BitStringConstructed(u, ref v) => {
+                data.extend_from_slice(v);
+                ignored_bits = u;
+            }
+            _ => return Err(Err::Error(BerError::InvalidTag)),
+        }
+    }
+    Ok((
+        rem,
+        BerObjectContent::BitStringConstructed(ignored_bits, data),
+    ))
+}
+
+// X.690 8.7.3: segments of a constructed encoding are concatenated
+fn ber_read_content_octetstring_constructed(
+    i: &[u8],
+    len: BerSize,
+    max_depth: usize,
+) -> BerResult<BerObjectContent> {
+    let (rem, content) = ber_read_content_sequence(i, len, max_depth)?;
+    let segments = content.as_sequence()?;
+    let mut data = Vec::new();
+    for obj in segments {
+        match obj.content {
+            BerObjectContent::OctetString(ref s) => data.extend_from_slice(s),
+            BerObjectContent::OctetStringConstructed(ref v) => data.extend_from_slice(v),
+            _ => return Err(Err::Error(BerError::InvalidTag)),
+        }
+    }
+    Ok((rem, BerObjectContent::OctetStringConstructed(data)))
 }
 
 #[inline]
@@ -262,35 +635,115 @@ fn ber_read_content_null(i: &[u8]) -> BerResult<BerObjectContent> {
 fn ber_read_content_oid(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
     custom_check!(i, len == 0, BerError::InvalidLength)?;
 
-    let (i1, oid) = verify(take(len), |os: &[u8]| os.last().unwrap() >> 7 == 0u8)(i)?;
+    let (i1, oid) = take(len)(i)?;
+    // the last byte must terminate an arc
+    custom_check!(i, oid[len - 1] >> 7 != 0, BerError::BerValueError)?;
+    // sub-identifiers must be minimally encoded, so the first one (encoding the first two arcs)
+    // always decodes to a root arc 0, 1 or 2 (X.690 8.19.2 and 8.19.4)
+    custom_check!(i, oid_has_padding(oid), BerError::BerValueError)?;
+    custom_check!(
+        i,
+        oid_arc_count(oid, false) > MAX_OID_ARCS,
+        BerError::InvalidLength
+    )?;
 
     let obj = BerObjectContent::OID(Oid::new(Cow::Borrowed(oid)));
     Ok((i1, obj))
 }
 
+/// Return true if a sub-identifier of an encoded object identifier starts with the padding byte
+/// `0x80` (X.690 8.19.2)
 #[inline]
+fn oid_has_padding(oid: &[u8]) -> bool {
+    // the first byte of a sub-identifier follows a byte with bit 8 cleared
+    let mut start = true;
+    for &b in oid {
+        if start && b == 0x80 {
+            return true;
+        }
+        start = b & 0x80 == 0;
+    }
+    false
+}
+
+/// Number of arcs of an encoded object identifier (the first subidentifier of absolute OIDs
+/// encodes two arcs)
+#[inline]
+fn oid_arc_count(oid: &[u8], relative: bool) -> usize {
+    let n = oid.iter().filter(|&&b| b & 0x80 == 0).count();
+    if relative {
+        n
+    } else {
+        n + 1
+    }
+}
+
+fn ber_read_content_real(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
+    let (i, data) = take(len)(i)?;
+    let f = decode_real(data)?;
+    Ok((i, BerObjectContent::Real(f)))
+}
+
+/// Read the content of an ENUMERATED value
+///
+/// The encoding is the same as INTEGER (X.690 8.4), so the value is a signed integer. It is
+/// stored as its 64-bits two's complement representation.
 fn ber_read_content_enum(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    parse_hex_to_u64!(i, len).map(|(rem, i)| (rem, BerObjectContent::Enum(i)))
+    custom_check!(i, len == 0, BerError::InvalidLength)?;
+    let (i, data) = take(len)(i)?;
+    let v = bytes_to_i64(data)?;
+    Ok((i, BerObjectContent::Enum(v as u64)))
 }
 
 fn ber_read_content_utf8string(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map_res(take(len), |bytes| {
-        std::str::from_utf8(bytes)
-            .map(|s| BerObjectContent::UTF8String(s))
-            .map_err(|_| BerError::BerValueError)
-    })(i)
+    let (i, bytes) = take(len)(i)?;
+    let s = core::str::from_utf8(bytes).or(Err(BerError::BerValueError))?;
+    Ok((i, BerObjectContent::UTF8String(Cow::Borrowed(s))))
 }
 
 fn ber_read_content_relativeoid(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
     custom_check!(i, len == 0, BerError::InvalidLength)?;
 
-    let (i1, oid) = verify(take(len), |os: &[u8]| os.last().unwrap() >> 7 == 0u8)(i)?;
+    let (i1, oid) = take(len)(i)?;
+    // the last byte must terminate an arc
+    custom_check!(i, oid[len - 1] >> 7 != 0, BerError::BerValueError)?;
+    custom_check!(i, oid_has_padding(oid), BerError::BerValueError)?;
+    custom_check!(
+        i,
+        oid_arc_count(oid, true) > MAX_OID_ARCS,
+        BerError::InvalidLength
+    )?;
 
     let obj = BerObjectContent::RelativeOID(Oid::new_relative(Cow::Borrowed(oid)));
     Ok((i1, obj))
 }
 
-fn ber_read_content_sequence(
+/// Parse the children of a constructed object with definite length
+///
+/// The children must exactly fill `data`: if a child cannot be parsed, its error is returned. If
+/// the last child is truncated (its encoding requires more bytes than available), or if the
+/// content is padded with end-of-content bytes, `BerError::InvalidLength` is returned.
+fn ber_read_children(data: &[u8], max_depth: usize) -> Result<Vec<BerObject>, Err<BerError>> {
+    let mut l = Vec::new();
+    let mut data = data;
+    while !data.is_empty() {
+        match parse_ber_recursive(data, max_depth) {
+            Ok((_, ref obj)) if is_end_of_content(&obj.header) => {
+                // end-of-content is only valid for indefinite length objects
+                return Err(Err::Error(BerError::InvalidLength));
+            }
+            Ok((rem, obj)) => {
+                l.push(obj);
+                data = rem;
+            }
+            Err(Err::Incomplete(_)) => return Err(Err::Error(BerError::InvalidLength)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(l)
+}
+
+pub(crate) fn ber_read_content_sequence(
     i: &[u8],
     len: BerSize,
     max_depth: usize,
@@ -299,8 +752,7 @@ fn ber_read_content_sequence(
     match len {
         BerSize::Definite(len) => {
             let (i, data) = take(len)(i)?;
-            let (_, l) = many0(complete(r_parse_ber(max_depth - 1)))(data)?;
-            // trailing bytes are ignored
+            let l = ber_read_children(data, max_depth - 1)?;
             Ok((i, BerObjectContent::Sequence(l)))
         }
         BerSize::Indefinite => {
@@ -317,8 +769,7 @@ fn ber_read_content_set(i: &[u8], len: BerSize, max_depth: usize) -> BerResult<B
     match len {
         BerSize::Definite(len) => {
             let (i, data) = take(len)(i)?;
-            let (_, l) = many0(complete(r_parse_ber(max_depth - 1)))(data)?;
-            // trailing bytes are ignored
+            let l = ber_read_children(data, max_depth - 1)?;
             Ok((i, BerObjectContent::Set(l)))
         }
         BerSize::Indefinite => {
@@ -330,96 +781,94 @@ fn ber_read_content_set(i: &[u8], len: BerSize, max_depth: usize) -> BerResult<B
     }
 }
 
+/// Return true if the byte is in the NumericString charset (X.680 41.2)
+// Argument must be a reference, because of the .iter().all(F) calls
+#[allow(clippy::trivially_copy_pass_by_ref)]
+pub(crate) fn is_numeric_char(b: &u8) -> bool {
+    match *b {
+        b'0'..=b'9' | b' ' => true,
+        _ => false,
+    }
+}
+
+/// Return true if the byte is in the VisibleString charset (X.680 41.4)
+#[allow(clippy::trivially_copy_pass_by_ref)]
+pub(crate) fn is_visible_char(b: &u8) -> bool {
+    0x20 <= *b && *b <= 0x7e
+}
+
+/// Return true if the byte is in the PrintableString charset (X.680 41.4)
+#[allow(clippy::trivially_copy_pass_by_ref)]
+pub(crate) fn is_printable_char(b: &u8) -> bool {
+    match *b {
+        b'a'..=b'z'
+        | b'A'..=b'Z'
+        | b'0'..=b'9'
+        | b' '
+        | b'\''
+        | b'('
+        | b')'
+        | b'+'
+        | b','
+        | b'-'
+        | b'.'
+        | b'/'
+        | b':'
+        | b'='
+        | b'?' => true,
+        _ => false,
+    }
+}
+
 fn ber_read_content_numericstring<'a>(i: &'a [u8], len: usize) -> BerResult<BerObjectContent<'a>> {
-    // Argument must be a reference, because of the .iter().all(F) call below
-    #[allow(clippy::trivially_copy_pass_by_ref)]
-    fn is_numeric(b: &u8) -> bool {
-        match *b {
-            b'0'..=b'9' | b' ' => true,
-            _ => false,
-        }
+    let (i, bytes) = take(len)(i)?;
+    if !bytes.iter().all(is_numeric_char) {
+        return Err(Err::Error(BerError::StringInvalidCharset));
     }
-    map_res!(i, take!(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(is_numeric) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map_err(|_| BerError::BerValueError)
-            .map(|s| BerObjectContent::NumericString(s))
-    })
+    let s = core::str::from_utf8(bytes).or(Err(Err::Error(BerError::StringInvalidCharset)))?;
+    Ok((i, BerObjectContent::NumericString(Cow::Borrowed(s))))
 }
 
 fn ber_read_content_visiblestring<'a>(i: &'a [u8], len: usize) -> BerResult<BerObjectContent<'a>> {
-    // Argument must be a reference, because of the .iter().all(F) call below
-    #[allow(clippy::trivially_copy_pass_by_ref)]
-    fn is_visible(b: &u8) -> bool {
-        0x20 <= *b && *b <= 0x7f
+    let (i, bytes) = take(len)(i)?;
+    if !bytes.iter().all(is_visible_char) {
+        return Err(Err::Error(BerError::StringInvalidCharset));
     }
-    map_res!(i, take!(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(is_visible) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map(|s| BerObjectContent::VisibleString(s))
-            .map_err(|_| BerError::BerValueError)
-    })
+    let s = core::str::from_utf8(bytes).or(Err(Err::Error(BerError::StringInvalidCharset)))?;
+    Ok((i, BerObjectContent::VisibleString(Cow::Borrowed(s))))
 }
 
 fn ber_read_content_printablestring<'a>(
     i: &'a [u8],
     len: usize,
 ) -> BerResult<BerObjectContent<'a>> {
-    // Argument must be a reference, because of the .iter().all(F) call below
-    #[allow(clippy::trivially_copy_pass_by_ref)]
-    fn is_printable(b: &u8) -> bool {
-        match *b {
-            b'a'..=b'z'
-            | b'A'..=b'Z'
-            | b'0'..=b'9'
-            | b' '
-            | b'\''
-            | b'('
-            | b')'
-            | b'+'
-            | b','
-            | b'-'
-            | b'.'
-            | b'/'
-            | b':'
-            | b'='
-            | b'?' => true,
-            _ => false,
-        }
+    let (i, bytes) = take(len)(i)?;
+    if !bytes.iter().all(is_printable_char) {
+        return Err(Err::Error(BerError::StringInvalidCharset));
     }
-    map_res!(i, take!(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(is_printable) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map(|s| BerObjectContent::PrintableString(s))
-            .map_err(|_| BerError::BerValueError)
-    })
+    let s = core::str::from_utf8(bytes).or(Err(Err::Error(BerError::StringInvalidCharset)))?;
+    Ok((i, BerObjectContent::PrintableString(Cow::Borrowed(s))))
 }
 
 #[inline]
 fn ber_read_content_t61string(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::T61String)(i)
+    map(take(len), |s| BerObjectContent::T61String(Cow::Borrowed(s)))(i)
 }
 
 #[inline]
 fn ber_read_content_videotexstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::VideotexString)(i)
+    map(take(len), |s| {
+        BerObjectContent::VideotexString(Cow::Borrowed(s))
+    })(i)
 }
 
 fn ber_read_content_ia5string<'a>(i: &'a [u8], len: usize) -> BerResult<BerObjectContent<'a>> {
-    map_res(take(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(u8::is_ascii) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map(BerObjectContent::IA5String)
-            .map_err(|_| BerError::BerValueError)
-    })(i)
+    let (i, bytes) = take(len)(i)?;
+    if !bytes.iter().all(u8::is_ascii) {
+        return Err(Err::Error(BerError::StringInvalidCharset));
+    }
+    let s = core::str::from_utf8(bytes).or(Err(Err::Error(BerError::StringInvalidCharset)))?;
+    Ok((i, BerObjectContent::IA5String(Cow::Borrowed(s))))
 }
 
 fn ber_read_content_utctime<'a>(i: &'a [u8], len: usize) -> BerResult<BerObjectContent<'a>> {
@@ -428,14 +877,10 @@ fn ber_read_content_utctime<'a>(i: &'a [u8], len: usize) -> BerResult<BerObjectC
     fn is_visible(b: &u8) -> bool {
         0x20 <= *b && *b <= 0x7f
     }
-    map_res!(i, take!(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(is_visible) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map(|s| BerObjectContent::UTCTime(s))
-            .map_err(|_| BerError::BerValueError)
-    })
+    let (i, bytes) = take(len)(i)?;
+    custom_check!(i, !bytes.iter().all(is_visible), BerError::BerValueError)?;
+    let s = core::str::from_utf8(bytes).or(Err(BerError::BerValueError))?;
+    Ok((i, BerObjectContent::UTCTime(Cow::Borrowed(s))))
 }
 
 fn ber_read_content_generalizedtime<'a>(
@@ -447,39 +892,43 @@ fn ber_read_content_generalizedtime<'a>(
     fn is_visible(b: &u8) -> bool {
         0x20 <= *b && *b <= 0x7f
     }
-    map_res!(i, take!(len), |bytes: &'a [u8]| {
-        if !bytes.iter().all(is_visible) {
-            return Err(BerError::BerValueError);
-        }
-        std::str::from_utf8(bytes)
-            .map(|s| BerObjectContent::GeneralizedTime(s))
-            .map_err(|_| BerError::BerValueError)
-    })
+    let (i, bytes) = take(len)(i)?;
+    custom_check!(i, !bytes.iter().all(is_visible), BerError::BerValueError)?;
+    let s = core::str::from_utf8(bytes).or(Err(BerError::BerValueError))?;
+    Ok((i, BerObjectContent::GeneralizedTime(Cow::Borrowed(s))))
 }
 
 #[inline]
 fn ber_read_content_objectdescriptor(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::ObjectDescriptor)(i)
+    map(take(len), |s| {
+        BerObjectContent::ObjectDescriptor(Cow::Borrowed(s))
+    })(i)
 }
 
 #[inline]
 fn ber_read_content_graphicstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::GraphicString)(i)
+    map(take(len), |s| {
+        BerObjectContent::GraphicString(Cow::Borrowed(s))
+    })(i)
 }
 
 #[inline]
 fn ber_read_content_generalstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::GeneralString)(i)
+    map(take(len), |s| {
+        BerObjectContent::GeneralString(Cow::Borrowed(s))
+    })(i)
 }
 
 #[inline]
 fn ber_read_content_bmpstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::BmpString)(i)
+    map(take(len), |s| BerObjectContent::BmpString(Cow::Borrowed(s)))(i)
 }
 
 #[inline]
 fn ber_read_content_universalstring(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
-    map(take(len), BerObjectContent::UniversalString)(i)
+    map(take(len), |s| {
+        BerObjectContent::UniversalString(Cow::Borrowed(s))
+    })(i)
 }
 
 /// Parse the next bytes as the *content* of a BER object.
@@ -515,7 +964,7 @@ pub fn ber_read_element_content_as(
     if let BerSize::Definite(l) = len {
         custom_check!(i, l > MAX_OBJECT_SIZE, BerError::InvalidLength)?;
         if i.len() < l {
-            return Err(Err::Incomplete(Needed::Size(l)));
+            return Err(Err::Incomplete(Needed::Size(l - i.len())));
         }
     }
     match tag {
@@ -526,6 +975,7 @@ pub fn ber_read_element_content_as(
         }
         // 0x01 bool
         BerTag::Boolean => {
+            custom_check!(i, constructed, BerError::ConstructUnexpected)?; // forbidden in 8.2.1
             let len = len.primitive()?;
             custom_check!(i, len != 1, BerError::InvalidLength)?;
             ber_read_content_bool(i)
@@ -538,13 +988,17 @@ pub fn ber_read_element_content_as(
         }
         // 0x03: bitstring
         BerTag::BitString => {
-            custom_check!(i, constructed, BerError::Unsupported)?; // XXX valid in BER (8.6.3)
+            if constructed {
+                return ber_read_content_bitstring_constructed(i, len, max_depth);
+            }
             let len = len.primitive()?;
             ber_read_content_bitstring(i, len)
         }
         // 0x04: octetstring
         BerTag::OctetString => {
-            custom_check!(i, constructed, BerError::Unsupported)?; // XXX valid in BER (8.7.1)
+            if constructed {
+                return ber_read_content_octetstring_constructed(i, len, max_depth);
+            }
             let len = len.primitive()?;
             ber_read_content_octetstring(i, len)
         }
@@ -568,12 +1022,28 @@ pub fn ber_read_element_content_as(
             let len = len.primitive()?;
             ber_read_content_objectdescriptor(i, len)
         }
+        // 0x08: external
+        BerTag::External => {
+            custom_check!(i, !constructed, BerError::ConstructExpected)?;
+            ber_read_content_external(i, len, max_depth)
+        }
+        // 0x09: real
+        BerTag::RealType => {
+            custom_check!(i, constructed, BerError::ConstructUnexpected)?; // forbidden in 8.5.1
+            let len = len.primitive()?;
+            ber_read_content_real(i, len)
+        }
         // 0x0a: enumerated
         BerTag::Enumerated => {
             custom_check!(i, constructed, BerError::ConstructUnexpected)?; // forbidden in 8.4
             let len = len.primitive()?;
             ber_read_content_enum(i, len)
         }
+        // 0x0b: embedded pdv
+        BerTag::EmbeddedPdv => {
+            custom_check!(i, !constructed, BerError::ConstructExpected)?;
+            ber_read_content_embedded_pdv(i, len, max_depth)
+        }
         // 0x0c: UTF8String - Unicode encoded with the UTF-8 charset (ISO/IEC
         // 10646-1, Annex D)
         BerTag::Utf8String => {
@@ -714,6 +1184,41 @@ pub fn parse_ber_content<'a>(
     }
 }
 
+/// Parse the next `len` bytes as the content of a BER object of type `tag` (combinator)
+///
+/// Unlike [`parse_ber_content`](fn.parse_ber_content.html), no header is required: this is
+/// useful to reinterpret a content region, for example the bytes of an OCTET STRING
+/// encapsulating another value (like the `extnValue` of X.509 extensions).
+///
+/// The content is assumed to be constructed for `Sequence` and `Set`, and primitive for all
+/// other tags. The object is parsed recursively, with a maximum depth of `MAX_RECURSION`.
+///
+/// The arguments of the parse function are: `(input, len)`.
+///
+/// Example: reading the content of an OCTET STRING as an INTEGER
+///
+/// ```
+/// # use der_parser::ber::*;
+/// #
+/// # let bytes = &[0x04, 0x03, 0x01, 0x00, 0x01];
+/// let (_, obj) = parse_ber_octetstring(bytes).expect("parsing failed");
+/// let data = obj.as_slice().expect("not an octetstring");
+/// let (rem, content) = parse_ber_content_with_len(BerTag::Integer)(data, data.len())
+///     .expect("parsing failed");
+/// #
+/// # assert!(rem.is_empty());
+/// # assert_eq!(content.as_u32(), Ok(0x10001));
+/// ```
+pub fn parse_ber_content_with_len<'a, Tag: Into<BerTag>>(
+    tag: Tag,
+) -> impl Fn(&'a [u8], usize) -> BerResult<'a, BerObjectContent<'a>> {
+    let tag = tag.into();
+    move |i: &[u8], len: usize| {
+        let constructed = tag == BerTag::Sequence || tag == BerTag::Set;
+        ber_read_element_content_as(i, tag, BerSize::Definite(len), constructed, MAX_RECURSION)
+    }
+}
+
 /// Parse a BER object, expecting a value with specified tag
 ///
 /// The object is parsed recursively, with a maximum depth of `MAX_RECURSION`.
@@ -731,18 +1236,26 @@ pub fn parse_ber_content<'a>(
 /// ```
 pub fn parse_ber_with_tag<Tag: Into<BerTag>>(i: &[u8], tag: Tag) -> BerResult {
     let tag = tag.into();
-    let (i, hdr) = ber_read_element_header(i)?;
+    let (data, hdr) = ber_read_element_header(i)?;
     if hdr.tag != tag {
         return Err(nom::Err::Error(BerError::InvalidTag));
     }
-    let (i, content) =
-        ber_read_element_content_as(i, hdr.tag, hdr.len, hdr.is_constructed(), MAX_RECURSION)?;
-    Ok((i, BerObject::from_header_and_content(hdr, content)))
+    let (rem, content) =
+        ber_read_element_content_as(data, hdr.tag, hdr.len, hdr.is_constructed(), MAX_RECURSION)?;
+    let obj = BerObject::from_header_and_content(hdr, content);
+    Ok((rem, with_raw_encoding(i, data, rem, obj)))
 }
 
 /// Read end of content marker
+///
+/// The marker must be the universal primitive tag 0, encoded as `00 00`: other objects with tag 0
+/// (for ex. an empty context-specific `[0]`) are rejected with `BerError::InvalidTag`.
 #[inline]
 pub fn parse_ber_endofcontent(i: &[u8]) -> BerResult {
+    let (_, hdr) = ber_read_element_header(i)?;
+    if !is_end_of_content(&hdr) {
+        return Err(Err::Error(BerError::InvalidTag));
+    }
     parse_ber_with_tag(i, BerTag::EndOfContent)
 }
 
@@ -776,9 +1289,10 @@ pub fn parse_ber_bool(i: &[u8]) -> BerResult {
 /// # extern crate nom;
 /// # use der_parser::ber::parse_ber_integer;
 /// # use der_parser::ber::{BerObject,BerObjectContent};
+/// # use std::borrow::Cow;
 /// let empty = &b""[..];
 /// let bytes = [0x02, 0x03, 0x01, 0x00, 0x01];
-/// let expected  = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+/// let expected  = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
 /// assert_eq!(
 ///     parse_ber_integer(&bytes),
 ///     Ok((empty, expected))
@@ -813,12 +1327,113 @@ pub fn parse_ber_oid(i: &[u8]) -> BerResult {
     parse_ber_with_tag(i, BerTag::Oid)
 }
 
+/// Read an object identifier value, with at most `max_arcs` arcs
+///
+/// OIDs with more arcs are rejected with `BerError::InvalidLength`. This can be used to lower
+/// the limit of [`MAX_OID_ARCS`](constant.MAX_OID_ARCS.html), which applies to all parsers.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::parse_ber_oid_with_max_arcs;
+/// # use der_parser::error::BerError;
+/// # use nom::Err;
+/// #
+/// // 1.2.840.113549
+/// let bytes = &[0x06, 0x06, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d];
+/// assert!(parse_ber_oid_with_max_arcs(bytes, 4).is_ok());
+/// assert_eq!(
+///     parse_ber_oid_with_max_arcs(bytes, 3),
+///     Err(Err::Error(BerError::InvalidLength))
+/// );
+/// ```
+pub fn parse_ber_oid_with_max_arcs(i: &[u8], max_arcs: usize) -> BerResult {
+    let (rem, obj) = parse_ber_oid(i)?;
+    let n = oid_arc_count(obj.as_oid()?.bytes(), false);
+    custom_check!(i, n > max_arcs, BerError::InvalidLength)?;
+    Ok((rem, obj))
+}
+
+/// Read an EXTERNAL value
+///
+/// The components are decoded (see [`BerExternal`](struct.BerExternal.html)): the optional
+/// references and descriptor, and the encoding of the value. A `single-ASN1-type` value is
+/// parsed recursively, other encodings are returned as bytes.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::{parse_ber_external, BerExternalEncoding};
+/// use der_parser::oid;
+/// use std::borrow::Cow;
+///
+/// // direct-reference 2.1.1 (ASN.1 BER), octet-aligned value
+/// let bytes = &[0x28, 0x09, 0x06, 0x02, 0x51, 0x01, 0x81, 0x03, 0x61, 0x62, 0x63];
+/// let (_, obj) = parse_ber_external(bytes).expect("parsing failed");
+/// let external = obj.as_external().expect("not an EXTERNAL");
+///
+/// assert_eq!(external.direct_reference, Some(oid!(2.1.1)));
+/// assert_eq!(external.encoding, BerExternalEncoding::OctetAligned(Cow::Borrowed(b"abc")));
+/// ```
+#[inline]
+pub fn parse_ber_external(i: &[u8]) -> BerResult {
+    parse_ber_with_tag(i, BerTag::External)
+}
+
+/// Read a real value
+///
+/// Binary, decimal and special (PLUS-INFINITY, MINUS-INFINITY, NOT-A-NUMBER, minus zero)
+/// encodings are supported (X.690 8.5). The value is stored as a `f64`, use the
+/// [`as_f64`](struct.BerObject.html#method.as_f64) method to read it.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::parse_ber_real;
+/// #
+/// // 5 * 2^-5
+/// let bytes = &[0x09, 0x03, 0x80, 0xfb, 0x05];
+/// let (_, obj) = parse_ber_real(bytes).expect("parsing failed");
+///
+/// assert_eq!(obj.as_f64(), Ok(0.15625));
+/// ```
+#[inline]
+pub fn parse_ber_real(i: &[u8]) -> BerResult {
+    parse_ber_with_tag(i, BerTag::RealType)
+}
+
 /// Read an enumerated value
 #[inline]
 pub fn parse_ber_enum(i: &[u8]) -> BerResult {
     parse_ber_with_tag(i, BerTag::Enumerated)
 }
 
+/// Read an EMBEDDED PDV value
+///
+/// The components are decoded (see [`BerEmbeddedPdv`](struct.BerEmbeddedPdv.html)): the
+/// identification of the syntaxes, and the encoded value (returned as bytes).
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::{parse_ber_embedded_pdv, BerPdvIdentification};
+/// use der_parser::oid;
+///
+/// // identification: syntax 2.1.1 (ASN.1 BER)
+/// let bytes = &[
+///     0x2b, 0x0b, 0xa0, 0x04, 0x81, 0x02, 0x51, 0x01, 0x82, 0x03, 0x61, 0x62, 0x63,
+/// ];
+/// let (_, obj) = parse_ber_embedded_pdv(bytes).expect("parsing failed");
+/// let pdv = obj.as_embedded_pdv().expect("not an EMBEDDED PDV");
+///
+/// assert_eq!(pdv.identification, BerPdvIdentification::Syntax(oid!(2.1.1)));
+/// assert_eq!(&pdv.data_value[..], b"abc");
+/// ```
+#[inline]
+pub fn parse_ber_embedded_pdv(i: &[u8]) -> BerResult {
+    parse_ber_with_tag(i, BerTag::EmbeddedPdv)
+}
+
 /// Read a UTF-8 string value. The encoding is checked.
 #[inline]
 pub fn parse_ber_utf8string(i: &[u8]) -> BerResult {
@@ -908,6 +1523,34 @@ pub fn parse_ber_generalizedtime(i: &[u8]) -> BerResult {
     parse_ber_with_tag(i, BerTag::GeneralizedTime)
 }
 
+/// Read a Time value: a CHOICE of UTCTime and GeneralizedTime, as used in X.509 certificates
+///
+/// The value is decoded, and returned as an `ASN1Time`. If the object is neither a UTCTime nor a
+/// GeneralizedTime, `BerError::UnexpectedTag` is returned.
+///
+/// ```rust
+/// # use der_parser::ber::{parse_ber_time, ASN1Time};
+/// let (_, t) = parse_ber_time(b"\x17\x0d991231235959Z").expect("parsing failed");
+/// if let ASN1Time::UtcTime(utc) = t {
+///     assert_eq!(utc.year, 1999);
+/// }
+/// let (_, t) = parse_ber_time(b"\x18\x0f20501231235959Z").expect("parsing failed");
+/// assert_eq!(t.year(), 2050);
+/// ```
+pub fn parse_ber_time(i: &[u8]) -> BerResult<ASN1Time> {
+    let (_, hdr) = peek_ber_header(i)?;
+    let (rem, obj) = match hdr.tag {
+        BerTag::UtcTime => parse_ber_utctime(i)?,
+        BerTag::GeneralizedTime => parse_ber_generalizedtime(i)?,
+        tag => return Err(Err::Error(BerError::UnexpectedTag(tag))),
+    };
+    let t = match obj.content {
+        BerObjectContent::UTCTime(_) => obj.as_utctime()?.into(),
+        _ => obj.as_generalizedtime()?.into(),
+    };
+    Ok((rem, t))
+}
+
 /// Read an ObjectDescriptor value
 #[inline]
 pub fn parse_ber_objectdescriptor(i: &[u8]) -> BerResult {
@@ -1066,16 +1709,198 @@ where
     }
 }
 
+/// Combinator for OPTIONAL values: parse an object only if the next tag matches
+///
+/// The header of the next object is read (without consuming it), and its class and tag are
+/// compared to `tag`. If they match, `f` is applied to the input and its result (including
+/// errors) is returned. Otherwise (or if no header could be read, for ex. at the end of the
+/// input), `None` is returned and the input is not consumed.
+///
+/// Contrary to `parse_ber_optional`, errors when parsing the object are not hidden.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::*;
+/// #
+/// let bytes = &[0x02, 0x03, 0x01, 0x00, 0x01];
+/// let parser = parse_ber_optional_with_tag(BerClassTag::universal(BerTag::Boolean), parse_ber_bool);
+/// let (rem, res) = parser(bytes).expect("parsing failed");
+/// assert!(res.is_none());
+/// assert_eq!(rem, bytes);
+///
+/// let parser = parse_ber_optional_with_tag(BerClassTag::universal(BerTag::Integer), parse_ber_integer);
+/// let (rem, res) = parser(bytes).expect("parsing failed");
+/// assert!(rem.is_empty());
+/// assert_eq!(res.expect("no object").as_u32(), Ok(0x10001));
+///
+/// // [2] INTEGER OPTIONAL: the universal INTEGER (tag number 2) does not match
+/// let parser = parse_ber_optional_with_tag(BerClassTag::context(2), parse_ber_integer);
+/// let (rem, res) = parser(bytes).expect("parsing failed");
+/// assert!(res.is_none());
+/// assert_eq!(rem, bytes);
+/// ```
+pub fn parse_ber_optional_with_tag<'a, O, F>(
+    tag: BerClassTag,
+    f: F,
+) -> impl Fn(&'a [u8]) -> BerResult<'a, Option<O>>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+{
+    move |i: &[u8]| match peek_ber_header(i) {
+        Ok((_, hdr)) if hdr.class_tag() == tag => {
+            let (rem, obj) = f(i)?;
+            Ok((rem, Some(obj)))
+        }
+        _ => Ok((i, None)),
+    }
+}
+
+/// Combinator for DEFAULT values: parse an object if the next tag matches, or return a default value
+///
+/// If the class and tag of the next object match `tag`, `f` is applied to the input and its result
+/// (including errors) is returned. Otherwise, a copy of `default` is returned and the input is
+/// not consumed.
+///
+/// *Note*: DER forbids encoding a value equal to the default. Use
+/// [`parse_der_default`](../der/fn.parse_der_default.html) to enforce this restriction.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::*;
+/// #
+/// // version INTEGER DEFAULT 0
+/// let parser = parse_ber_default(BerClassTag::universal(BerTag::Integer), parse_ber_u32, 0);
+/// let (rem, version) = parser(&[0x02, 0x01, 0x02]).expect("parsing failed");
+/// assert!(rem.is_empty());
+/// assert_eq!(version, 2);
+///
+/// let bytes = &[0x05, 0x00];
+/// let (rem, version) = parser(bytes).expect("parsing failed");
+/// assert_eq!(rem, bytes);
+/// assert_eq!(version, 0);
+///
+/// // version [0] EXPLICIT INTEGER DEFAULT 0 (as in X.509 certificates)
+/// let parser = parse_ber_default(
+///     BerClassTag::context(0),
+///     parse_ber_tagged_explicit_g(0, |_, content| parse_ber_u32(content)),
+///     0,
+/// );
+/// let (rem, version) = parser(&[0xa0, 0x03, 0x02, 0x01, 0x02]).expect("parsing failed");
+/// assert!(rem.is_empty());
+/// assert_eq!(version, 2);
+/// ```
+pub fn parse_ber_default<'a, O, F>(
+    tag: BerClassTag,
+    f: F,
+    default: O,
+) -> impl Fn(&'a [u8]) -> BerResult<'a, O>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+    O: Clone,
+{
+    let parser = parse_ber_optional_with_tag(tag, f);
+    move |i: &[u8]| {
+        let (rem, opt) = parser(i)?;
+        Ok((rem, opt.unwrap_or_else(|| default.clone())))
+    }
+}
+
+/// Combinator for CHOICE values: select a parser depending on the tag of the next object
+///
+/// The header of the next object is read (without consuming it), and the parser associated with
+/// the first entry of `choices` matching its class and tag is applied to the input. If no entry
+/// matches, `BerError::UnexpectedTag` is returned, with the tag of the object.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::*;
+/// # use der_parser::error::{BerError, BerResult};
+/// #
+/// // Value ::= CHOICE { i INTEGER, b BOOLEAN }
+/// let choices: &[(BerClassTag, fn(&[u8]) -> BerResult)] = &[
+///     (BerClassTag::universal(BerTag::Integer), parse_ber_integer),
+///     (BerClassTag::universal(BerTag::Boolean), parse_ber_bool),
+/// ];
+/// let parser = parse_ber_choice(choices);
+/// let (_, obj) = parser(&[0x01, 0x01, 0xff]).expect("parsing failed");
+/// assert_eq!(obj.as_bool(), Ok(true));
+///
+/// let res = parser(&[0x05, 0x00]);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::UnexpectedTag(BerTag::Null))));
+/// ```
+pub fn parse_ber_choice<'a, 'b, O, F>(
+    choices: &'b [(BerClassTag, F)],
+) -> impl Fn(&'a [u8]) -> BerResult<'a, O> + 'b
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+{
+    move |i: &[u8]| {
+        let (_, hdr) = peek_ber_header(i)?;
+        let class_tag = hdr.class_tag();
+        match choices.iter().find(|(tag, _)| *tag == class_tag) {
+            Some((_, f)) => f(i),
+            None => Err(Err::Error(BerError::UnexpectedTag(hdr.tag))),
+        }
+    }
+}
+
+/// Combinator to limit the number of bytes that can be consumed by a parser
+///
+/// The parser `f` is applied to at most `max_len` bytes of the input. If it tries to read beyond
+/// this limit (for ex. if the length of an object is larger than `max_len`), parsing fails with
+/// `BerError::InvalidLength`. Otherwise, the remaining bytes are returned as usual, including the
+/// bytes after the limit.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::*;
+/// # use der_parser::error::BerError;
+/// #
+/// let bytes = &[0x02, 0x03, 0x01, 0x00, 0x01, 0x05, 0x00];
+/// // the INTEGER fits in 5 bytes
+/// let (rem, obj) = parse_ber_within(5, parse_ber_integer)(bytes).expect("parsing failed");
+/// assert_eq!(obj.as_u32(), Ok(0x10001));
+/// assert_eq!(rem, &[0x05, 0x00]);
+///
+/// // but not in 4 bytes
+/// let res = parse_ber_within(4, parse_ber_integer)(bytes);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::InvalidLength)));
+/// ```
+pub fn parse_ber_within<'a, O, F>(max_len: usize, f: F) -> impl Fn(&'a [u8]) -> BerResult<'a, O>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+{
+    move |i: &'a [u8]| {
+        if i.len() <= max_len {
+            return f(i);
+        }
+        match f(&i[..max_len]) {
+            // reattach the bytes after the limit to the remaining input
+            Ok((rem, o)) => Ok((&i[max_len - rem.len()..], o)),
+            Err(Err::Incomplete(_)) => Err(Err::Error(BerError::InvalidLength)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 /// Parse BER object and try to decode it as a 32-bits unsigned integer
 #[inline]
 pub fn parse_ber_u32(i: &[u8]) -> BerResult<u32> {
-    map_res(parse_ber_integer, |o| o.as_u32())(i)
+    let (i, obj) = parse_ber_integer(i)?;
+    let v = obj.as_u32()?;
+    Ok((i, v))
 }
 
 /// Parse BER object and try to decode it as a 64-bits unsigned integer
 #[inline]
 pub fn parse_ber_u64(i: &[u8]) -> BerResult<u64> {
-    map_res(parse_ber_integer, |o| o.as_u64())(i)
+    let (i, obj) = parse_ber_integer(i)?;
+    let v = obj.as_u64()?;
+    Ok((i, v))
 }
 
 /// Helper combinator, to create a parser with a maximum parsing depth
@@ -1100,15 +1925,39 @@ pub(crate) fn r_parse_ber(max_depth: usize) -> impl Fn(&[u8]) -> BerResult {
 /// ```
 pub fn parse_ber_recursive(i: &[u8], max_depth: usize) -> BerResult {
     custom_check!(i, max_depth == 0, BerError::BerMaxDepth)?;
-    let (rem, hdr) = ber_read_element_header(i)?;
+    let (content, hdr) = ber_read_element_header(i)?;
+    let (rem, obj) = ber_read_element_recursive(content, hdr, max_depth)?;
+    Ok((rem, with_raw_encoding(i, content, rem, obj)))
+}
+
+/// Keep references to the raw encoding of an object
+///
+/// `i` is the start of the object, `content` the start of the content, and `rem` the remaining
+/// bytes after the object.
+pub(crate) fn with_raw_encoding<'a>(
+    i: &'a [u8],
+    content: &'a [u8],
+    rem: &'a [u8],
+    mut obj: BerObject<'a>,
+) -> BerObject<'a> {
+    obj.header.raw_header = Some(Cow::Borrowed(&i[..i.len() - content.len()]));
+    obj.header.raw_content = Some(Cow::Borrowed(&content[..content.len() - rem.len()]));
+    obj
+}
+
+fn ber_read_element_recursive<'a>(
+    rem: &'a [u8],
+    hdr: BerObjectHeader<'a>,
+    max_depth: usize,
+) -> BerResult<'a> {
     if let BerSize::Definite(l) = hdr.len {
-        custom_check!(i, l > MAX_OBJECT_SIZE, BerError::InvalidLength)?;
+        custom_check!(rem, l > MAX_OBJECT_SIZE, BerError::InvalidLength)?;
     }
     match hdr.class {
         BerClass::Universal | BerClass::Private => (),
         _ => {
-            let (rem, content) = ber_get_object_content(rem, &hdr)?;
-            let content = BerObjectContent::Unknown(hdr.tag, content);
+            let (rem, content) = ber_get_object_content(rem, &hdr, max_depth)?;
+            let content = BerObjectContent::Unknown(hdr.tag, Cow::Borrowed(content));
             let obj = BerObject::from_header_and_content(hdr, content);
             return Ok((rem, obj));
         }
@@ -1116,8 +1965,8 @@ pub fn parse_ber_recursive(i: &[u8], max_depth: usize) -> BerResult {
     match ber_read_element_content_as(rem, hdr.tag, hdr.len, hdr.is_constructed(), max_depth) {
         Ok((rem, content)) => Ok((rem, BerObject::from_header_and_content(hdr, content))),
         Err(Err::Error(BerError::UnknownTag)) => {
-            let (rem, content) = ber_get_object_content(rem, &hdr)?;
-            let content = BerObjectContent::Unknown(hdr.tag, content);
+            let (rem, content) = ber_get_object_content(rem, &hdr, max_depth)?;
+            let content = BerObjectContent::Unknown(hdr.tag, Cow::Borrowed(content));
             let obj = BerObject::from_header_and_content(hdr, content);
             Ok((rem, obj))
         }
@@ -1146,20 +1995,316 @@ pub fn parse_ber(i: &[u8]) -> BerResult {
     parse_ber_recursive(i, MAX_RECURSION)
 }
 
+/// Parse BER object recursively, and check that input contains exactly one object
+///
+/// Return an `UnexpectedData` error, with the number of remaining bytes, if data remains after the
+/// object. If the object has an indefinite length, its end-of-content marker is part of the
+/// object. On success, the remaining input is always empty.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::parse_ber_complete;
+/// use der_parser::error::BerError;
+///
+/// let bytes = &[0x30, 0x80, 0x02, 0x01, 0x01, 0x00, 0x00];
+/// let (_, obj) = parse_ber_complete(bytes).expect("parsing failed");
+/// assert_eq!(obj.len(), 1);
+///
+/// let res = parse_ber_complete(&[0x05, 0x00, 0x00, 0x00]);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::UnexpectedData(2))));
+/// ```
+pub fn parse_ber_complete(i: &[u8]) -> BerResult {
+    let (rem, obj) = parse_ber(i)?;
+    custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+    Ok((rem, obj))
+}
+
+/// Encoding rules used by [`parse_with_mode`](fn.parse_with_mode.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Basic Encoding Rules (X.690 section 8)
+    Ber,
+    /// Distinguished Encoding Rules (X.690 section 10)
+    Der,
+}
+
+/// Parse an object recursively, using the encoding rules selected by `mode`
+///
+/// This is the same as calling `parse_ber` or `parse_der`, but can be used when the encoding rules
+/// are only known at runtime (for ex. negotiated by a protocol).
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::{parse_with_mode, Mode};
+///
+/// // BOOLEAN TRUE, encoded as 0x01 (valid in BER only)
+/// let bytes = &[0x01, 0x01, 0x01];
+/// assert!(parse_with_mode(Mode::Ber, bytes).is_ok());
+/// assert!(parse_with_mode(Mode::Der, bytes).is_err());
+/// ```
+pub fn parse_with_mode(mode: Mode, i: &[u8]) -> BerResult {
+    match mode {
+        Mode::Ber => parse_ber(i),
+        Mode::Der => crate::der::parse_der(i),
+    }
+}
+
+/// Result of a best-effort parsing (see `parse_ber_lenient`)
+#[derive(Debug, PartialEq)]
+pub struct BerPartialObject<'a> {
+    /// The object, possibly incomplete if an error occurred while parsing its children
+    ///
+    /// This is `None` if the first object itself could not be parsed.
+    pub object: Option<BerObject<'a>>,
+    /// The first error, and the offset (relative to the start of the input) of the object which
+    /// caused it
+    pub error: Option<(usize, BerError)>,
+}
+
+/// Parse BER object recursively, returning the part of the tree parsed before the first error
+///
+/// **This is a best-effort parser**, intended for the analysis of malformed inputs (for ex. when
+/// triaging fuzzing results). It should not be used to validate data: use `parse_ber` instead.
+///
+/// When a child of a SEQUENCE or SET cannot be parsed, parsing stops and the parent objects are
+/// returned with the children parsed so far. The error is returned, with the offset of the child
+/// which caused it. If the content of a constructed object is truncated, the available bytes are
+/// used and an `ObjectTooShort` error is reported.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::parse_ber_lenient;
+/// use der_parser::error::BerError;
+///
+/// // SEQUENCE { INTEGER 1, <invalid BOOLEAN> }
+/// let bytes = &[0x30, 0x07, 0x02, 0x01, 0x01, 0x01, 0x02, 0x00, 0x00];
+/// let res = parse_ber_lenient(bytes);
+/// let obj = res.object.expect("no object");
+/// assert_eq!(obj.len(), 1);
+/// assert_eq!(res.error, Some((5, BerError::InvalidLength)));
+/// ```
+pub fn parse_ber_lenient(i: &[u8]) -> BerPartialObject {
+    let (_, object, error) = ber_lenient_recursive(i, 0, MAX_RECURSION);
+    BerPartialObject { object, error }
+}
+
+/// Parse an object (see `parse_ber_lenient`), starting at `offset` in the input
+///
+/// Return the number of bytes read, the (partial) object, and the first error.
+#[allow(clippy::type_complexity)]
+fn ber_lenient_recursive(
+    i: &[u8],
+    offset: usize,
+    max_depth: usize,
+) -> (usize, Option<BerObject>, Option<(usize, BerError)>) {
+    fn to_ber_error(e: Err<BerError>) -> BerError {
+        match e {
+            Err::Error(e) | Err::Failure(e) => e,
+            Err::Incomplete(_) => BerError::ObjectTooShort,
+        }
+    }
+    if max_depth == 0 {
+        return (0, None, Some((offset, BerError::BerMaxDepth)));
+    }
+    let (content_start, hdr) = match ber_read_element_header(i) {
+        Ok(res) => res,
+        Err(e) => return (0, None, Some((offset, to_ber_error(e)))),
+    };
+    let is_container = hdr.class == BerClass::Universal
+        && hdr.is_constructed()
+        && (hdr.tag == BerTag::Sequence || hdr.tag == BerTag::Set);
+    if !is_container {
+        return match parse_ber_recursive(i, max_depth) {
+            Ok((rem, obj)) => (i.len() - rem.len(), Some(obj), None),
+            Err(e) => (0, None, Some((offset, to_ber_error(e)))),
+        };
+    }
+    let header_len = i.len() - content_start.len();
+    let (region, mut error) = match hdr.len {
+        BerSize::Definite(len) if len > content_start.len() => {
+            (content_start, Some(BerError::ObjectTooShort))
+        }
+        BerSize::Definite(len) => (&content_start[..len], None),
+        BerSize::Indefinite => (content_start, None),
+    };
+    let mut data = region;
+    let mut children = Vec::new();
+    let mut child_error = None;
+    loop {
+        let child_offset = offset + header_len + (region.len() - data.len());
+        if hdr.len.is_indefinite() {
+            if data.starts_with(&[0, 0]) {
+                data = &data[2..];
+                break;
+            }
+            if data.is_empty() {
+                error = Some(BerError::ObjectTooShort);
+                break;
+            }
+        } else if data.is_empty() {
+            break;
+        }
+        let (read, child, err) = ber_lenient_recursive(data, child_offset, max_depth - 1);
+        data = &data[read..];
+        if let Some(child) = child {
+            children.push(child);
+        }
+        if err.is_some() {
+            child_error = err;
+            break;
+        }
+    }
+    let consumed = header_len + region.len() - data.len();
+    let content = if hdr.tag == BerTag::Sequence {
+        BerObjectContent::Sequence(children)
+    } else {
+        BerObjectContent::Set(children)
+    };
+    let obj = BerObject::from_header_and_content(hdr, content);
+    let error = child_error.or_else(|| error.map(|e| (offset, e)));
+    let obj = if error.is_none() {
+        with_raw_encoding(i, content_start, &i[consumed..], obj)
+    } else {
+        obj
+    };
+    (consumed, Some(obj), error)
+}
+
+/// Parse BER object recursively, using the provided parser options
+///
+/// Return a tuple containing the remaining (unparsed) bytes and the BER Object, or an error.
+///
+/// The size limit applies to the whole object, including its children: if the object (or one of
+/// its children) declares a length that would exceed it, `BerError::InvalidLength` is returned
+/// without reading the content.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::{parse_ber_with_options, BerParserOptions};
+/// use der_parser::error::BerError;
+/// use nom::Err;
+///
+/// let bytes = &[0x30, 0x05, 0x30, 0x03, 0x02, 0x01, 0x01];
+/// let options = BerParserOptions::default().with_max_depth(2);
+/// assert_eq!(
+///     parse_ber_with_options(bytes, &options),
+///     Err(Err::Error(BerError::BerMaxDepth))
+/// );
+/// let options = BerParserOptions::default().with_max_depth(3);
+/// assert!(parse_ber_with_options(bytes, &options).is_ok());
+///
+/// // the object is larger than the size limit
+/// let options = BerParserOptions::default().with_max_size(4);
+/// assert_eq!(
+///     parse_ber_with_options(bytes, &options),
+///     Err(Err::Error(BerError::InvalidLength))
+/// );
+/// ```
+#[inline]
+pub fn parse_ber_with_options<'a>(i: &'a [u8], options: &BerParserOptions) -> BerResult<'a> {
+    parse_with_max_size(i, options.max_size, |i| {
+        parse_ber_recursive(i, options.max_depth)
+    })
+}
+
+/// Find the offset of the innermost object that cannot be parsed
+///
+/// `i` is an object that failed to parse, and must be a subslice of `input`. Children of
+/// constructed objects (Sequence and Set) are parsed until one of them fails, which is then
+/// inspected recursively.
+fn ber_error_offset(input: &[u8], i: &[u8], max_depth: usize) -> usize {
+    let offset = i.as_ptr() as usize - input.as_ptr() as usize;
+    if max_depth == 0 {
+        return offset;
+    }
+    let (rem, hdr) = match ber_read_element_header(i) {
+        Ok(res) => res,
+        Err(_) => return offset,
+    };
+    if hdr.class != BerClass::Universal
+        || !hdr.is_constructed()
+        || (hdr.tag != BerTag::Sequence && hdr.tag != BerTag::Set)
+    {
+        return offset;
+    }
+    let mut content = match hdr.len {
+        BerSize::Definite(l) if l <= rem.len() => &rem[..l],
+        BerSize::Definite(_) => return offset,
+        BerSize::Indefinite => rem,
+    };
+    while !content.is_empty() {
+        match parse_ber_recursive(content, max_depth - 1) {
+            Ok((_, ref obj)) if hdr.is_indefinite() && is_end_of_content(&obj.header) => break,
+            Ok((rem, _)) => content = rem,
+            Err(_) => return ber_error_offset(input, content, max_depth - 1),
+        }
+    }
+    offset
+}
+
+/// Add the offset of the failing object to the error returned by `parser`
+pub(crate) fn with_error_offset<'a, F>(
+    i: &'a [u8],
+    parser: F,
+) -> IResult<&'a [u8], BerObject<'a>, BerErrorWithOffset>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a>,
+{
+    parser(i).map_err(|e| {
+        e.map(|error| BerErrorWithOffset {
+            error,
+            offset: ber_error_offset(i, i, MAX_RECURSION),
+        })
+    })
+}
+
+/// Parse BER object recursively, returning the position of the object where parsing failed
+///
+/// This is the same as `parse_ber`, except that errors contain the offset (relative to `i`) of
+/// the innermost object that could not be parsed. The offset is only computed on errors, by
+/// parsing again the objects.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::parse_ber_with_offset;
+/// use nom::Err;
+///
+/// // the NULL object (in an indefinite-length sequence) has an invalid length
+/// let bytes = &[0x30, 0x80, 0x02, 0x01, 0x01, 0x05, 0x01, 0x00, 0x00, 0x00];
+/// match parse_ber_with_offset(bytes) {
+///     Err(Err::Error(e)) => assert_eq!(e.offset, 5),
+///     _ => panic!("parsing should fail"),
+/// }
+/// ```
+pub fn parse_ber_with_offset(i: &[u8]) -> IResult<&[u8], BerObject, BerErrorWithOffset> {
+    with_error_offset(i, parse_ber)
+}
+
 #[test]
 fn test_numericstring() {
     assert_eq!(
         ber_read_content_numericstring(b" 0123  4495768 ", 15),
         Ok((
             [].as_ref(),
-            BerObjectContent::NumericString(" 0123  4495768 ")
+            BerObjectContent::NumericString(Cow::Borrowed(" 0123  4495768 "))
         )),
     );
     assert_eq!(
         ber_read_content_numericstring(b"", 0),
-        Ok(([].as_ref(), BerObjectContent::NumericString(""))),
+        Ok((
+            [].as_ref(),
+            BerObjectContent::NumericString(Cow::Borrowed(""))
+        )),
+    );
+    assert_eq!(
+        ber_read_content_numericstring(b"123a", 4),
+        Err(Err::Error(BerError::StringInvalidCharset))
     );
-    assert!(ber_read_content_numericstring(b"123a", 4).is_err());
 }
 
 #[test]
@@ -1168,14 +2313,21 @@ fn text_visiblestring() {
         ber_read_content_visiblestring(b"AZaz]09 '()+,-./:=?", 19),
         Ok((
             [].as_ref(),
-            BerObjectContent::VisibleString("AZaz]09 '()+,-./:=?")
+            BerObjectContent::VisibleString(Cow::Borrowed("AZaz]09 '()+,-./:=?"))
         )),
     );
     assert_eq!(
         ber_read_content_visiblestring(b"", 0),
-        Ok(([].as_ref(), BerObjectContent::VisibleString(""))),
+        Ok((
+            [].as_ref(),
+            BerObjectContent::VisibleString(Cow::Borrowed(""))
+        )),
+    );
+    assert_eq!(
+        ber_read_content_visiblestring(b"\n", 1),
+        Err(Err::Error(BerError::StringInvalidCharset))
     );
-    assert!(ber_read_content_visiblestring(b"\n", 1).is_err());
+    assert!(ber_read_content_visiblestring(b"\x7f", 1).is_err());
 }
 
 #[test]
@@ -1184,14 +2336,24 @@ fn test_printablestring() {
         ber_read_content_printablestring(b"AZaz09 '()+,-./:=?", 18),
         Ok((
             [].as_ref(),
-            BerObjectContent::PrintableString("AZaz09 '()+,-./:=?")
+            BerObjectContent::PrintableString(Cow::Borrowed("AZaz09 '()+,-./:=?"))
         )),
     );
     assert_eq!(
         ber_read_content_printablestring(b"", 0),
-        Ok(([].as_ref(), BerObjectContent::PrintableString(""))),
+        Ok((
+            [].as_ref(),
+            BerObjectContent::PrintableString(Cow::Borrowed(""))
+        )),
     );
-    assert!(ber_read_content_printablestring(b"]\n", 2).is_err());
+    assert_eq!(
+        ber_read_content_printablestring(b"]\n", 2),
+        Err(Err::Error(BerError::StringInvalidCharset))
+    );
+    // '*', '@' and '&' are not in the PrintableString charset
+    assert!(ber_read_content_printablestring(b"a*b", 3).is_err());
+    assert!(ber_read_content_printablestring(b"a@b.c", 5).is_err());
+    assert!(ber_read_content_printablestring(b"a&b", 3).is_err());
 }
 
 #[test]
@@ -1200,14 +2362,17 @@ fn test_ia5string() {
         ber_read_content_ia5string(b"AZaz\n09 '()+,-./:=?[]{}\0\n", 25),
         Ok((
             [].as_ref(),
-            BerObjectContent::IA5String("AZaz\n09 '()+,-./:=?[]{}\0\n")
+            BerObjectContent::IA5String(Cow::Borrowed("AZaz\n09 '()+,-./:=?[]{}\0\n"))
         )),
     );
     assert_eq!(
         ber_read_content_ia5string(b"", 0),
-        Ok(([].as_ref(), BerObjectContent::IA5String(""))),
+        Ok(([].as_ref(), BerObjectContent::IA5String(Cow::Borrowed("")))),
+    );
+    assert_eq!(
+        ber_read_content_ia5string(b"\xFF", 1),
+        Err(Err::Error(BerError::StringInvalidCharset))
     );
-    assert!(ber_read_content_ia5string(b"\xFF", 1).is_err());
 }
 
 #[test]
@@ -1216,12 +2381,12 @@ fn test_utf8string() {
         ber_read_content_utf8string("AZaz09 '()+,-./:=?[]{}\0\nüÜ".as_ref(), 28),
         Ok((
             [].as_ref(),
-            BerObjectContent::UTF8String("AZaz09 '()+,-./:=?[]{}\0\nüÜ")
+            BerObjectContent::UTF8String(Cow::Borrowed("AZaz09 '()+,-./:=?[]{}\0\nüÜ"))
         )),
     );
     assert_eq!(
         ber_read_content_utf8string(b"", 0),
-        Ok(([].as_ref(), BerObjectContent::UTF8String(""))),
+        Ok(([].as_ref(), BerObjectContent::UTF8String(Cow::Borrowed("")))),
     );
     assert!(ber_read_content_utf8string(b"\xe2\x28\xa1", 3).is_err());
 }
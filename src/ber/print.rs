@@ -1,10 +1,20 @@
-use crate::ber::BitStringObject;
-use crate::ber::{BerObject, BerObjectContent, BerTag};
-use std::fmt;
-use std::iter::FromIterator;
-use std::str;
+use crate::ber::{BerObject, BerObjectContent, BerSize, BerTag};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::iter::FromIterator;
+use core::str;
 
-use rusticata_macros::debug;
+/// Wrapper for printing a slice as hex data (same as `rusticata_macros::debug::HexSlice`, but
+/// using `core`)
+struct HexSlice<'a>(&'a [u8]);
+
+impl<'a> fmt::Debug for HexSlice<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s: Vec<_> = self.0.iter().map(|&i| format!("{:02x}", i)).collect();
+        write!(f, "[{}]", s.join(" "))
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum PrettyPrinterFlag {
@@ -15,6 +25,7 @@ pub struct PrettyBer<'a> {
     obj: &'a BerObject<'a>,
     indent: usize,
     inc: usize,
+    max_bytes: Option<usize>,
 
     flags: Vec<PrettyPrinterFlag>,
 }
@@ -25,6 +36,7 @@ impl<'a> BerObject<'a> {
             obj: self,
             indent,
             inc: increment,
+            max_bytes: None,
 
             flags: Vec::new(),
         }
@@ -38,11 +50,19 @@ impl<'a> PrettyBer<'a> {
         }
     }
 
+    /// Limit the number of bytes displayed for OctetString and BitString objects
+    ///
+    /// Longer values are truncated, and the total length is displayed.
+    pub fn set_max_bytes(&mut self, max_bytes: usize) {
+        self.max_bytes = Some(max_bytes);
+    }
+
     pub fn next_indent<'b>(&self, obj: &'b BerObject) -> PrettyBer<'b> {
         PrettyBer {
             obj,
             indent: self.indent + self.inc,
             inc: self.inc,
+            max_bytes: self.max_bytes,
             flags: self.flags.to_vec(),
         }
     }
@@ -55,8 +75,19 @@ impl<'a> fmt::Debug for PrettyBer<'a> {
             write!(f, "{:1$}", " ", self.indent)?;
         };
         if self.flags.contains(&PrettyPrinterFlag::ShowHeader) {
-            write!(f, "[c:{:?}, s:{}, t:{}] ", self.obj.header.class, self.obj.header.structured, self.obj.header.tag)?;
+            let hdr = &self.obj.header;
+            write!(f, "[c:{:?}, s:{}, t:{}, l:", hdr.class, hdr.structured, hdr.tag.0)?;
+            match hdr.len {
+                BerSize::Definite(l) => write!(f, "{}] ", l)?,
+                BerSize::Indefinite  => write!(f, "indefinite] ")?,
+            }
         };
+        fn print_bytes_with_type(f: &mut fmt::Formatter, s: &[u8], ty: &str, max_bytes: Option<usize>) -> fmt::Result {
+            match max_bytes {
+                Some(n) if s.len() > n => writeln!(f, "{}({:?}...) <{} bytes>", ty, HexSlice(&s[..n]), s.len()),
+                _                      => writeln!(f, "{}({:?})", ty, HexSlice(s)),
+            }
+        }
         fn print_utf8_string_with_type(f: &mut fmt::Formatter, s: &[u8], ty: &str) -> fmt::Result {
             match str::from_utf8(s) {
                 Ok(b)  => writeln!(f, "{}(\"{}\")", ty, b),
@@ -77,7 +108,7 @@ impl<'a> fmt::Debug for PrettyBer<'a> {
         fn print_utf32_string_with_type(f: &mut fmt::Formatter, s: &[u8], ty: &str) -> fmt::Result {
             let chars: Option<Vec<char>> = s
                 .chunks_exact(4)
-                .map(|a| std::char::from_u32(u32::from_be_bytes([a[0], a[1], a[2], a[3]])))
+                .map(|a| core::char::from_u32(u32::from_be_bytes([a[0], a[1], a[2], a[3]])))
                 .collect();
 
             match chars {
@@ -88,28 +119,43 @@ impl<'a> fmt::Debug for PrettyBer<'a> {
         match self.obj.content {
             BerObjectContent::EndOfContent           => writeln!(f, "EndOfContent"),
             BerObjectContent::Boolean(b)             => writeln!(f, "Boolean({:?})", b),
-            BerObjectContent::Integer(i)             => writeln!(f, "Integer({:?})", debug::HexSlice(i)),
-            BerObjectContent::Enum(i)                => writeln!(f, "Enum({})", i),
-            BerObjectContent::OID(ref v)             => writeln!(f, "OID({:?})", v),
-            BerObjectContent::RelativeOID(ref v)     => writeln!(f, "RelativeOID({:?})", v),
+            BerObjectContent::Integer(ref i)             => {
+                match self.obj.content.as_i128() {
+                    Ok(v)  => writeln!(f, "Integer({})", v),
+                    Err(_) => writeln!(f, "Integer({:?})", HexSlice(i)),
+                }
+            }
+            BerObjectContent::Real(r)                => writeln!(f, "Real({})", r),
+            BerObjectContent::Enum(i)                => writeln!(f, "Enum({})", i as i64),
+            #[cfg(feature = "oid-names")]
+            BerObjectContent::OID(ref v) if v.to_name().is_some()
+                                                     => writeln!(f, "OID({} [{}])", v.to_name().unwrap_or_default(), v.to_id_string()),
+            BerObjectContent::OID(ref v)             => writeln!(f, "OID({})", v.to_id_string()),
+            BerObjectContent::RelativeOID(ref v)     => writeln!(f, "RelativeOID({})", v.to_id_string()),
             BerObjectContent::Null                   => writeln!(f, "Null"),
-            BerObjectContent::OctetString(v)         => writeln!(f, "OctetString({:?})", debug::HexSlice(v)),
-            BerObjectContent::BitString(u,BitStringObject{data:v})
-                                                     => writeln!(f, "BitString({},{:?})", u, debug::HexSlice(v)),
-            BerObjectContent::GeneralizedTime(s)     => writeln!(f, "GeneralizedTime(\"{}\")", s),
-            BerObjectContent::UTCTime(s)             => writeln!(f, "UTCTime(\"{}\")", s),
-            BerObjectContent::VisibleString(s)       => writeln!(f, "VisibleString(\"{}\")", s),
-            BerObjectContent::PrintableString(s)     => writeln!(f, "PrintableString(\"{}\")", s),
-            BerObjectContent::NumericString(s)       => writeln!(f, "NumericString(\"{}\")", s),
-            BerObjectContent::UTF8String(s)          => writeln!(f, "UTF8String(\"{}\")", s),
-            BerObjectContent::IA5String(s)           => writeln!(f, "IA5String(\"{}\")", s),
-            BerObjectContent::T61String(v)           => writeln!(f, "T61String({:?})", debug::HexSlice(v)),
-            BerObjectContent::VideotexString(v)      => writeln!(f, "VideotexString({:?})", debug::HexSlice(v)),
-            BerObjectContent::BmpString(s)           => print_utf16_string_with_type(f, s, "BmpString"),
-            BerObjectContent::UniversalString(s)     => print_utf32_string_with_type(f, s, "UniversalString"),
-            BerObjectContent::ObjectDescriptor(s)    => print_utf8_string_with_type(f, s, "ObjectDescriptor"),
-            BerObjectContent::GraphicString(s)       => print_utf8_string_with_type(f, s, "GraphicString"),
-            BerObjectContent::GeneralString(s)       => print_utf8_string_with_type(f, s, "GeneralString"),
+            BerObjectContent::OctetString(ref v)         => print_bytes_with_type(f, v, "OctetString", self.max_bytes),
+            BerObjectContent::OctetStringConstructed(ref v)
+                                                     => print_bytes_with_type(f, v, "OctetString(constructed)", self.max_bytes),
+            BerObjectContent::BitString(ref b)
+                                                     => print_bytes_with_type(f, &b.data, &format!("BitString({})", b.unused_bits()), self.max_bytes),
+            BerObjectContent::BitStringConstructed(u, ref v)
+                                                     => print_bytes_with_type(f, v, &format!("BitString({}, constructed)", u), self.max_bytes),
+            BerObjectContent::GeneralizedTime(ref s)     => writeln!(f, "GeneralizedTime(\"{}\")", s),
+            BerObjectContent::UTCTime(ref s)             => writeln!(f, "UTCTime(\"{}\")", s),
+            BerObjectContent::VisibleString(ref s)       => writeln!(f, "VisibleString(\"{}\")", s),
+            BerObjectContent::PrintableString(ref s)     => writeln!(f, "PrintableString(\"{}\")", s),
+            BerObjectContent::NumericString(ref s)       => writeln!(f, "NumericString(\"{}\")", s),
+            BerObjectContent::UTF8String(ref s)          => writeln!(f, "UTF8String(\"{}\")", s),
+            BerObjectContent::IA5String(ref s)           => writeln!(f, "IA5String(\"{}\")", s),
+            BerObjectContent::T61String(ref v)           => writeln!(f, "T61String({:?})", HexSlice(v)),
+            BerObjectContent::VideotexString(ref v)      => writeln!(f, "VideotexString({:?})", HexSlice(v)),
+            BerObjectContent::BmpString(ref s)           => print_utf16_string_with_type(f, s, "BmpString"),
+            BerObjectContent::UniversalString(ref s)     => print_utf32_string_with_type(f, s, "UniversalString"),
+            BerObjectContent::ObjectDescriptor(ref s)    => print_utf8_string_with_type(f, s, "ObjectDescriptor"),
+            BerObjectContent::GraphicString(ref s)       => print_utf8_string_with_type(f, s, "GraphicString"),
+            BerObjectContent::GeneralString(ref s)       => print_utf8_string_with_type(f, s, "GeneralString"),
+            BerObjectContent::External(ref e)        => writeln!(f, "External({:?})", e),
+            BerObjectContent::EmbeddedPdv(ref p)     => writeln!(f, "EmbeddedPdv({:?})", p),
             BerObjectContent::Optional(ref o) => {
                 match o {
                     Some(obj) => writeln!(f, "OPTION {:?}", obj),
@@ -117,7 +163,7 @@ impl<'a> fmt::Debug for PrettyBer<'a> {
                 }
             }
             BerObjectContent::Tagged(class, tag, ref obj) => {
-                writeln!(f, "ContextSpecific [{} {}] {{", class, tag)?;
+                writeln!(f, "{:?} [{}] {{", class, tag.0)?;
                 write!(f, "{:?}", self.next_indent(obj))?;
                 if self.indent > 0 {
                     write!(f, "{:1$}", " ", self.indent)?;
@@ -138,15 +184,68 @@ impl<'a> fmt::Debug for PrettyBer<'a> {
                 writeln!(f, "]")?;
                 Ok(())
             },
-            BerObjectContent::Unknown(tag,o)         => writeln!(f, "Unknown({:?},{:x?})", tag, o),
+            BerObjectContent::Unknown(tag,ref o)         => writeln!(f, "Unknown({:?},{:x?})", tag, o),
         }
     }
 }
 
-#[cfg(test)]
+impl<'a> fmt::Display for PrettyBer<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+impl<'a> BerObjectContent<'a> {
+    /// Return a dump of the content bytes, similar to the output of `xxd`
+    ///
+    /// Each line contains the offset, `width` bytes in hexadecimal, and the same bytes as ASCII
+    /// (non-printable characters are replaced by `.`). If `width` is 0, 16 bytes are displayed
+    /// per line.
+    ///
+    /// This is mostly useful for `Unknown` content, but also works for all content that can be
+    /// read using `as_slice`. An empty string is returned for other content types.
+    ///
+    /// ```rust
+    /// use der_parser::ber::{BerObjectContent, BerTag};
+    /// use std::borrow::Cow;
+    ///
+    /// let content = BerObjectContent::Unknown(BerTag(0x45), Cow::Borrowed(b"\x00\x01abc"));
+    /// assert_eq!(content.hex_dump(4), "00000000: 00 01 61 62  ..ab\n00000004: 63           c\n");
+    /// ```
+    pub fn hex_dump(&self, width: usize) -> String {
+        let width = if width == 0 { 16 } else { width };
+        let data = match self.as_cow_slice() {
+            Ok(data) => data,
+            Err(_) => return String::new(),
+        };
+        let mut s = String::new();
+        for (idx, chunk) in data.chunks(width).enumerate() {
+            s.push_str(&format!("{:08x}:", idx * width));
+            for b in chunk {
+                s.push_str(&format!(" {:02x}", b));
+            }
+            for _ in chunk.len()..width {
+                s.push_str("   ");
+            }
+            s.push_str("  ");
+            s.extend(chunk.iter().map(|&b| {
+                if 0x20 <= b && b <= 0x7e {
+                    b as char
+                } else {
+                    '.'
+                }
+            }));
+            s.push('\n');
+        }
+        s
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::PrettyPrinterFlag;
     use crate::ber::*;
+    use alloc::borrow::Cow;
 
     #[test]
     fn test_pretty_print() {
@@ -165,4 +264,111 @@ mod tests {
         pp.set_flag(PrettyPrinterFlag::ShowHeader);
         println!("{:?}", pp);
     }
+
+    #[test]
+    fn test_pretty_print_values() {
+        let oid = crate::oid::Oid::from(&[1, 2, 840, 113_549, 1, 1, 5]).unwrap();
+        let d = BerObject::from_seq(vec![
+            BerObject::from_int_slice(b"\x01\x00\x01"),
+            BerObject::from_int_slice(b"\xff\x7f"),
+            BerObject::from_oid(oid),
+            BerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed("abc"))),
+            BerObject::from_octetstring(b"\x01\x02\x03\x04\x05"),
+        ]);
+        let mut pp = d.as_pretty(0, 2);
+        pp.set_max_bytes(4);
+        let s = format!("{}", pp);
+        let oid_line = if cfg!(feature = "oid-names") {
+            "OID(sha1WithRSAEncryption [1.2.840.113549.1.1.5])"
+        } else {
+            "OID(1.2.840.113549.1.1.5)"
+        };
+        assert_eq!(
+            s,
+            format!(
+                r#"Sequence[
+  Integer(65537)
+  Integer(-129)
+  {}
+  PrintableString("abc")
+  OctetString([01 02 03 04]...) <5 bytes>
+]
+"#,
+                oid_line
+            )
+        );
+        // header: class, structured flag, tag number and length
+        let (_, obj) = parse_ber(b"\x30\x03\x02\x01\x05").expect("could not parse");
+        let mut pp = obj.as_pretty(0, 2);
+        pp.set_flag(PrettyPrinterFlag::ShowHeader);
+        let s = format!("{}", pp);
+        assert_eq!(
+            s,
+            "[c:Universal, s:1, t:16, l:3] Sequence[\n  [c:Universal, s:0, t:2, l:1] Integer(5)\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_pretty_print_enum() {
+        // ENUMERATED values are signed, like INTEGER
+        let (_, obj) = parse_ber(b"\x0a\x01\x05").expect("could not parse");
+        assert_eq!(format!("{}", obj.as_pretty(0, 2)), "Enum(5)\n");
+        let (_, obj) = parse_ber(b"\x0a\x01\xff").expect("could not parse");
+        assert_eq!(format!("{}", obj.as_pretty(0, 2)), "Enum(-1)\n");
+    }
+
+    #[test]
+    fn test_pretty_print_tagged() {
+        let inner = BerObject::from_int_slice(b"\x05");
+        let obj = BerObject::from_obj(BerObjectContent::Tagged(
+            BerClass::ContextSpecific,
+            BerTag(0),
+            Box::new(inner.clone()),
+        ));
+        assert_eq!(
+            format!("{}", obj.as_pretty(0, 2)),
+            "ContextSpecific [0] {\n  Integer(5)\n}\n"
+        );
+        let obj = BerObject::from_obj(BerObjectContent::Tagged(
+            BerClass::Application,
+            BerTag(5),
+            Box::new(inner),
+        ));
+        assert_eq!(
+            format!("{}", obj.as_pretty(0, 2)),
+            "Application [5] {\n  Integer(5)\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_pretty_print_constructed_strings() {
+        // constructed encodings (BER only) are annotated
+        let (_, obj) =
+            parse_ber(b"\x24\x80\x04\x01\x01\x04\x01\x02\x00\x00").expect("could not parse");
+        assert!(obj.is_constructed());
+        assert_eq!(
+            format!("{}", obj.as_pretty(0, 2)),
+            "OctetString(constructed)([01 02])\n"
+        );
+        let (_, obj) = parse_ber(b"\x04\x02\x01\x02").expect("could not parse");
+        assert!(!obj.is_constructed());
+        assert_eq!(format!("{}", obj.as_pretty(0, 2)), "OctetString([01 02])\n");
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let (_, obj) =
+            parse_ber(b"\x9f\x45\x11Hello, world!\x00\xff\x7f\x20").expect("could not parse");
+        assert_eq!(
+            obj.content.hex_dump(16),
+            "00000000: 48 65 6c 6c 6f 2c 20 77 6f 72 6c 64 21 00 ff 7f  Hello, world!...\n\
+             00000010: 20                                                \n"
+        );
+        assert_eq!(obj.content.hex_dump(0), obj.content.hex_dump(16));
+        assert_eq!(
+            BerObjectContent::Unknown(BerTag(1), Cow::Borrowed(b"")).hex_dump(8),
+            ""
+        );
+        assert_eq!(BerObjectContent::Null.hex_dump(8), "");
+    }
 }
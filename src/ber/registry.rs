@@ -0,0 +1,204 @@
+use crate::ber::*;
+use crate::error::*;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use core::fmt;
+use nom::Err;
+
+/// Function parsing the content of an object, given its header and the maximum recursion depth
+///
+/// The input contains exactly the content of the object (the end-of-content marker is removed
+/// for objects using the indefinite length form).
+pub type BerContentParser =
+    for<'a> fn(&'a [u8], &BerObjectHeader<'a>, usize) -> BerResult<'a, BerObjectContent<'a>>;
+
+/// Registry of custom parsers for universal types
+///
+/// A registry maps universal tag numbers to content parsers, used by
+/// [`parse_ber_with_registry`](fn.parse_ber_with_registry.html) instead of the built-in parsers.
+/// This can be used to decode types which are not natively supported, or to replace the
+/// decoding of a supported type.
+///
+/// The default (empty) registry reproduces the behavior of [`parse_ber`](fn.parse_ber.html).
+///
+/// ```rust
+/// use der_parser::ber::*;
+/// use der_parser::error::BerResult;
+/// use std::borrow::Cow;
+///
+/// // decode the (reserved) universal tag 14 as an OCTET STRING
+/// fn parse_tag14<'a>(
+///     i: &'a [u8],
+///     _hdr: &BerObjectHeader<'a>,
+///     _max_depth: usize,
+/// ) -> BerResult<'a, BerObjectContent<'a>> {
+///     Ok((&i[i.len()..], BerObjectContent::OctetString(Cow::Borrowed(i))))
+/// }
+///
+/// let registry = BerParserRegistry::new().with_parser(BerTag(14), parse_tag14);
+/// let bytes = &[0x30, 0x04, 0x0e, 0x02, 0x61, 0x62];
+/// let (_, obj) = parse_ber_with_registry(&registry, bytes).expect("parsing failed");
+/// assert_eq!(obj[0].as_slice(), Ok(&b"ab"[..]));
+/// ```
+#[derive(Clone, Default)]
+pub struct BerParserRegistry {
+    parsers: BTreeMap<u32, BerContentParser>,
+}
+
+impl BerParserRegistry {
+    /// Build a new, empty registry
+    pub fn new() -> Self {
+        BerParserRegistry::default()
+    }
+
+    /// Add (or replace) the parser for universal tag `tag`
+    pub fn with_parser(mut self, tag: BerTag, f: BerContentParser) -> Self {
+        self.register(tag, f);
+        self
+    }
+
+    /// Add (or replace) the parser for universal tag `tag`
+    pub fn register(&mut self, tag: BerTag, f: BerContentParser) {
+        self.parsers.insert(tag.0, f);
+    }
+
+    /// Get the parser for universal tag `tag`, if registered
+    pub fn get(&self, tag: BerTag) -> Option<BerContentParser> {
+        self.parsers.get(&tag.0).cloned()
+    }
+
+    /// Return true if no custom parser is registered
+    pub fn is_empty(&self) -> bool {
+        self.parsers.is_empty()
+    }
+}
+
+impl fmt::Debug for BerParserRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("BerParserRegistry")
+            .field("tags", &self.parsers.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// Parse BER object recursively, using custom parsers for the universal tags of the registry
+///
+/// Custom parsers are also used for the elements of SEQUENCE and SET objects. Objects with other
+/// tags are parsed using [`parse_ber`](fn.parse_ber.html). Custom parsers must consume all the
+/// content, or `BerError::UnexpectedData` is returned.
+///
+/// See [`BerParserRegistry`](struct.BerParserRegistry.html) for an example.
+pub fn parse_ber_with_registry<'a>(registry: &BerParserRegistry, i: &'a [u8]) -> BerResult<'a> {
+    registry_parse_recursive(registry, i, MAX_RECURSION)
+}
+
+fn registry_parse_recursive<'a>(
+    registry: &BerParserRegistry,
+    i: &'a [u8],
+    max_depth: usize,
+) -> BerResult<'a> {
+    if registry.is_empty() {
+        return parse_ber_recursive(i, max_depth);
+    }
+    custom_check!(i, max_depth == 0, BerError::BerMaxDepth)?;
+    let (content, hdr) = ber_read_element_header(i)?;
+    if hdr.class != BerClass::Universal {
+        return parse_ber_recursive(i, max_depth);
+    }
+    let (rem, obj_content) = if let Some(f) = registry.get(hdr.tag) {
+        let (rem, data) = ber_get_object_content(content, &hdr, max_depth)?;
+        let (data, obj_content) = f(data, &hdr, max_depth)?;
+        custom_check!(data, !data.is_empty(), BerError::UnexpectedData(data.len()))?;
+        (rem, obj_content)
+    } else if hdr.is_constructed() && (hdr.tag == BerTag::Sequence || hdr.tag == BerTag::Set) {
+        let (rem, data) = ber_get_object_content(content, &hdr, max_depth)?;
+        let l = registry_read_children(registry, data, max_depth - 1)?;
+        if hdr.tag == BerTag::Sequence {
+            (rem, BerObjectContent::Sequence(l))
+        } else {
+            (rem, BerObjectContent::Set(l))
+        }
+    } else {
+        return parse_ber_recursive(i, max_depth);
+    };
+    let obj = BerObject::from_header_and_content(hdr, obj_content);
+    Ok((rem, with_raw_encoding(i, content, rem, obj)))
+}
+
+fn registry_read_children<'a>(
+    registry: &BerParserRegistry,
+    data: &'a [u8],
+    max_depth: usize,
+) -> Result<Vec<BerObject<'a>>, Err<BerError>> {
+    let mut l = Vec::new();
+    let mut data = data;
+    while !data.is_empty() {
+        match registry_parse_recursive(registry, data, max_depth) {
+            Ok((_, ref obj)) if is_end_of_content(&obj.header) => {
+                // end-of-content is only valid for indefinite length objects
+                return Err(Err::Error(BerError::InvalidLength));
+            }
+            Ok((rem, obj)) => {
+                l.push(obj);
+                data = rem;
+            }
+            Err(Err::Incomplete(_)) => return Err(Err::Error(BerError::InvalidLength)),
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(l)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::borrow::Cow;
+    use hex_literal::hex;
+
+    fn parse_utf8_raw<'a>(
+        i: &'a [u8],
+        _hdr: &BerObjectHeader<'a>,
+        _max_depth: usize,
+    ) -> BerResult<'a, BerObjectContent<'a>> {
+        // replace the built-in parser (which checks the encoding)
+        Ok((
+            &i[i.len()..],
+            BerObjectContent::Unknown(BerTag::Utf8String, Cow::Borrowed(i)),
+        ))
+    }
+
+    #[test]
+    fn test_registry() {
+        // default registry
+        let registry = BerParserRegistry::new();
+        assert!(registry.is_empty());
+        let bytes = hex!("30 80 02 01 01 0c 02 ff fe 00 00");
+        let res = parse_ber_with_registry(&registry, &bytes);
+        assert_eq!(res, parse_ber(&bytes));
+        assert!(res.is_err());
+        let bytes = hex!("31 08 02 01 01 30 03 01 01 ff");
+        assert_eq!(
+            parse_ber_with_registry(&registry, &bytes),
+            parse_ber(&bytes)
+        );
+        // custom parser, also used for elements of (indefinite length) constructed objects
+        let registry = registry.with_parser(BerTag::Utf8String, parse_utf8_raw);
+        assert!(registry.get(BerTag::Utf8String).is_some());
+        let bytes = hex!("30 80 02 01 01 30 04 0c 02 ff fe 00 00");
+        let (rem, obj) = parse_ber_with_registry(&registry, &bytes).expect("parsing failed");
+        assert!(rem.is_empty());
+        assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(2));
+        assert_eq!(
+            obj[1][0].content,
+            BerObjectContent::Unknown(BerTag::Utf8String, Cow::Borrowed(&hex!("ff fe")))
+        );
+        assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+        assert_eq!(obj.content_bytes(), Some(&bytes[2..]));
+        // end-of-content in definite length content
+        let bytes = hex!("30 02 00 00");
+        assert_eq!(
+            parse_ber_with_registry(&registry, &bytes),
+            Err(Err::Error(BerError::InvalidLength))
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use crate::ber::*;
+use ::serde::ser::{Serialize, SerializeStruct, Serializer};
+use alloc::string::ToString;
+use core::fmt;
+
+/// Byte slice, serialized as a lowercase hex string
+struct Hex<'a>(&'a [u8]);
+
+impl<'a> fmt::Display for Hex<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Serialize for Hex<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Content of a BitString: number of unused bits, and data
+struct BitStringValue<'a>(u8, &'a [u8]);
+
+impl<'a> Serialize for BitStringValue<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("BitString", 2)?;
+        s.serialize_field("unused_bits", &self.0)?;
+        s.serialize_field("data", &Hex(self.1))?;
+        s.end()
+    }
+}
+
+/// Header and content of a tagged or unknown object
+struct TaggedValue<'a, T: Serialize> {
+    class: Option<BerClass>,
+    tag: BerTag,
+    value: &'a T,
+}
+
+impl<'a, T: Serialize> Serialize for TaggedValue<'a, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let len = if self.class.is_some() { 3 } else { 2 };
+        let mut s = serializer.serialize_struct("Tagged", len)?;
+        if let Some(class) = self.class {
+            s.serialize_field("class", &class.to_string())?;
+        }
+        s.serialize_field("tag", &self.tag.0)?;
+        s.serialize_field("value", self.value)?;
+        s.end()
+    }
+}
+
+impl<'a> Serialize for BerExternalEncoding<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "BerExternalEncoding";
+        match self {
+            BerExternalEncoding::SingleAsn1Type(obj) => {
+                serializer.serialize_newtype_variant(NAME, 0, "SingleAsn1Type", obj.as_ref())
+            }
+            BerExternalEncoding::OctetAligned(s) => {
+                serializer.serialize_newtype_variant(NAME, 1, "OctetAligned", &Hex(s))
+            }
+            BerExternalEncoding::OctetAlignedConstructed(v) => {
+                serializer.serialize_newtype_variant(NAME, 1, "OctetAligned", &Hex(v))
+            }
+            BerExternalEncoding::Arbitrary(b) => serializer.serialize_newtype_variant(
+                NAME,
+                2,
+                "Arbitrary",
+                &BitStringValue(b.unused_bits(), &b.data),
+            ),
+            BerExternalEncoding::ArbitraryConstructed(u, v) => {
+                serializer.serialize_newtype_variant(NAME, 2, "Arbitrary", &BitStringValue(*u, v))
+            }
+        }
+    }
+}
+
+impl<'a> Serialize for BerExternal<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("External", 4)?;
+        let direct_reference = self.direct_reference.as_ref().map(|oid| oid.to_id_string());
+        s.serialize_field("direct_reference", &direct_reference)?;
+        s.serialize_field(
+            "indirect_reference",
+            &self.indirect_reference.as_ref().map(|s| Hex(s)),
+        )?;
+        s.serialize_field(
+            "data_value_descriptor",
+            &self.data_value_descriptor.as_ref().map(|s| Hex(s)),
+        )?;
+        s.serialize_field("encoding", &self.encoding)?;
+        s.end()
+    }
+}
+
+impl<'a> Serialize for BerPdvIdentification<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "BerPdvIdentification";
+        match self {
+            BerPdvIdentification::Syntaxes(a, t) => serializer.serialize_newtype_variant(
+                NAME,
+                0,
+                "Syntaxes",
+                &(a.to_id_string(), t.to_id_string()),
+            ),
+            BerPdvIdentification::Syntax(oid) => {
+                serializer.serialize_newtype_variant(NAME, 1, "Syntax", &oid.to_id_string())
+            }
+            BerPdvIdentification::PresentationContextId(s) => {
+                serializer.serialize_newtype_variant(NAME, 2, "PresentationContextId", &Hex(s))
+            }
+            BerPdvIdentification::ContextNegotiation(s, oid) => serializer
+                .serialize_newtype_variant(
+                    NAME,
+                    3,
+                    "ContextNegotiation",
+                    &(Hex(s), oid.to_id_string()),
+                ),
+            BerPdvIdentification::TransferSyntax(oid) => {
+                serializer.serialize_newtype_variant(NAME, 4, "TransferSyntax", &oid.to_id_string())
+            }
+            BerPdvIdentification::Fixed => serializer.serialize_unit_variant(NAME, 5, "Fixed"),
+        }
+    }
+}
+
+impl<'a> Serialize for BerEmbeddedPdv<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("EmbeddedPdv", 2)?;
+        s.serialize_field("identification", &self.identification)?;
+        s.serialize_field("data_value", &Hex(&self.data_value))?;
+        s.end()
+    }
+}
+
+/// Serialize object to a readable form, for ex. to inspect parsed objects as JSON
+///
+/// Only the content is serialized (see `BerObjectContent`), the header is ignored.
+///
+/// *This implementation is only available if the `serde` feature is enabled.*
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'a> Serialize for BerObject<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.content.serialize(serializer)
+    }
+}
+
+/// Serialize content to a readable form, for ex. to inspect parsed objects as JSON
+///
+/// Content is serialized as a variant named after the type of the object, with the
+/// following values:
+/// - integers are serialized as numbers if they fit into `i64`, as hex strings otherwise
+/// - OIDs are serialized as dotted strings
+/// - OCTET STRING, and strings with no defined charset, are serialized as hex strings
+/// - Sequence and Set are serialized as arrays
+/// - Tagged and Unknown objects are serialized as structures with the tag and value
+/// - External and EmbeddedPdv objects are serialized as structures with their components
+///
+/// Deserialization is not supported.
+///
+/// *This implementation is only available if the `serde` feature is enabled.*
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'a> Serialize for BerObjectContent<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        const NAME: &str = "BerObjectContent";
+        match self {
+            BerObjectContent::EndOfContent => {
+                serializer.serialize_unit_variant(NAME, 0, "EndOfContent")
+            }
+            BerObjectContent::Boolean(b) => {
+                serializer.serialize_newtype_variant(NAME, 1, "Boolean", b)
+            }
+            BerObjectContent::Integer(i) => match self.as_i64() {
+                Ok(v) => serializer.serialize_newtype_variant(NAME, 2, "Integer", &v),
+                Err(_) => serializer.serialize_newtype_variant(NAME, 2, "Integer", &Hex(i)),
+            },
+            BerObjectContent::BitString(b) => serializer.serialize_newtype_variant(
+                NAME,
+                3,
+                "BitString",
+                &BitStringValue(b.unused_bits(), &b.data),
+            ),
+            BerObjectContent::BitStringConstructed(u, v) => {
+                serializer.serialize_newtype_variant(NAME, 3, "BitString", &BitStringValue(*u, v))
+            }
+            BerObjectContent::OctetString(s) => {
+                serializer.serialize_newtype_variant(NAME, 4, "OctetString", &Hex(s))
+            }
+            BerObjectContent::OctetStringConstructed(v) => {
+                serializer.serialize_newtype_variant(NAME, 4, "OctetString", &Hex(v))
+            }
+            BerObjectContent::Null => serializer.serialize_unit_variant(NAME, 5, "Null"),
+            BerObjectContent::Real(f) => serializer.serialize_newtype_variant(NAME, 6, "Real", f),
+            BerObjectContent::Enum(i) => serializer.serialize_newtype_variant(NAME, 7, "Enum", i),
+            BerObjectContent::OID(oid) => {
+                serializer.serialize_newtype_variant(NAME, 8, "OID", &oid.to_id_string())
+            }
+            BerObjectContent::RelativeOID(oid) => {
+                serializer.serialize_newtype_variant(NAME, 9, "RelativeOID", &oid.to_id_string())
+            }
+            BerObjectContent::NumericString(s) => {
+                serializer.serialize_newtype_variant(NAME, 10, "NumericString", s)
+            }
+            BerObjectContent::VisibleString(s) => {
+                serializer.serialize_newtype_variant(NAME, 11, "VisibleString", s)
+            }
+            BerObjectContent::PrintableString(s) => {
+                serializer.serialize_newtype_variant(NAME, 12, "PrintableString", s)
+            }
+            BerObjectContent::IA5String(s) => {
+                serializer.serialize_newtype_variant(NAME, 13, "IA5String", s)
+            }
+            BerObjectContent::UTF8String(s) => {
+                serializer.serialize_newtype_variant(NAME, 14, "UTF8String", s)
+            }
+            BerObjectContent::T61String(s) => {
+                serializer.serialize_newtype_variant(NAME, 15, "T61String", &Hex(s))
+            }
+            BerObjectContent::VideotexString(s) => {
+                serializer.serialize_newtype_variant(NAME, 16, "VideotexString", &Hex(s))
+            }
+            BerObjectContent::BmpString(s) => {
+                serializer.serialize_newtype_variant(NAME, 17, "BmpString", &Hex(s))
+            }
+            BerObjectContent::UniversalString(s) => {
+                serializer.serialize_newtype_variant(NAME, 18, "UniversalString", &Hex(s))
+            }
+            BerObjectContent::Sequence(v) => {
+                serializer.serialize_newtype_variant(NAME, 19, "Sequence", v)
+            }
+            BerObjectContent::Set(v) => serializer.serialize_newtype_variant(NAME, 20, "Set", v),
+            BerObjectContent::UTCTime(s) => {
+                serializer.serialize_newtype_variant(NAME, 21, "UTCTime", s)
+            }
+            BerObjectContent::GeneralizedTime(s) => {
+                serializer.serialize_newtype_variant(NAME, 22, "GeneralizedTime", s)
+            }
+            BerObjectContent::ObjectDescriptor(s) => {
+                serializer.serialize_newtype_variant(NAME, 23, "ObjectDescriptor", &Hex(s))
+            }
+            BerObjectContent::GraphicString(s) => {
+                serializer.serialize_newtype_variant(NAME, 24, "GraphicString", &Hex(s))
+            }
+            BerObjectContent::GeneralString(s) => {
+                serializer.serialize_newtype_variant(NAME, 25, "GeneralString", &Hex(s))
+            }
+            BerObjectContent::External(e) => {
+                serializer.serialize_newtype_variant(NAME, 28, "External", e)
+            }
+            BerObjectContent::EmbeddedPdv(p) => {
+                serializer.serialize_newtype_variant(NAME, 29, "EmbeddedPdv", p)
+            }
+            // optional values are transparent
+            BerObjectContent::Optional(Some(obj)) => obj.serialize(serializer),
+            BerObjectContent::Optional(None) => serializer.serialize_none(),
+            BerObjectContent::Tagged(class, tag, obj) => {
+                let value = TaggedValue {
+                    class: Some(*class),
+                    tag: *tag,
+                    value: obj.as_ref(),
+                };
+                serializer.serialize_newtype_variant(NAME, 26, "Tagged", &value)
+            }
+            BerObjectContent::Unknown(tag, s) => {
+                let value = TaggedValue {
+                    class: None,
+                    tag: *tag,
+                    value: &Hex(s),
+                };
+                serializer.serialize_newtype_variant(NAME, 27, "Unknown", &value)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::oid::Oid;
+    use alloc::borrow::Cow;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_serde_json() {
+        let oid = Oid::from(&[1, 2, 840, 113_549, 1, 1, 5]).unwrap();
+        let obj = BerObject::from_seq(vec![
+            BerObject::from_int_slice(b"\x01\x00\x01"),
+            BerObject::from_int_slice(&hex!("00 ff ff ff ff ff ff ff ff ff")),
+            BerObject::from_oid(oid),
+            BerObject::from_obj(BerObjectContent::Null),
+            BerObject::from_octetstring(b"\x01\xab"),
+            BerObject::from_set(vec![BerObject::from_obj(BerObjectContent::UTF8String(
+                Cow::Borrowed("abc"),
+            ))]),
+            BerObject::from_obj(BerObjectContent::Tagged(
+                BerClass::ContextSpecific,
+                BerTag(0),
+                Box::new(BerObject::from_bool(true)),
+            )),
+        ]);
+        let s = serde_json::to_string(&obj).expect("serialization failed");
+        assert_eq!(
+            s,
+            r#"{"Sequence":[{"Integer":65537},{"Integer":"00ffffffffffffffffff"},{"OID":"1.2.840.113549.1.1.5"},"Null",{"OctetString":"01ab"},{"Set":[{"UTF8String":"abc"}]},{"Tagged":{"class":"CONTEXT-SPECIFIC","tag":0,"value":{"Boolean":true}}}]}"#
+        );
+    }
+
+    #[test]
+    fn test_serde_json_external() {
+        let bytes = hex!("28 0b 06 02 51 01 a0 05 0c 03 61 62 63");
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        let s = serde_json::to_string(&obj).expect("serialization failed");
+        assert_eq!(
+            s,
+            r#"{"External":{"direct_reference":"2.1.1","indirect_reference":null,"data_value_descriptor":null,"encoding":{"SingleAsn1Type":{"UTF8String":"abc"}}}}"#
+        );
+        let bytes = hex!("2b 0b a0 04 81 02 51 01 82 03 61 62 63");
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        let s = serde_json::to_string(&obj).expect("serialization failed");
+        assert_eq!(
+            s,
+            r#"{"EmbeddedPdv":{"identification":{"Syntax":"2.1.1"},"data_value":"616263"}}"#
+        );
+    }
+}
@@ -6,7 +6,8 @@ use cookie_factory::gen_simple;
 use cookie_factory::multi::many_ref;
 use cookie_factory::sequence::tuple;
 use cookie_factory::{GenError, SerializeFn};
-use std::io::Write;
+use std::io::{self, Write};
+use std::slice;
 
 // we do not use .copied() for compatibility with 1.34
 #[allow(clippy::map_clone)]
@@ -15,7 +16,7 @@ fn encode_length<'a, W: Write + 'a, Len: Into<BerSize>>(len: Len) -> impl Serial
     move |out| {
         match l {
             BerSize::Definite(sz) => {
-                if sz <= 128 {
+                if sz < 128 {
                     // definite, short form
                     be_u8(sz as u8)(out)
                 } else {
@@ -46,15 +47,51 @@ pub fn ber_encode_header<'a, 'b: 'a, W: Write + 'a>(
         // identifier octets (X.690 8.1.2)
         let class_u8 = (hdr.class as u8) << 6;
         let pc_u8 = (hdr.structured & 1) << 5;
-        if hdr.tag.0 >= 30 {
-            unimplemented!();
+        if hdr.tag.0 < 31 {
+            let byte_0 = class_u8 | pc_u8 | (hdr.tag.0 as u8);
+            // length octets (X.690 8.1.3)
+            tuple((be_u8(byte_0), encode_length(hdr.len)))(out)
+        } else {
+            // high tag number form (X.690 8.1.2.4)
+            let byte_0 = class_u8 | pc_u8 | 0x1f;
+            tuple((
+                be_u8(byte_0),
+                slice(encode_tag_number(hdr.tag.0)),
+                encode_length(hdr.len),
+            ))(out)
         }
-        let byte_0 = class_u8 | pc_u8 | (hdr.tag.0 as u8);
-        // length octets (X.690 8.1.3)
-        tuple((be_u8(byte_0), encode_length(hdr.len)))(out)
     }
 }
 
+/// Encode tag number as base 128, most significant group first, with bit 8 set on all groups
+/// except the last one
+fn encode_tag_number(tag: u32) -> Vec<u8> {
+    let mut v = Vec::with_capacity(ber_tag_number_len(tag));
+    let mut shift = 7 * (ber_tag_number_len(tag) - 1);
+    while shift > 0 {
+        v.push(0x80 | ((tag >> shift) & 0x7f) as u8);
+        shift -= 7;
+    }
+    v.push((tag & 0x7f) as u8);
+    v
+}
+
+/// Number of base 128 groups required to encode a tag number in the high tag number form
+fn ber_tag_number_len(tag: u32) -> usize {
+    let bits = 32 - tag.leading_zeros() as usize;
+    core::cmp::max(1, (bits + 6) / 7)
+}
+
+/// Encode the value of an ENUMERATED as a minimal two's complement integer (X.690 8.3.2)
+fn encode_enum(i: u64) -> Vec<u8> {
+    let bytes = i.to_be_bytes();
+    let mut s = &bytes[..];
+    while s.len() > 1 && ((s[0] == 0 && s[1] & 0x80 == 0) || (s[0] == 0xff && s[1] & 0x80 != 0)) {
+        s = &s[1..];
+    }
+    s.to_vec()
+}
+
 fn ber_encode_oid<'a, W: Write + 'a>(oid: &'a Oid) -> impl SerializeFn<W> + 'a {
     move |out| {
         // check oid.relative attribute ? this should not be necessary
@@ -62,10 +99,93 @@ fn ber_encode_oid<'a, W: Write + 'a>(oid: &'a Oid) -> impl SerializeFn<W> + 'a {
     }
 }
 
+/// Split a finite, non-zero value into sign bit, mantissa and exponent, using the DER
+/// constraints for binary encoding, base 2 (X.690 11.3.1): the mantissa is odd
+fn real_binary_parts(f: f64) -> (u8, u64, i64) {
+    let bits = f.to_bits();
+    let sign = (bits >> 63) as u8;
+    let biased_exp = ((bits >> 52) & 0x7ff) as i64;
+    let fraction = bits & ((1 << 52) - 1);
+    let (mut mantissa, mut exp) = if biased_exp == 0 {
+        (fraction, -1074)
+    } else {
+        (fraction | (1 << 52), biased_exp - 1075)
+    };
+    // mantissa must be odd
+    let tz = mantissa.trailing_zeros();
+    mantissa >>= tz;
+    exp += i64::from(tz);
+    (sign, mantissa, exp)
+}
+
+/// Length of the content produced by `encode_real`
+fn encode_real_len(f: f64) -> usize {
+    if f.is_nan() || f.is_infinite() {
+        return 1;
+    }
+    if f == 0.0 {
+        return if f.is_sign_negative() { 1 } else { 0 };
+    }
+    let (_, mantissa, exp) = real_binary_parts(f);
+    let exp_len = if exp >= -128 && exp <= 127 { 1 } else { 2 };
+    1 + exp_len + 8 - (mantissa.leading_zeros() / 8) as usize
+}
+
+// we do not use .copied() for compatibility with 1.34
+#[allow(clippy::map_clone)]
+fn encode_real(f: f64) -> Vec<u8> {
+    // special real values (X.690 8.5.9)
+    if f.is_nan() {
+        return vec![0x42];
+    }
+    if f.is_infinite() {
+        return if f > 0.0 { vec![0x40] } else { vec![0x41] };
+    }
+    if f == 0.0 {
+        return if f.is_sign_negative() {
+            vec![0x43]
+        } else {
+            vec![]
+        };
+    }
+    let (sign, mantissa, exp) = real_binary_parts(f);
+    let exp_bytes: Vec<u8> = if exp >= -128 && exp <= 127 {
+        vec![exp as u8]
+    } else {
+        (exp as i16).to_be_bytes().iter().map(|&x| x).collect()
+    };
+    let b0 = 0x80 | (sign << 6) | (exp_bytes.len() as u8 - 1);
+    let mut v = vec![b0];
+    v.extend_from_slice(&exp_bytes);
+    v.extend(
+        mantissa
+            .to_be_bytes()
+            .iter()
+            .map(|&x| x)
+            .skip_while(|&b| b == 0),
+    );
+    v
+}
+
 fn ber_encode_sequence<'a, W: Write + Default + AsRef<[u8]> + 'a>(
     v: &'a [BerObject],
+    der: bool,
 ) -> impl SerializeFn<W> + 'a {
-    many_ref(v, ber_encode_object)
+    many_ref(v, move |obj| encode_object(obj, der))
+}
+
+/// Encode the elements of a SET OF, sorted by their encodings in ascending order (X.690 11.6)
+fn der_encode_set<'a, W: Write + Default + AsRef<[u8]> + 'a>(
+    v: &'a [BerObject],
+) -> impl SerializeFn<W> + 'a {
+    move |out| {
+        let mut encoded = v
+            .iter()
+            .map(|obj| gen_simple(der_encode_object(obj), Vec::new()))
+            .collect::<Result<Vec<_>, _>>()?;
+        encoded.sort();
+        slice(encoded.concat())(out)
+    }
 }
 
 /// Encode the provided object in an EXPLICIT tagged value, using the provided tag ans class
@@ -108,32 +228,52 @@ pub fn ber_encode_tagged_implicit<'a, W: Write + Default + AsRef<[u8]> + 'a>(
     }
 }
 
+fn ber_encode_object_content<'a, W: Write + Default + AsRef<[u8]> + 'a>(
+    c: &'a BerObjectContent,
+) -> impl SerializeFn<W> + 'a {
+    encode_object_content(c, false)
+}
+
+/// Encode object content, using the DER rules for children if `der` is true
 // we do not use .copied() for compatibility with 1.34
 #[allow(clippy::map_clone)]
-fn ber_encode_object_content<'a, W: Write + Default + AsRef<[u8]> + 'a>(
+fn encode_object_content<'a, W: Write + Default + AsRef<[u8]> + 'a>(
     c: &'a BerObjectContent,
+    der: bool,
 ) -> impl SerializeFn<W> + 'a {
     move |out| match c {
-        BerObjectContent::EndOfContent => be_u8(0)(out),
+        BerObjectContent::EndOfContent => Ok(out),
         BerObjectContent::Boolean(b) => {
             let b0 = if *b { 0xff } else { 0x00 };
             be_u8(b0)(out)
         }
+        BerObjectContent::Integer(s) if der => slice(trim_integer_padding(s))(out),
         BerObjectContent::Integer(s) => slice(s)(out),
-        BerObjectContent::BitString(ignored_bits, s) => {
-            tuple((be_u8(*ignored_bits), slice(s)))(out)
+        BerObjectContent::BitString(s) if der => {
+            der_encode_bitstring(s.unused_bits(), &s.data)(out)
         }
+        BerObjectContent::BitString(s) => tuple((be_u8(s.unused_bits()), slice(s)))(out),
         BerObjectContent::OctetString(s) => slice(s)(out),
-        BerObjectContent::Null => Ok(out),
-        BerObjectContent::Enum(i) => {
-            let v: Vec<u8> = i
-                .to_be_bytes()
-                .iter()
-                .map(|&x| x)
-                .skip_while(|&b| b == 0)
-                .collect();
+        // DER uses the primitive form for strings (X.690 10.2)
+        BerObjectContent::BitStringConstructed(ignored_bits, v) if der => {
+            der_encode_bitstring(*ignored_bits, v)(out)
+        }
+        BerObjectContent::OctetStringConstructed(v) if der => slice(v)(out),
+        BerObjectContent::BitStringConstructed(ignored_bits, v) => {
+            // encode as a single primitive segment, since the header has the constructed bit
+            let hdr = BerObjectHeader::new(BerClass::Universal, 0, BerTag::BitString, v.len() + 1);
+            let out = tuple((ber_encode_header(&hdr), be_u8(*ignored_bits), slice(v)))(out)?;
+            Ok(out)
+        }
+        BerObjectContent::OctetStringConstructed(v) => {
+            // encode as a single primitive segment, since the header has the constructed bit
+            let segment = BerObject::from_octetstring(v);
+            let v = gen_simple(ber_encode_object(&segment), W::default())?;
             slice(v)(out)
         }
+        BerObjectContent::Null => Ok(out),
+        BerObjectContent::Real(f) => slice(encode_real(*f))(out),
+        BerObjectContent::Enum(i) => slice(encode_enum(*i))(out),
         BerObjectContent::OID(oid) | BerObjectContent::RelativeOID(oid) => ber_encode_oid(oid)(out),
         BerObjectContent::NumericString(s)
         | BerObjectContent::UTCTime(s)
@@ -141,7 +281,7 @@ fn ber_encode_object_content<'a, W: Write + Default + AsRef<[u8]> + 'a>(
         | BerObjectContent::VisibleString(s)
         | BerObjectContent::PrintableString(s)
         | BerObjectContent::IA5String(s)
-        | BerObjectContent::UTF8String(s) => slice(s)(out),
+        | BerObjectContent::UTF8String(s) => slice(s.as_bytes())(out),
         BerObjectContent::T61String(s)
         | BerObjectContent::VideotexString(s)
         | BerObjectContent::BmpString(s)
@@ -149,24 +289,146 @@ fn ber_encode_object_content<'a, W: Write + Default + AsRef<[u8]> + 'a>(
         | BerObjectContent::ObjectDescriptor(s)
         | BerObjectContent::GraphicString(s)
         | BerObjectContent::GeneralString(s) => slice(s)(out),
-        BerObjectContent::Sequence(v) | BerObjectContent::Set(v) => ber_encode_sequence(&v)(out),
+        BerObjectContent::Set(v) if der => der_encode_set(v)(out),
+        BerObjectContent::Sequence(v) | BerObjectContent::Set(v) => {
+            ber_encode_sequence(v, der)(out)
+        }
         // best we can do is tagged-explicit, but we don't know
         BerObjectContent::Optional(inner) => {
             // directly encode inner object
             match inner {
-                Some(obj) => ber_encode_object_content(&obj.content)(out),
+                Some(obj) => encode_object_content(&obj.content, der)(out),
                 None => slice(&[])(out), // XXX encode NOP ?
             }
         }
         BerObjectContent::Tagged(_class, _tag, inner) => {
             // directly encode inner object
             // XXX wrong, we should wrap it!
-            ber_encode_object(inner)(out)
+            encode_object(inner, der)(out)
+        }
+        BerObjectContent::External(_) | BerObjectContent::EmbeddedPdv(_) => {
+            let components = external_components(c);
+            let v = gen_simple(ber_encode_sequence(&components, der), W::default())?;
+            slice(v)(out)
         }
         BerObjectContent::Unknown(_tag, s) => slice(s)(out),
     }
 }
 
+/// Build a context-specific object, with an IMPLICIT tag
+fn context_object<'a>(tag: u32, structured: u8, content: BerObjectContent<'a>) -> BerObject<'a> {
+    let hdr = BerObjectHeader::new(BerClass::ContextSpecific, structured, BerTag(tag), 0);
+    BerObject::from_header_and_content(hdr, content)
+}
+
+/// Build the components of `External` and `EmbeddedPdv` objects, to encode them as SEQUENCE
+/// content (other objects have no components)
+fn external_components<'a>(c: &BerObjectContent<'a>) -> Vec<BerObject<'a>> {
+    let mut v = Vec::new();
+    match c {
+        BerObjectContent::External(e) => {
+            if let Some(ref oid) = e.direct_reference {
+                v.push(BerObject::from_oid(oid.clone()));
+            }
+            if let Some(ref s) = e.indirect_reference {
+                v.push(BerObject::from_obj(BerObjectContent::Integer(s.clone())));
+            }
+            if let Some(ref s) = e.data_value_descriptor {
+                v.push(BerObject::from_obj(BerObjectContent::ObjectDescriptor(
+                    s.clone(),
+                )));
+            }
+            let encoding = match e.encoding {
+                BerExternalEncoding::SingleAsn1Type(ref obj) => {
+                    let tagged =
+                        BerObjectContent::Tagged(BerClass::ContextSpecific, BerTag(0), obj.clone());
+                    context_object(0, 1, tagged)
+                }
+                BerExternalEncoding::OctetAligned(ref s) => {
+                    context_object(1, 0, BerObjectContent::OctetString(s.clone()))
+                }
+                BerExternalEncoding::OctetAlignedConstructed(ref v) => {
+                    context_object(1, 1, BerObjectContent::OctetStringConstructed(v.clone()))
+                }
+                BerExternalEncoding::Arbitrary(ref b) => {
+                    context_object(2, 0, BerObjectContent::BitString(b.clone()))
+                }
+                BerExternalEncoding::ArbitraryConstructed(u, ref v) => {
+                    context_object(2, 1, BerObjectContent::BitStringConstructed(u, v.clone()))
+                }
+            };
+            v.push(encoding);
+        }
+        BerObjectContent::EmbeddedPdv(p) => {
+            let oid =
+                |tag, oid: &Oid<'a>| context_object(tag, 0, BerObjectContent::OID(oid.clone()));
+            let identification = match p.identification {
+                BerPdvIdentification::Syntaxes(ref a, ref t) => {
+                    let l = vec![oid(0, a), oid(1, t)];
+                    context_object(0, 1, BerObjectContent::Sequence(l))
+                }
+                BerPdvIdentification::Syntax(ref o) => oid(1, o),
+                BerPdvIdentification::PresentationContextId(ref s) => {
+                    context_object(2, 0, BerObjectContent::Integer(s.clone()))
+                }
+                BerPdvIdentification::ContextNegotiation(ref s, ref o) => {
+                    let l = vec![
+                        context_object(0, 0, BerObjectContent::Integer(s.clone())),
+                        oid(1, o),
+                    ];
+                    context_object(3, 1, BerObjectContent::Sequence(l))
+                }
+                BerPdvIdentification::TransferSyntax(ref o) => oid(4, o),
+                BerPdvIdentification::Fixed => context_object(5, 0, BerObjectContent::Null),
+            };
+            // the identification is a CHOICE, so the tag is EXPLICIT
+            let tagged = BerObjectContent::Tagged(
+                BerClass::ContextSpecific,
+                BerTag(0),
+                Box::new(identification),
+            );
+            v.push(context_object(0, 1, tagged));
+            v.push(context_object(
+                2,
+                0,
+                BerObjectContent::OctetString(p.data_value.clone()),
+            ));
+        }
+        _ => (),
+    }
+    v
+}
+
+/// Encode the content of a bit string, with the unused bits set to zero (X.690 11.2.1)
+fn der_encode_bitstring<'a, W: Write + 'a>(
+    ignored_bits: u8,
+    data: &'a [u8],
+) -> impl SerializeFn<W> + 'a {
+    move |out| match data.split_last() {
+        Some((&last, data)) => tuple((
+            be_u8(ignored_bits),
+            slice(data),
+            be_u8(der_mask_unused_bits(ignored_bits, last)),
+        ))(out),
+        None => be_u8(ignored_bits)(out),
+    }
+}
+
+#[inline]
+fn der_mask_unused_bits(ignored_bits: u8, b: u8) -> u8 {
+    b & 0xffu8.checked_shl(u32::from(ignored_bits)).unwrap_or(0)
+}
+
+/// Return true if the content is a string using the constructed form
+#[inline]
+fn is_constructed_string(c: &BerObjectContent) -> bool {
+    match c {
+        BerObjectContent::BitStringConstructed(_, _)
+        | BerObjectContent::OctetStringConstructed(_) => true,
+        _ => false,
+    }
+}
+
 /// Encode header and object content as BER, without any validation
 ///
 /// Note that the encoding will not check *any* `field of the header (including length)
@@ -192,20 +454,365 @@ pub fn ber_encode_object_raw<'a, 'b: 'a, 'c: 'a, W: Write + Default + AsRef<[u8]
 #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
 pub fn ber_encode_object<'a, 'b: 'a, W: Write + Default + AsRef<[u8]> + 'a>(
     obj: &'b BerObject,
+) -> impl SerializeFn<W> + 'a {
+    encode_object(obj, false)
+}
+
+/// Encode object as DER
+///
+/// This is the same as `ber_encode_object`, except that the object is converted to the DER form
+/// (X.690 section 10 and 11):
+/// - the elements of `Set` objects are sorted by their encodings, as required for SET OF
+/// - redundant leading octets of `Integer` objects are removed
+/// - the unused bits of `BitString` objects are set to zero
+/// - constructed strings are encoded using the primitive form
+///
+/// Definite lengths are always used. Other constraints (for ex. on the content of strings or
+/// times) are not checked.
+///
+/// *This function is only available if the `serialize` feature is enabled.*
+#[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+pub fn der_encode_object<'a, 'b: 'a, W: Write + Default + AsRef<[u8]> + 'a>(
+    obj: &'b BerObject,
+) -> impl SerializeFn<W> + 'a {
+    encode_object(obj, true)
+}
+
+fn encode_object<'a, 'b: 'a, W: Write + Default + AsRef<[u8]> + 'a>(
+    obj: &'b BerObject,
+    der: bool,
 ) -> impl SerializeFn<W> + 'a {
     move |out| {
         // XXX should we make an exception for tagged values here ?
-        let v = gen_simple(ber_encode_object_content(&obj.content), W::default())?;
+        let v = gen_simple(encode_object_content(&obj.content, der), W::default())?;
         let len = v.as_ref().len();
-        let hdr = obj.header.clone().with_len(len.into());
+        let mut hdr = obj.header.clone().with_len(len.into());
+        if der && is_constructed_string(&obj.content) {
+            hdr.structured = 0;
+        }
         let v_hdr = gen_simple(ber_encode_header(&hdr), W::default())?;
         tuple((slice(v_hdr), slice(v)))(out)
     }
 }
 
+/// Maximum number of content octets of a primitive string segment in CER (X.690 9.2)
+const CER_SEGMENT_LEN: usize = 1000;
+
+/// Encode object as CER
+///
+/// The following Canonical Encoding Rules are applied:
+/// - constructed objects (`Sequence`, `Set` and `Tagged`) use the indefinite length form (X.690 9.1)
+/// - `OctetString` objects with more than 1000 bytes of content are encoded as constructed,
+///   using segments of 1000 bytes (X.690 9.2)
+/// - elements of `Set` objects are sorted by their encodings, as for DER (X.690 9.3)
+///
+/// Other objects are encoded as DER. Other string types are not segmented.
+///
+/// *This function is only available if the `serialize` feature is enabled.*
+#[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+pub fn cer_encode_object<'a, 'b: 'a, W: Write + Default + AsRef<[u8]> + 'a>(
+    obj: &'b BerObject,
+) -> impl SerializeFn<W> + 'a {
+    move |out| {
+        let mut v = Vec::new();
+        cer_encode(obj, &mut v)?;
+        slice(v)(out)
+    }
+}
+
+fn cer_encode_indefinite_header(hdr: &BerObjectHeader, v: &mut Vec<u8>) -> Result<(), GenError> {
+    let hdr = BerObjectHeader::new(hdr.class, 1, hdr.tag, BerSize::Indefinite);
+    let h = gen_simple(ber_encode_header(&hdr), Vec::new())?;
+    v.extend_from_slice(&h);
+    Ok(())
+}
+
+fn cer_encode(obj: &BerObject, v: &mut Vec<u8>) -> Result<(), GenError> {
+    match obj.content {
+        BerObjectContent::OctetString(ref s) => cer_encode_octetstring(&obj.header, s, v)?,
+        BerObjectContent::OctetStringConstructed(ref s) => {
+            cer_encode_octetstring(&obj.header, s, v)?
+        }
+        BerObjectContent::Sequence(ref l) => {
+            cer_encode_indefinite_header(&obj.header, v)?;
+            for child in l {
+                cer_encode(child, v)?;
+            }
+            v.extend_from_slice(&[0, 0]);
+        }
+        BerObjectContent::Set(ref l) => {
+            cer_encode_indefinite_header(&obj.header, v)?;
+            let mut encoded = l
+                .iter()
+                .map(|child| {
+                    let mut e = Vec::new();
+                    cer_encode(child, &mut e).map(|_| e)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            encoded.sort();
+            for e in encoded {
+                v.extend_from_slice(&e);
+            }
+            v.extend_from_slice(&[0, 0]);
+        }
+        BerObjectContent::Tagged(_, _, ref inner) => {
+            cer_encode_indefinite_header(&obj.header, v)?;
+            cer_encode(inner, v)?;
+            v.extend_from_slice(&[0, 0]);
+        }
+        _ => {
+            let e = gen_simple(der_encode_object(obj), Vec::new())?;
+            v.extend_from_slice(&e);
+        }
+    }
+    Ok(())
+}
+
+fn cer_encode_octetstring(
+    hdr: &BerObjectHeader,
+    s: &[u8],
+    v: &mut Vec<u8>,
+) -> Result<(), GenError> {
+    if s.len() <= CER_SEGMENT_LEN {
+        // short strings use the primitive form
+        let hdr = BerObjectHeader::new(hdr.class, 0, hdr.tag, s.len());
+        let h = gen_simple(ber_encode_header(&hdr), Vec::new())?;
+        v.extend_from_slice(&h);
+        v.extend_from_slice(s);
+        return Ok(());
+    }
+    cer_encode_indefinite_header(hdr, v)?;
+    for chunk in s.chunks(CER_SEGMENT_LEN) {
+        let segment =
+            BerObjectHeader::new(BerClass::Universal, 0, BerTag::OctetString, chunk.len());
+        let h = gen_simple(ber_encode_header(&segment), Vec::new())?;
+        v.extend_from_slice(&h);
+        v.extend_from_slice(chunk);
+    }
+    v.extend_from_slice(&[0, 0]);
+    Ok(())
+}
+
+/// Number of identifier and length octets required to encode a header
+fn ber_header_len(tag: BerTag, len: usize) -> usize {
+    let tag_len = if tag.0 < 31 {
+        1
+    } else {
+        1 + ber_tag_number_len(tag.0)
+    };
+    tag_len + ber_length_len(len)
+}
+
+/// Number of length octets required to encode a definite length
+fn ber_length_len(sz: usize) -> usize {
+    if sz < 128 {
+        1
+    } else {
+        1 + sz.to_be_bytes().iter().skip_while(|&&b| b == 0).count()
+    }
+}
+
+/// Length of the DER-encoded content, without encoding it
+///
+/// This must be kept in sync with `der_encode_object`.
+fn ber_content_len(c: &BerObjectContent) -> usize {
+    match c {
+        BerObjectContent::EndOfContent => 0,
+        BerObjectContent::Boolean(_) => 1,
+        BerObjectContent::Integer(s) => trim_integer_padding(s).len(),
+        BerObjectContent::OctetString(s) | BerObjectContent::Unknown(_, s) => s.len(),
+        BerObjectContent::OctetStringConstructed(v) => v.len(),
+        BerObjectContent::BitString(s) => 1 + s.data.len(),
+        BerObjectContent::BitStringConstructed(_, v) => 1 + v.len(),
+        BerObjectContent::Null => 0,
+        BerObjectContent::Real(f) => encode_real_len(*f),
+        BerObjectContent::Enum(i) => encode_enum(*i).len(),
+        BerObjectContent::OID(oid) | BerObjectContent::RelativeOID(oid) => oid.bytes().len(),
+        BerObjectContent::NumericString(s)
+        | BerObjectContent::UTCTime(s)
+        | BerObjectContent::GeneralizedTime(s)
+        | BerObjectContent::VisibleString(s)
+        | BerObjectContent::PrintableString(s)
+        | BerObjectContent::IA5String(s)
+        | BerObjectContent::UTF8String(s) => s.len(),
+        BerObjectContent::T61String(s)
+        | BerObjectContent::VideotexString(s)
+        | BerObjectContent::BmpString(s)
+        | BerObjectContent::UniversalString(s)
+        | BerObjectContent::ObjectDescriptor(s)
+        | BerObjectContent::GraphicString(s)
+        | BerObjectContent::GeneralString(s) => s.len(),
+        BerObjectContent::Sequence(v) | BerObjectContent::Set(v) => {
+            v.iter().map(ber_total_len).sum()
+        }
+        BerObjectContent::Optional(inner) => inner
+            .as_ref()
+            .map_or(0, |obj| ber_content_len(&obj.content)),
+        BerObjectContent::Tagged(_class, _tag, inner) => ber_total_len(inner),
+        BerObjectContent::External(_) | BerObjectContent::EmbeddedPdv(_) => {
+            external_components(c).iter().map(ber_total_len).sum()
+        }
+    }
+}
+
+/// Length of the encoded object (header and content), without encoding it
+fn ber_total_len(obj: &BerObject) -> usize {
+    let len = ber_content_len(&obj.content);
+    ber_header_len(obj.header.tag, len) + len
+}
+
+/// Content lengths of an object and of its children, in the order they are written by
+/// `ber_write_object`
+///
+/// The lengths are computed in a single pass: each object is visited once, instead of once for
+/// each of its ancestors.
+fn der_length_table(obj: &BerObject) -> Vec<usize> {
+    let mut lens = Vec::new();
+    der_length_table_object(obj, &mut lens);
+    lens
+}
+
+/// Add the content length of `obj` (before the lengths of its children), and return the total
+/// length of the object
+fn der_length_table_object(obj: &BerObject, lens: &mut Vec<usize>) -> usize {
+    let idx = lens.len();
+    lens.push(0);
+    let len = der_length_table_content(&obj.content, lens);
+    lens[idx] = len;
+    ber_header_len(obj.header.tag, len) + len
+}
+
+/// This must be kept in sync with `ber_write_content`.
+fn der_length_table_content(c: &BerObjectContent, lens: &mut Vec<usize>) -> usize {
+    match c {
+        BerObjectContent::Sequence(v) => {
+            v.iter().map(|obj| der_length_table_object(obj, lens)).sum()
+        }
+        BerObjectContent::Optional(Some(obj)) => der_length_table_content(&obj.content, lens),
+        BerObjectContent::Tagged(_class, _tag, inner) => der_length_table_object(inner, lens),
+        // elements of sets are encoded separately (to be sorted), so they have no entry
+        _ => ber_content_len(c),
+    }
+}
+
+fn gen_error_to_io(e: GenError) -> io::Error {
+    match e {
+        GenError::IoError(e) => e,
+        e => io::Error::new(io::ErrorKind::Other, e.to_string()),
+    }
+}
+
+fn ber_write_slice<W: Write>(w: &mut W, s: &[u8]) -> io::Result<usize> {
+    w.write_all(s)?;
+    Ok(s.len())
+}
+
+fn ber_write_header<W: Write>(w: &mut W, hdr: &BerObjectHeader) -> io::Result<usize> {
+    let v = gen_simple(ber_encode_header(hdr), Vec::new()).map_err(gen_error_to_io)?;
+    ber_write_slice(w, &v)
+}
+
+/// Write the content of a bit string, with the unused bits set to zero
+fn ber_write_bitstring<W: Write>(w: &mut W, ignored_bits: u8, data: &[u8]) -> io::Result<usize> {
+    let n = ber_write_slice(w, &[ignored_bits])?;
+    match data.split_last() {
+        Some((&last, data)) => {
+            let n = n + ber_write_slice(w, data)?;
+            Ok(n + ber_write_slice(w, &[der_mask_unused_bits(ignored_bits, last)])?)
+        }
+        None => Ok(n),
+    }
+}
+
+/// Stream the DER-encoded content to the writer
+///
+/// Large values and children are written directly, without intermediate buffers. The lengths of
+/// the children are read from `lens` (see `der_length_table`).
+fn ber_write_content<W: Write>(
+    w: &mut W,
+    c: &BerObjectContent,
+    lens: &mut slice::Iter<usize>,
+) -> io::Result<usize> {
+    match c {
+        BerObjectContent::Integer(s) => ber_write_slice(w, trim_integer_padding(s)),
+        BerObjectContent::OctetString(s) | BerObjectContent::Unknown(_, s) => ber_write_slice(w, s),
+        BerObjectContent::OctetStringConstructed(v) => ber_write_slice(w, v),
+        BerObjectContent::BitString(s) => ber_write_bitstring(w, s.unused_bits(), &s.data),
+        BerObjectContent::BitStringConstructed(ignored_bits, v) => {
+            ber_write_bitstring(w, *ignored_bits, v)
+        }
+        BerObjectContent::NumericString(s)
+        | BerObjectContent::UTCTime(s)
+        | BerObjectContent::GeneralizedTime(s)
+        | BerObjectContent::VisibleString(s)
+        | BerObjectContent::PrintableString(s)
+        | BerObjectContent::IA5String(s)
+        | BerObjectContent::UTF8String(s) => ber_write_slice(w, s.as_bytes()),
+        BerObjectContent::T61String(s)
+        | BerObjectContent::VideotexString(s)
+        | BerObjectContent::BmpString(s)
+        | BerObjectContent::UniversalString(s)
+        | BerObjectContent::ObjectDescriptor(s)
+        | BerObjectContent::GraphicString(s)
+        | BerObjectContent::GeneralString(s) => ber_write_slice(w, s),
+        BerObjectContent::Set(v) => {
+            // children must be encoded to be sorted
+            let mut encoded = v
+                .iter()
+                .map(|obj| gen_simple(der_encode_object(obj), Vec::new()))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(gen_error_to_io)?;
+            encoded.sort();
+            encoded
+                .iter()
+                .try_fold(0, |acc, v| ber_write_slice(w, v).map(|n| acc + n))
+        }
+        BerObjectContent::Sequence(v) => v.iter().try_fold(0, |acc, obj| {
+            ber_write_object(w, obj, lens).map(|n| acc + n)
+        }),
+        BerObjectContent::Optional(inner) => match inner {
+            Some(obj) => ber_write_content(w, &obj.content, lens),
+            None => Ok(0),
+        },
+        BerObjectContent::Tagged(_class, _tag, inner) => ber_write_object(w, inner, lens),
+        BerObjectContent::External(_) | BerObjectContent::EmbeddedPdv(_) => {
+            // components have no entry in `lens`
+            let v =
+                gen_simple(encode_object_content(c, true), Vec::new()).map_err(gen_error_to_io)?;
+            ber_write_slice(w, &v)
+        }
+        // small values: use the serializer
+        _ => {
+            let v =
+                gen_simple(ber_encode_object_content(c), Vec::new()).map_err(gen_error_to_io)?;
+            ber_write_slice(w, &v)
+        }
+    }
+}
+
+fn ber_write_object<W: Write>(
+    w: &mut W,
+    obj: &BerObject,
+    lens: &mut slice::Iter<usize>,
+) -> io::Result<usize> {
+    let len = *lens
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "missing length"))?;
+    let mut hdr = obj.header.clone().with_len(len.into());
+    if is_constructed_string(&obj.content) {
+        hdr.structured = 0;
+    }
+    let n = ber_write_header(w, &hdr)?;
+    Ok(n + ber_write_content(w, &obj.content, lens)?)
+}
+
 impl<'a> BerObject<'a> {
     /// Attempt to encode object as BER
     ///
+    /// The object is encoded in its current form: for ex. constructed strings and non-minimal
+    /// integers are kept as they were parsed (definite lengths are used, though). Use
+    /// `to_der_vec` to get the canonical DER encoding.
+    ///
     /// Note that the encoding will not check that the values of the `BerObject` fields are correct.
     /// The length is automatically calculated, and the field is ignored.
     ///
@@ -216,12 +823,103 @@ impl<'a> BerObject<'a> {
     pub fn to_vec(&self) -> Result<Vec<u8>, GenError> {
         gen_simple(ber_encode_object(self), Vec::new())
     }
+
+    /// Attempt to encode object as DER
+    ///
+    /// The object is converted to the DER form, regardless of how it was originally encoded:
+    /// indefinite lengths are replaced by definite lengths, elements of `Set` objects are sorted,
+    /// integers are minimized, etc. See `der_encode_object` for the rules which are applied.
+    ///
+    /// *This function is only available if the `serialize` feature is enabled.*
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn to_der_vec(&self) -> Result<Vec<u8>, GenError> {
+        gen_simple(der_encode_object(self), Vec::new())
+    }
+
+    /// Attempt to encode object as CER
+    ///
+    /// See `cer_encode_object` for the rules which are applied.
+    ///
+    /// *This function is only available if the `serialize` feature is enabled.*
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn to_cer_vec(&self) -> Result<Vec<u8>, GenError> {
+        gen_simple(cer_encode_object(self), Vec::new())
+    }
+
+    /// Return the number of bytes of the encoded content, without encoding the object
+    ///
+    /// The header (identifier and length octets) is not included, see `der_total_len`.
+    ///
+    /// *This function is only available if the `serialize` feature is enabled.*
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn der_content_len(&self) -> usize {
+        ber_content_len(&self.content)
+    }
+
+    /// Return the number of bytes of the encoded object (header and content), without
+    /// encoding the object
+    ///
+    /// This is the length of the output of `to_der_vec` and `write_der` (and of `to_vec`, if the
+    /// object is already in DER form).
+    ///
+    /// *This function is only available if the `serialize` feature is enabled.*
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn der_total_len(&self) -> usize {
+        ber_total_len(self)
+    }
+
+    /// Attempt to encode object as DER, streaming the output to the writer
+    ///
+    /// This produces the same encoding as `to_der_vec`, but the content is not buffered: lengths
+    /// of the children are computed first (in a single pass), so the headers can be written
+    /// before the content. Only the elements of `Set` objects are buffered, since they must be
+    /// sorted.
+    ///
+    /// Returns the number of bytes written.
+    ///
+    /// *This function is only available if the `serialize` feature is enabled.*
+    #[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+    pub fn write_der<W: Write>(&self, w: &mut W) -> io::Result<usize> {
+        let lens = der_length_table(self);
+        ber_write_object(w, self, &mut lens.iter())
+    }
+}
+
+/// Check that `bytes` is re-encoded exactly by the DER serializer
+///
+/// The input is parsed using `parse_der`, and encoded using both `to_der_vec` and `write_der`.
+/// Returns `true` only if the input is a single, valid DER object, and both encodings are
+/// identical to the input. This is mostly useful in tests, for ex. to check that a signature
+/// computed over re-encoded data would match the original encoding.
+///
+/// ```rust
+/// use der_parser::ber::roundtrip_der;
+///
+/// assert!(roundtrip_der(&[0x31, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]));
+/// // not DER: elements of the SET are not sorted
+/// assert!(!roundtrip_der(&[0x31, 0x06, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01]));
+/// ```
+///
+/// *This function is only available if the `serialize` feature is enabled.*
+#[cfg_attr(docsrs, doc(cfg(feature = "serialize")))]
+pub fn roundtrip_der(bytes: &[u8]) -> bool {
+    let obj = match crate::der::parse_der(bytes) {
+        Ok((rem, obj)) if rem.is_empty() => obj,
+        _ => return false,
+    };
+    let mut w = Vec::new();
+    match (obj.to_der_vec(), obj.write_der(&mut w)) {
+        (Ok(v), Ok(_)) => v == bytes && w == bytes,
+        _ => false,
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::der::parse_der_real;
     use crate::error::BerResult;
+    use alloc::borrow::Cow;
     use cookie_factory::gen_simple;
     use hex_literal::hex;
 
@@ -266,7 +964,7 @@ mod test {
 
     #[test]
     fn test_encode_integer() {
-        let i = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+        let i = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
         encode_and_parse!(i, ber_encode_object, parse_ber_integer);
     }
 
@@ -274,8 +972,7 @@ mod test {
     fn test_encode_bitstring() {
         let bytes = hex!("03 04 06 6e 5d e0");
         let b = BerObject::from_obj(BerObjectContent::BitString(
-            6,
-            BitStringObject { data: &bytes[3..] },
+            BitStringObject::new(6, &bytes[3..]).unwrap(),
         ));
         let v = encode_and_parse!(b, ber_encode_object, parse_ber_bitstring);
         assert_eq!(&v[..], bytes)
@@ -283,16 +980,39 @@ mod test {
 
     #[test]
     fn test_encode_octetstring() {
-        let i = BerObject::from_obj(BerObjectContent::OctetString(b"AAAAA"));
+        let i = BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(b"AAAAA")));
         let v = encode_and_parse!(i, ber_encode_object, parse_ber_octetstring);
         assert_eq!(&v[..], hex!("04 05 41 41 41 41 41"))
     }
 
+    #[test]
+    fn test_encode_octetstring_constructed() {
+        let i = BerObject::from_obj(BerObjectContent::OctetStringConstructed(b"AAAAA".to_vec()));
+        let v = encode_and_parse!(i, ber_encode_object, parse_ber_octetstring);
+        assert_eq!(&v[..], hex!("24 07 04 05 41 41 41 41 41"))
+    }
+
+    #[test]
+    fn test_encode_bitstring_constructed() {
+        let b = BerObject::from_obj(BerObjectContent::BitStringConstructed(6, vec![0x6e, 0x40]));
+        let v = encode_and_parse!(b, ber_encode_object, parse_ber_bitstring);
+        assert_eq!(&v[..], hex!("23 05 03 03 06 6e 40"))
+    }
+
     #[test]
     fn test_encode_enum() {
         let i = BerObject::from_obj(BerObjectContent::Enum(2));
         let v = encode_and_parse!(i, ber_encode_object, parse_ber_enum);
-        assert_eq!(&v[..], hex!("0a 01 02"))
+        assert_eq!(&v[..], hex!("0a 01 02"));
+        let i = BerObject::from_obj(BerObjectContent::Enum(0));
+        let v = encode_and_parse!(i, ber_encode_object, parse_ber_enum);
+        assert_eq!(&v[..], hex!("0a 01 00"));
+        let i = BerObject::from_obj(BerObjectContent::Enum(0x80));
+        let v = encode_and_parse!(i, ber_encode_object, parse_ber_enum);
+        assert_eq!(&v[..], hex!("0a 02 00 80"));
+        let i = BerObject::from_obj(BerObjectContent::Enum(-129i64 as u64));
+        let v = encode_and_parse!(i, ber_encode_object, parse_ber_enum);
+        assert_eq!(&v[..], hex!("0a 02 ff 7f"));
     }
 
     #[test]
@@ -301,6 +1021,21 @@ mod test {
         encode_and_parse!(i, ber_encode_object, parse_ber_null);
     }
 
+    #[test]
+    fn test_encode_real() {
+        let r = BerObject::from_obj(BerObjectContent::Real(0.15625));
+        let v = encode_and_parse!(r, ber_encode_object, parse_der_real);
+        assert_eq!(&v[..], hex!("09 03 80 fb 05"));
+        let r = BerObject::from_obj(BerObjectContent::Real(-1.0e300));
+        encode_and_parse!(r, ber_encode_object, parse_der_real);
+        let r = BerObject::from_obj(BerObjectContent::Real(0.0));
+        let v = encode_and_parse!(r, ber_encode_object, parse_der_real);
+        assert_eq!(&v[..], hex!("09 00"));
+        let r = BerObject::from_obj(BerObjectContent::Real(core::f64::NEG_INFINITY));
+        let v = encode_and_parse!(r, ber_encode_object, parse_der_real);
+        assert_eq!(&v[..], hex!("09 01 41"));
+    }
+
     #[test]
     fn test_encode_oid() {
         let bytes = hex!("06 09 2A 86 48 86 F7 0D 01 01 05");
@@ -412,4 +1147,330 @@ mod test {
         let bytes = hex!("62 03 02 01 02");
         assert_eq!(&v[..], bytes);
     }
+
+    #[test]
+    fn test_encode_length_128() {
+        // 128 requires the long form, 0x80 would mean indefinite length
+        let v = gen_simple(encode_length(128), Vec::new()).expect("could not serialize");
+        assert_eq!(&v[..], &[0x81, 0x80]);
+        let v = gen_simple(encode_length(127), Vec::new()).expect("could not serialize");
+        assert_eq!(&v[..], &[0x7f]);
+    }
+
+    #[test]
+    fn test_write_der_large_octetstring() {
+        let data = vec![0x5a; 1024 * 1024];
+        let obj = BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(&data)));
+        let mut v = Vec::new();
+        let n = obj.write_der(&mut v).expect("could not write");
+        assert_eq!(n, v.len());
+        assert_eq!(&v[..6], &hex!("04 83 10 00 00 5a"));
+        assert_eq!(v, obj.to_vec().expect("could not encode"));
+    }
+
+    #[test]
+    fn test_write_der_nested() {
+        let data = vec![0x41; 300];
+        let obj = BerObject::from_seq(vec![
+            BerObject::from_int_slice(b"\x01\x00\x01"),
+            BerObject::from_obj(BerObjectContent::OctetStringConstructed(data.clone())),
+            BerObject::from_obj(BerObjectContent::BitString(
+                BitStringObject::new(6, &data[..2]).unwrap(),
+            )),
+            BerObject::from_set(vec![
+                BerObject::from_obj(BerObjectContent::Real(0.15625)),
+                BerObject::from_obj(BerObjectContent::Enum(2)),
+                BerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed("abc"))),
+                BerObject::from_obj(BerObjectContent::Null),
+            ]),
+            BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(&data[..128]))),
+        ]);
+        let mut v = Vec::new();
+        let n = obj.write_der(&mut v).expect("could not write");
+        assert_eq!(n, v.len());
+        assert_eq!(v, obj.to_der_vec().expect("could not encode"));
+        let (rem, obj2) = parse_ber(&v).expect("could not re-parse");
+        assert!(rem.is_empty());
+        assert_eq!(obj2.as_sequence().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_write_der_length_table() {
+        // deeply nested objects, using all the kinds of children
+        let data = vec![0x41; 200];
+        let mut obj = BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(&data)));
+        for depth in 0..40 {
+            obj = match depth % 4 {
+                0 => BerObject::from_seq(vec![BerObject::from_int_slice(b"\x00\x01"), obj]),
+                1 => BerObject::from_obj(BerObjectContent::Tagged(
+                    BerClass::ContextSpecific,
+                    BerTag(depth),
+                    Box::new(obj),
+                )),
+                2 => BerObject::from_obj(BerObjectContent::Optional(Some(Box::new(obj)))),
+                _ => BerObject::from_seq(vec![
+                    BerObject::from_set(vec![
+                        BerObject::from_int_slice(b"\x02"),
+                        BerObject::from_int_slice(b"\x01"),
+                    ]),
+                    obj,
+                ]),
+            };
+        }
+        let lens = der_length_table(&obj);
+        // root, and the children of sequences and tagged objects (not the elements of sets)
+        assert_eq!(lens.len(), 1 + 20 + 10 + 20);
+        assert_eq!(lens[0], obj.der_content_len());
+        let mut v = Vec::new();
+        let n = obj.write_der(&mut v).expect("could not write");
+        assert_eq!(n, v.len());
+        assert_eq!(n, obj.der_total_len());
+        assert_eq!(v, obj.to_der_vec().expect("could not encode"));
+    }
+
+    #[test]
+    fn test_encode_header_high_tag() {
+        // tag 30 still fits in the low tag number form
+        let hdr = BerObjectHeader::new(BerClass::Universal, 0, BerTag::BmpString, 2);
+        let v = encode_and_parse!(hdr, ber_encode_header, ber_read_element_header);
+        assert_eq!(&v[..], &hex!("1e 02"));
+        let hdr = BerObjectHeader::new(BerClass::ContextSpecific, 1, BerTag(0x1234), 2);
+        let v = gen_simple(ber_encode_header(&hdr), Vec::new()).expect("could not encode");
+        assert_eq!(&v[..], &hex!("bf a4 34 02"));
+        let (_, hdr2) = ber_read_element_header(&v).expect("could not re-parse");
+        assert_eq!(hdr2.tag, BerTag(0x1234));
+        assert_eq!(hdr2.class, BerClass::ContextSpecific);
+    }
+
+    #[test]
+    fn test_der_total_len() {
+        let data = vec![0x41; 70_000];
+        let mut objects = vec![
+            BerObject::from_obj(BerObjectContent::Null),
+            BerObject::from_obj(BerObjectContent::Boolean(true)),
+            BerObject::from_obj(BerObjectContent::Enum(0x1_0000)),
+            BerObject::from_obj(BerObjectContent::Real(0.15625)),
+            BerObject::from_obj(BerObjectContent::Real(-1.0e300)),
+            BerObject::from_obj(BerObjectContent::Real(-0.0)),
+            BerObject::from_obj(BerObjectContent::OctetStringConstructed(
+                data[..200].to_vec(),
+            )),
+            BerObject::from_obj(BerObjectContent::BitStringConstructed(
+                0,
+                data[..127].to_vec(),
+            )),
+            BerObject::from_header_and_content(
+                BerObjectHeader::new(BerClass::Private, 0, BerTag(0x1234), 0),
+                BerObjectContent::Unknown(BerTag(0x1234), Cow::Borrowed(&data[..3])),
+            ),
+        ];
+        for &l in &[0, 127, 128, 255, 256, 65_535, 65_536] {
+            objects.push(BerObject::from_obj(BerObjectContent::OctetString(
+                Cow::Borrowed(&data[..l]),
+            )));
+        }
+        objects.push(BerObject::from_seq(objects.clone()));
+        for obj in &objects {
+            let v = obj.to_der_vec().expect("could not encode");
+            assert_eq!(obj.der_total_len(), v.len());
+            let (_, hdr) = ber_read_element_header(&v).expect("could not parse header");
+            assert_eq!(hdr.len, BerSize::Definite(obj.der_content_len()));
+        }
+    }
+
+    #[test]
+    fn test_encode_set_of_der_order() {
+        let obj = BerObject::from_set(vec![
+            BerObject::from_int_slice(b"\x03"),
+            BerObject::from_int_slice(b"\x01\x00"),
+            BerObject::from_int_slice(b"\x01"),
+            BerObject::from_int_slice(b"\x02"),
+        ]);
+        // BER keeps input order
+        let v = obj.to_vec().expect("could not encode");
+        assert_eq!(
+            &v[..],
+            &hex!("31 0d 02 01 03 02 02 01 00 02 01 01 02 01 02")
+        );
+        // DER sorts encodings
+        let v = obj.to_der_vec().expect("could not encode");
+        assert_eq!(
+            &v[..],
+            &hex!("31 0d 02 01 01 02 01 02 02 01 03 02 02 01 00")
+        );
+        let mut v2 = Vec::new();
+        obj.write_der(&mut v2).expect("could not write");
+        assert_eq!(v, v2);
+        // nested sets are also sorted
+        let seq = BerObject::from_seq(vec![obj]);
+        let v = seq.to_der_vec().expect("could not encode");
+        assert_eq!(
+            &v[2..],
+            &hex!("31 0d 02 01 01 02 01 02 02 01 03 02 02 01 00")
+        );
+    }
+
+    #[test]
+    fn test_encode_cer_octetstring() {
+        let data = vec![0x5a; 2500];
+        let obj = BerObject::from_octetstring(&data);
+        let v = obj.to_cer_vec().expect("could not encode");
+        assert_eq!(v.len(), 2 + 2 * (4 + 1000) + (4 + 500) + 2);
+        assert_eq!(&v[..6], &hex!("24 80 04 82 03 e8"));
+        assert_eq!(&v[1006..1010], &hex!("04 82 03 e8"));
+        assert_eq!(&v[2010..2014], &hex!("04 82 01 f4"));
+        assert_eq!(&v[v.len() - 2..], &hex!("00 00"));
+        let (rem, obj2) = parse_ber(&v).expect("could not parse");
+        assert!(rem.is_empty());
+        assert_eq!(obj2.as_cow_slice(), Ok(Cow::Borrowed(&data[..])));
+        // short strings are primitive
+        let obj = BerObject::from_octetstring(&data[..1000]);
+        let v = obj.to_cer_vec().expect("could not encode");
+        assert_eq!(&v[..4], &hex!("04 82 03 e8"));
+        assert_eq!(v.len(), 1004);
+    }
+
+    #[test]
+    fn test_encode_cer_constructed() {
+        let obj = BerObject::from_seq(vec![
+            BerObject::from_int_slice(b"\x01"),
+            BerObject::from_set(vec![
+                BerObject::from_bool(true),
+                BerObject::from_obj(BerObjectContent::Null),
+            ]),
+        ]);
+        let v = obj.to_cer_vec().expect("could not encode");
+        assert_eq!(
+            &v[..],
+            &hex!("30 80 02 01 01 31 80 01 01 ff 05 00 00 00 00 00")[..]
+        );
+        let (rem, obj2) = parse_ber(&v).expect("could not parse");
+        assert!(rem.is_empty());
+        assert_eq!(obj2[0].as_u32(), Ok(1));
+        assert_eq!(obj2[1][0].as_bool(), Ok(true));
+    }
+
+    #[test]
+    fn test_roundtrip_der() {
+        let inputs: &[&[u8]] = &[
+            &hex!("01 01 ff"),
+            &hex!("01 01 00"),
+            &hex!("02 01 00"),
+            &hex!("02 02 00 80"),
+            &hex!("02 02 ff 7f"),
+            &hex!("02 09 00 ff ff ff ff ff ff ff ff"),
+            &hex!("03 01 00"),
+            &hex!("03 02 07 80"),
+            &hex!("04 00"),
+            &hex!("05 00"),
+            &hex!("06 03 2b 65 70"),
+            &hex!("06 06 2a 86 48 86 f7 0d"),
+            &hex!("09 00"),
+            &hex!("09 01 40"),
+            &hex!("09 03 80 fb 05"),
+            &hex!("09 03 c0 02 05"),
+            &hex!("0a 01 05"),
+            &hex!("0a 02 00 80"),
+            &hex!("0c 03 61 62 63"),
+            &hex!("0d 02 01 02"),
+            &hex!("13 02 61 62"),
+            &hex!("17 0d 31 39 30 31 30 31 30 30 30 30 30 30 5a"),
+            &hex!("18 0f 32 30 31 39 30 31 30 31 30 30 30 30 30 30 5a"),
+            &hex!("1e 02 00 61"),
+            &hex!("30 00"),
+            &hex!("30 06 02 01 01 02 01 02"),
+            &hex!("31 07 02 01 05 02 02 00 80"),
+            &hex!("30 09 31 07 02 01 05 02 02 00 80"),
+            &hex!("a0 03 02 01 01"),
+            &hex!("80 01 01"),
+            &hex!("62 03 02 01 01"),
+            &hex!("9f 1f 01 00"),
+        ];
+        for input in inputs {
+            assert!(roundtrip_der(input), "round-trip failed for {:02x?}", input);
+        }
+        // long form lengths
+        let mut input = vec![0x04, 0x82, 0x01, 0x2c];
+        input.extend_from_slice(&[0x61; 300]);
+        assert!(roundtrip_der(&input));
+        // not DER, or trailing data
+        assert!(!roundtrip_der(&hex!("01 01 01")));
+        assert!(!roundtrip_der(&hex!("02 02 00 01")));
+        assert!(!roundtrip_der(&hex!("04 81 01 00")));
+        assert!(!roundtrip_der(&hex!("30 80 02 01 01 00 00")));
+        assert!(!roundtrip_der(&hex!("05 00 05 00")));
+    }
+
+    #[test]
+    fn test_encode_end_of_content() {
+        let obj = BerObject::from_obj(BerObjectContent::EndOfContent);
+        let v = obj.to_vec().expect("could not encode");
+        assert_eq!(&v[..], &hex!("00 00"));
+        assert_eq!(obj.der_total_len(), 2);
+    }
+
+    #[test]
+    fn test_to_der_vec_canonical() {
+        // BER: indefinite lengths, padded integer, unsorted set, constructed octet string, and
+        // non-zero unused bits
+        let bytes = hex!(
+            "30 80 02 02 00 01 31 80 02 01 02 02 01 01 00 00
+             24 80 04 01 61 04 01 62 00 00 03 02 04 ff 00 00"
+        );
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        let expected = hex!("30 13 02 01 01 31 06 02 01 01 02 01 02 04 02 61 62 03 02 04 f0");
+        let v = obj.to_der_vec().expect("could not encode");
+        assert_eq!(&v[..], &expected[..]);
+        assert!(roundtrip_der(&v));
+        assert_eq!(obj.der_total_len(), v.len());
+        let mut w = Vec::new();
+        obj.write_der(&mut w).expect("could not write");
+        assert_eq!(w, v);
+        // BER keeps the current form
+        let v = obj.to_vec().expect("could not encode");
+        assert_eq!(
+            &v[..],
+            &hex!("30 16 02 02 00 01 31 06 02 01 02 02 01 01 24 04 04 02 61 62 03 02 04 ff")[..]
+        );
+    }
+
+    #[test]
+    fn test_encode_external() {
+        let inputs: &[&[u8]] = &[
+            &hex!("28 09 06 02 51 01 81 03 61 62 63"),
+            &hex!("28 0b 02 01 01 07 01 64 a0 03 02 01 05"),
+            &hex!("28 05 82 03 04 a0 b0"),
+            &hex!("2b 0e a0 0a a0 08 80 02 51 01 81 02 51 01 82 00"),
+            &hex!("2b 0e a0 09 a3 07 80 01 01 81 02 51 01 82 01 ff"),
+            &hex!("2b 06 a0 02 85 00 82 00"),
+        ];
+        for input in inputs {
+            assert!(roundtrip_der(input), "round-trip failed for {:02x?}", input);
+            let (_, obj) = parse_ber(input).expect("parsing failed");
+            assert_eq!(&obj.to_vec().expect("could not encode")[..], *input);
+            assert_eq!(obj.der_total_len(), input.len());
+            let mut w = Vec::new();
+            obj.write_der(&mut w).expect("could not write");
+            assert_eq!(&w[..], *input);
+        }
+        // a padded INTEGER in the single-ASN1-type value is encoded as DER
+        let bytes = hex!("28 06 a0 04 02 02 00 05");
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        let v = obj.to_der_vec().expect("could not encode");
+        assert_eq!(&v[..], &hex!("28 05 a0 03 02 01 05")[..]);
+        // constructed octet-aligned and arbitrary encodings are primitive in DER
+        let bytes = hex!("28 0b 06 02 51 01 a1 05 04 03 61 62 63");
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        assert_eq!(&obj.to_vec().expect("could not encode")[..], &bytes[..]);
+        let v = obj.to_der_vec().expect("could not encode");
+        assert_eq!(&v[..], &hex!("28 09 06 02 51 01 81 03 61 62 63")[..]);
+        let bytes = hex!("28 0b 06 02 51 01 a2 05 03 03 04 a0 b0");
+        let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+        assert_eq!(&obj.to_vec().expect("could not encode")[..], &bytes[..]);
+        let v = obj.to_der_vec().expect("could not encode");
+        assert_eq!(&v[..], &hex!("28 09 06 02 51 01 82 03 04 a0 b0")[..]);
+        let mut w = Vec::new();
+        obj.write_der(&mut w).expect("could not write");
+        assert_eq!(w, v);
+    }
 }
@@ -0,0 +1,236 @@
+use crate::ber::*;
+use crate::error::*;
+use alloc::vec::Vec;
+use nom::{Err, Needed};
+
+/// Incremental parser, for objects received in chunks
+///
+/// Data is added using [`feed`](#method.feed), and complete objects are read using
+/// [`next_object`](#method.next_object). When the buffered data does not contain a complete
+/// object, `next_object` returns `Incomplete`, with the exact number of additional bytes needed
+/// (computed from the length field of the header).
+///
+/// The number of bytes needed is not known in advance for objects using the indefinite length
+/// form: in that case, `Needed::Unknown` is returned.
+///
+/// ```rust
+/// use der_parser::ber::BerStreamParser;
+/// use nom::{Err, Needed};
+///
+/// let mut parser = BerStreamParser::new();
+/// parser.feed(&[0x02, 0x03, 0x01]);
+/// assert_eq!(
+///     parser.next_object().map(|_| ()),
+///     Err(Err::Incomplete(Needed::Size(2)))
+/// );
+/// parser.feed(&[0x00, 0x01]);
+/// let obj = parser.next_object().expect("parsing failed");
+/// assert_eq!(obj.as_u32(), Ok(0x10001));
+/// ```
+#[derive(Debug, Default)]
+pub struct BerStreamParser {
+    buffer: Vec<u8>,
+    // number of bytes of the last object returned, to be removed from the buffer
+    consumed: usize,
+}
+
+impl BerStreamParser {
+    /// Build a new parser, with an empty buffer
+    pub fn new() -> Self {
+        BerStreamParser::default()
+    }
+
+    /// Append data to the internal buffer
+    pub fn feed(&mut self, data: &[u8]) {
+        self.discard_consumed();
+        self.buffer.extend_from_slice(data);
+    }
+
+    /// Return the number of buffered bytes, not yet returned as objects
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() - self.consumed
+    }
+
+    /// Return the number of additional bytes needed to read the next object
+    ///
+    /// Returns `Some(0)` if a complete object is buffered, and `None` if the number of bytes is
+    /// unknown (the object uses the indefinite length form) or if the data is invalid.
+    pub fn needed(&self) -> Option<usize> {
+        match stream_object_size(&self.buffer[self.consumed..]) {
+            Ok(_) => Some(0),
+            Err(Err::Incomplete(Needed::Size(n))) => Some(n),
+            Err(_) => None,
+        }
+    }
+
+    /// Read the next object from the buffered data
+    ///
+    /// The object borrows the internal buffer: its bytes are removed from the buffer when
+    /// more data is fed, or when the next object is read.
+    ///
+    /// If the buffer does not contain a complete object, `Incomplete` is returned, and no data is
+    /// consumed. Objects with a declared length larger than `MAX_OBJECT_SIZE` are rejected with
+    /// `BerError::InvalidLength`, so that the caller does not keep buffering data.
+    ///
+    /// If a complete object is buffered but cannot be parsed, the error is returned and the bytes
+    /// of the object are dropped, so that the next call reads the following object.
+    ///
+    /// If the end of the object cannot be found (for ex. the header, or the header of a child of an
+    /// indefinite length object, is invalid), the error is returned and all the buffered data is
+    /// dropped: the parser can then be fed with new objects.
+    pub fn next_object(&mut self) -> Result<BerObject, Err<BerError>> {
+        self.discard_consumed();
+        let size = match stream_object_size(&self.buffer) {
+            Ok(size) => size,
+            Err(Err::Incomplete(n)) => return Err(Err::Incomplete(n)),
+            Err(e) => {
+                self.buffer.clear();
+                return Err(e);
+            }
+        };
+        self.consumed = size;
+        let (_, obj) = parse_ber(&self.buffer[..size])?;
+        Ok(obj)
+    }
+
+    fn discard_consumed(&mut self) {
+        if self.consumed > 0 {
+            self.buffer.drain(..self.consumed);
+            self.consumed = 0;
+        }
+    }
+}
+
+/// Return the size (header and content) of the first object of `i`, or the exact number of
+/// missing bytes
+fn stream_object_size(i: &[u8]) -> Result<usize, Err<BerError>> {
+    let (content, hdr) = match ber_read_element_header(i) {
+        Ok(res) => res,
+        Err(Err::Incomplete(_)) => return Err(Err::Incomplete(header_needed(i))),
+        Err(e) => return Err(e),
+    };
+    let header_len = i.len() - content.len();
+    match hdr.len {
+        BerSize::Definite(len) => {
+            if len > MAX_OBJECT_SIZE {
+                return Err(Err::Error(BerError::InvalidLength));
+            }
+            if content.len() < len {
+                return Err(Err::Incomplete(Needed::Size(len - content.len())));
+            }
+            Ok(header_len + len)
+        }
+        BerSize::Indefinite => match ber_skip_object_content_get_size(content, &hdr, MAX_RECURSION)
+        {
+            Ok((_, len)) => Ok(header_len + len),
+            Err(Err::Incomplete(_)) => Err(Err::Incomplete(Needed::Unknown)),
+            Err(e) => Err(e),
+        },
+    }
+}
+
+/// Return the number of bytes missing to read an incomplete header
+fn header_needed(i: &[u8]) -> Needed {
+    if i.is_empty() {
+        return Needed::Size(1);
+    }
+    // identifier octets: high tag numbers use more bytes, the last one with bit 8 cleared
+    let mut pos = 1;
+    if i[0] & 0x1f == 0x1f {
+        while pos < i.len() && i[pos] & 0x80 != 0 {
+            pos += 1;
+        }
+        if pos == i.len() {
+            return Needed::Size(1);
+        }
+        pos += 1;
+    }
+    // length octets
+    match i.get(pos) {
+        Some(&b) if b & 0x80 != 0 => {
+            let total = pos + 1 + usize::from(b & 0x7f);
+            Needed::Size(total.saturating_sub(i.len()).max(1))
+        }
+        _ => Needed::Size(1),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use hex_literal::hex;
+
+    #[test]
+    fn test_stream_parser() {
+        let bytes = hex!("30 84 00 00 00 06 02 01 01 02 01 02 05 00");
+        let mut parser = BerStreamParser::new();
+        assert_eq!(parser.needed(), Some(1));
+        // header, with long form length
+        parser.feed(&bytes[..2]);
+        assert_eq!(parser.needed(), Some(4));
+        parser.feed(&bytes[2..6]);
+        assert_eq!(parser.needed(), Some(6));
+        assert_eq!(
+            parser.next_object().map(|_| ()),
+            Err(Err::Incomplete(Needed::Size(6)))
+        );
+        parser.feed(&bytes[6..9]);
+        assert_eq!(parser.needed(), Some(3));
+        // end of first object, and beginning of the next one
+        parser.feed(&bytes[9..13]);
+        assert_eq!(parser.needed(), Some(0));
+        {
+            let obj = parser.next_object().expect("parsing failed");
+            assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(2));
+        }
+        assert_eq!(parser.buffered(), 1);
+        assert_eq!(parser.needed(), Some(1));
+        parser.feed(&bytes[13..]);
+        assert!(parser.next_object().expect("parsing failed").is_null());
+        assert_eq!(parser.buffered(), 0);
+        // indefinite length
+        parser.feed(&hex!("30 80 02 01 01"));
+        assert_eq!(parser.needed(), None);
+        assert_eq!(
+            parser.next_object().map(|_| ()),
+            Err(Err::Incomplete(Needed::Unknown))
+        );
+        parser.feed(&hex!("00 00"));
+        assert!(parser.next_object().is_ok());
+        assert_eq!(parser.buffered(), 0);
+        // invalid object: the error is returned, and the object is dropped
+        parser.feed(&hex!("01 02 ff ff 02 01 05"));
+        assert_eq!(
+            parser.next_object().map(|_| ()),
+            Err(Err::Error(BerError::InvalidLength))
+        );
+        assert_eq!(parser.buffered(), 3);
+        let obj = parser.next_object().expect("parsing failed");
+        assert_eq!(obj.as_u32(), Ok(5));
+        // invalid header: the end of the object is unknown, all data is dropped
+        parser.feed(&hex!("02 ff 02 01 05"));
+        assert_eq!(
+            parser.next_object().map(|_| ()),
+            Err(Err::Error(BerError::InvalidTag))
+        );
+        assert_eq!(parser.buffered(), 0);
+        parser.feed(&hex!("02 01 06"));
+        let obj = parser.next_object().expect("parsing failed");
+        assert_eq!(obj.as_u32(), Ok(6));
+        // indefinite length, with an empty context-specific [0] child
+        parser.feed(&hex!("30 80 80 00 02 01 05"));
+        assert_eq!(
+            parser.next_object().map(|_| ()),
+            Err(Err::Incomplete(Needed::Unknown))
+        );
+        parser.feed(&hex!("00 00"));
+        {
+            let obj = parser.next_object().expect("parsing failed");
+            assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(2));
+        }
+        assert_eq!(parser.buffered(), 0);
+        // high tag number
+        assert_eq!(header_needed(&hex!("1f 81")), Needed::Size(1));
+        assert_eq!(header_needed(&hex!("1f 81 01 82")), Needed::Size(2));
+    }
+}
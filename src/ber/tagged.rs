@@ -1,5 +1,6 @@
 use crate::ber::*;
 use crate::error::*;
+use alloc::boxed::Box;
 use nom::{Err, IResult};
 
 /// Read a TAGGED EXPLICIT value (combinator)
@@ -51,8 +52,57 @@ where
     })
 }
 
+/// Read a TAGGED EXPLICIT value, checking the class of the tag (combinator)
+///
+/// This is the same as [parse_ber_tagged_explicit](fn.parse_ber_tagged_explicit.html), except
+/// that the class of the tag must be `class` (otherwise, `BerError::InvalidClass` is returned).
+/// The other tagged parsers accept any class except `Universal`.
+///
+/// The following parses `[APPLICATION 5] EXPLICIT INTEGER`:
+///
+/// ```rust
+/// # use der_parser::ber::*;
+/// # use der_parser::error::BerError;
+/// # use nom::Err;
+/// #
+/// let bytes = &[0x65, 0x03, 0x02, 0x01, 0x05];
+/// let parser = parse_ber_tagged_explicit_with_class(TagClass::Application, 5, parse_ber_integer);
+/// let (_, obj) = parser(bytes).expect("parsing failed");
+/// assert_eq!(obj.as_tagged().map(|t| t.2.as_u32()), Ok(Ok(5)));
+///
+/// // [5] (context-specific) is rejected
+/// let bytes = &[0xa5, 0x03, 0x02, 0x01, 0x05];
+/// assert_eq!(parser(bytes), Err(Err::Error(BerError::InvalidClass)));
+/// ```
+pub fn parse_ber_tagged_explicit_with_class<'a, Tag, F>(
+    class: TagClass,
+    tag: Tag,
+    f: F,
+) -> impl Fn(&'a [u8]) -> BerResult
+where
+    F: Fn(&'a [u8]) -> BerResult<BerObject>,
+    Tag: Into<BerTag>,
+{
+    let tag = tag.into();
+    parse_ber_tagged_explicit_g(tag, move |hdr, content| {
+        if hdr.class != class {
+            return Err(Err::Error(BerError::InvalidClass));
+        }
+        let (rem, obj) = f(content)?;
+        let obj2 = BerObject::from_header_and_content(
+            hdr,
+            BerObjectContent::Tagged(class, tag, Box::new(obj)),
+        );
+        Ok((rem, obj2))
+    })
+}
+
 /// Read a TAGGED EXPLICIT value (generic version)
 ///
+/// The closure receives the header of the tagged object and its content (the encoded inner
+/// object), and can return any type, similarly to
+/// [parse_ber_container](fn.parse_ber_container.html).
+///
 /// The following parses `[2] EXPLICIT INTEGER`:
 ///
 /// ```rust
@@ -181,10 +231,82 @@ where
     })
 }
 
+/// Read a TAGGED IMPLICIT value, checking the class of the tag (combinator)
+///
+/// This is the same as [parse_ber_tagged_implicit](fn.parse_ber_tagged_implicit.html), except
+/// that the class of the tag must be `class` (otherwise, `BerError::InvalidClass` is returned).
+///
+/// The following parses `[PRIVATE 2] IMPLICIT INTEGER`:
+///
+/// ```rust
+/// # use der_parser::ber::*;
+/// #
+/// let bytes = &[0xc2, 0x03, 0x01, 0x00, 0x01];
+/// let (_, obj) = parse_ber_tagged_implicit_with_class(
+///     TagClass::Private,
+///     2,
+///     parse_ber_content(BerTag::Integer),
+/// )(bytes)
+/// .expect("parsing failed");
+/// assert_eq!(obj.as_u32(), Ok(0x10001));
+/// ```
+pub fn parse_ber_tagged_implicit_with_class<'a, Tag, F>(
+    class: TagClass,
+    tag: Tag,
+    f: F,
+) -> impl Fn(&'a [u8]) -> BerResult
+where
+    F: Fn(&'a [u8], &'_ BerObjectHeader, usize) -> BerResult<'a, BerObjectContent<'a>>,
+    Tag: Into<BerTag>,
+{
+    let tag = tag.into();
+    parse_ber_tagged_implicit_g(tag, move |i, hdr, depth| {
+        if hdr.class != class {
+            return Err(Err::Error(BerError::InvalidClass));
+        }
+        let (rem, content) = f(i, &hdr, depth)?;
+        // trailing bytes are ignored
+        let obj = BerObject::from_header_and_content(hdr, content);
+        Ok((rem, obj))
+    })
+}
+
+/// Read a TAGGED IMPLICIT value, reinterpreting the content as the underlying type (combinator)
+///
+/// This is a shortcut for `parse_ber_tagged_implicit(tag, parse_ber_content(underlying_tag))`.
+/// The content is parsed as an object of type `underlying_tag` (for ex. `Integer`), instead of
+/// `Unknown`, and the built object uses the original header (and tag).
+///
+/// The following parses `[0] IMPLICIT INTEGER`:
+///
+/// ```rust
+/// # use der_parser::ber::*;
+/// #
+/// let bytes = &[0x80, 0x03, 0x01, 0x00, 0x01];
+/// let (rem, obj) = parse_ber_tagged_implicit_as(0, BerTag::Integer)(bytes)
+///     .expect("parsing failed");
+/// assert!(rem.is_empty());
+/// assert_eq!(obj.header.tag, BerTag(0));
+/// assert_eq!(obj.as_slice(), Ok(&[0x01, 0x00, 0x01][..]));
+/// ```
+pub fn parse_ber_tagged_implicit_as<'a, Tag, T>(
+    tag: Tag,
+    underlying_tag: T,
+) -> impl Fn(&'a [u8]) -> BerResult
+where
+    Tag: Into<BerTag>,
+    T: Into<BerTag>,
+{
+    parse_ber_tagged_implicit(tag, parse_ber_content(underlying_tag.into()))
+}
+
 /// Read a TAGGED IMPLICIT value (generic version)
 ///
 /// Parse a TAGGED IMPLICIT value, given the expected tag, and the content parsing function.
 ///
+/// The closure receives the content, the header of the tagged object and the maximum depth, and
+/// can return any type, similarly to [parse_ber_container](fn.parse_ber_container.html).
+///
 /// # Examples
 ///
 /// The following parses `[2] IMPLICIT INTEGER` into an `u32`, raising an error if the integer is
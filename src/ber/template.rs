@@ -0,0 +1,260 @@
+use crate::ber::*;
+use crate::error::*;
+use alloc::string::String;
+use alloc::vec::Vec;
+use nom::Err;
+
+/// Type of a node of an [`Asn1Template`](struct.Asn1Template.html)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Asn1TemplateKind {
+    /// A single object, parsed using the usual BER rules (its content is not described by the
+    /// template)
+    Primitive,
+    /// A SEQUENCE (or tagged constructed object), with its elements in order
+    Sequence(Vec<Asn1Template>),
+    /// A SET, with its elements in any order
+    Set(Vec<Asn1Template>),
+}
+
+/// Runtime description (schema) of an expected structure
+///
+/// A template is a tree of nodes, each with a name, an expected class and tag, and a cardinality
+/// (required or `OPTIONAL`). This can be used when the structure is only known at runtime (for
+/// ex. loaded from an ASN.1 module): when it is known at compile-time, the `BerSequence` derive
+/// macro (see the `derive` feature) is usually more convenient.
+///
+/// ```rust
+/// use der_parser::ber::*;
+///
+/// // AlgorithmIdentifier ::= SEQUENCE {
+/// //     algorithm  OBJECT IDENTIFIER,
+/// //     parameters ANY OPTIONAL }
+/// let template = Asn1Template::sequence(
+///     "AlgorithmIdentifier",
+///     vec![
+///         Asn1Template::primitive("algorithm", BerTag::Oid),
+///         Asn1Template::primitive("parameters", BerTag::Null).optional(),
+///     ],
+/// );
+/// let bytes = &[0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70];
+/// let (_, value) = parse_with_template(&template, bytes).expect("parsing failed");
+/// assert!(value.get("algorithm").is_some());
+/// assert!(value.get("parameters").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Asn1Template {
+    /// Name of the node, used to label the parsed values
+    pub name: String,
+    /// Expected class
+    pub class: BerClass,
+    /// Expected tag
+    pub tag: BerTag,
+    /// If true, the node can be absent (`OPTIONAL`)
+    pub optional: bool,
+    /// Type of the node
+    pub kind: Asn1TemplateKind,
+}
+
+impl Asn1Template {
+    /// Build a template for a single object with the given universal tag
+    pub fn primitive<S: Into<String>>(name: S, tag: BerTag) -> Self {
+        Asn1Template {
+            name: name.into(),
+            class: BerClass::Universal,
+            tag,
+            optional: false,
+            kind: Asn1TemplateKind::Primitive,
+        }
+    }
+
+    /// Build a template for a SEQUENCE
+    pub fn sequence<S: Into<String>>(name: S, elements: Vec<Asn1Template>) -> Self {
+        Asn1Template {
+            name: name.into(),
+            class: BerClass::Universal,
+            tag: BerTag::Sequence,
+            optional: false,
+            kind: Asn1TemplateKind::Sequence(elements),
+        }
+    }
+
+    /// Build a template for a SET
+    pub fn set<S: Into<String>>(name: S, elements: Vec<Asn1Template>) -> Self {
+        Asn1Template {
+            name: name.into(),
+            class: BerClass::Universal,
+            tag: BerTag::Set,
+            optional: false,
+            kind: Asn1TemplateKind::Set(elements),
+        }
+    }
+
+    /// Mark the node as `OPTIONAL`
+    pub fn optional(self) -> Self {
+        Asn1Template {
+            optional: true,
+            ..self
+        }
+    }
+
+    /// Replace the expected class and tag (IMPLICIT tagging)
+    pub fn with_tag(self, class: BerClass, tag: BerTag) -> Self {
+        Asn1Template { class, tag, ..self }
+    }
+
+    fn matches(&self, hdr: &BerObjectHeader) -> bool {
+        hdr.class == self.class && hdr.tag == self.tag
+    }
+}
+
+/// Value of a node, returned by [`parse_with_template`](fn.parse_with_template.html)
+#[derive(Debug, PartialEq)]
+pub enum Asn1TemplateValue<'t, 'a> {
+    /// The object matching a `Primitive` node
+    Primitive(BerObject<'a>),
+    /// The values of the elements of a `Sequence` or `Set` node which are present, in the order
+    /// of the input
+    Constructed(Vec<Asn1TemplateMatch<'t, 'a>>),
+}
+
+/// Labeled value of a node, returned by [`parse_with_template`](fn.parse_with_template.html)
+#[derive(Debug, PartialEq)]
+pub struct Asn1TemplateMatch<'t, 'a> {
+    /// Name of the template node
+    pub name: &'t str,
+    /// Header of the parsed object
+    pub header: BerObjectHeader<'a>,
+    /// Parsed value
+    pub value: Asn1TemplateValue<'t, 'a>,
+}
+
+impl<'t, 'a> Asn1TemplateMatch<'t, 'a> {
+    /// Get the value of the element named `name`, if present
+    ///
+    /// This function only looks at the direct children of a constructed value. It returns `None`
+    /// for primitive values.
+    pub fn get(&self, name: &str) -> Option<&Asn1TemplateMatch<'t, 'a>> {
+        match self.value {
+            Asn1TemplateValue::Constructed(ref v) => v.iter().find(|m| m.name == name),
+            Asn1TemplateValue::Primitive(_) => None,
+        }
+    }
+
+    /// Get the object, if the value is primitive
+    pub fn as_object(&self) -> Option<&BerObject<'a>> {
+        match self.value {
+            Asn1TemplateValue::Primitive(ref obj) => Some(obj),
+            Asn1TemplateValue::Constructed(_) => None,
+        }
+    }
+}
+
+/// Parse an object, and validate it against a template
+///
+/// The class and tag of each object must match the template. For `Sequence` nodes, the elements
+/// must appear in order (optional elements can be absent). For `Set` nodes, they can appear in
+/// any order. The content of constructed objects must be entirely described by the template.
+///
+/// Errors:
+/// - `InvalidClass` or `UnexpectedTag` if an object does not match the template
+/// - `ConstructExpected` if a `Sequence` or `Set` node matches a primitive object
+/// - `ObjectTooShort` if a required element is missing
+/// - `UnexpectedData` if data remains after the elements of a constructed object
+///
+/// See [`Asn1Template`](struct.Asn1Template.html) for an example.
+pub fn parse_with_template<'t, 'a>(
+    template: &'t Asn1Template,
+    i: &'a [u8],
+) -> BerResult<'a, Asn1TemplateMatch<'t, 'a>> {
+    template_parse_recursive(template, i, MAX_RECURSION)
+}
+
+fn template_parse_recursive<'t, 'a>(
+    template: &'t Asn1Template,
+    i: &'a [u8],
+    max_depth: usize,
+) -> BerResult<'a, Asn1TemplateMatch<'t, 'a>> {
+    custom_check!(i, max_depth == 0, BerError::BerMaxDepth)?;
+    let (content, hdr) = ber_read_element_header(i)?;
+    custom_check!(i, hdr.class != template.class, BerError::InvalidClass)?;
+    custom_check!(i, hdr.tag != template.tag, BerError::UnexpectedTag(hdr.tag))?;
+    let elements = match template.kind {
+        Asn1TemplateKind::Primitive => {
+            let (rem, obj) = parse_ber_recursive(i, max_depth)?;
+            let header = obj.header.clone();
+            let value = Asn1TemplateValue::Primitive(obj);
+            let m = Asn1TemplateMatch {
+                name: &template.name,
+                header,
+                value,
+            };
+            return Ok((rem, m));
+        }
+        Asn1TemplateKind::Sequence(ref elements) | Asn1TemplateKind::Set(ref elements) => elements,
+    };
+    custom_check!(i, !hdr.is_constructed(), BerError::ConstructExpected)?;
+    // the end-of-content marker is not part of the content
+    let (rem, data) = ber_get_object_content(content, &hdr, max_depth)?;
+    let (data, values) = match template.kind {
+        Asn1TemplateKind::Set(_) => template_parse_set(elements, data, max_depth - 1)?,
+        _ => template_parse_sequence(elements, data, max_depth - 1)?,
+    };
+    custom_check!(data, !data.is_empty(), BerError::UnexpectedData(data.len()))?;
+    let m = Asn1TemplateMatch {
+        name: &template.name,
+        header: hdr,
+        value: Asn1TemplateValue::Constructed(values),
+    };
+    Ok((rem, m))
+}
+
+fn template_parse_sequence<'t, 'a>(
+    elements: &'t [Asn1Template],
+    i: &'a [u8],
+    max_depth: usize,
+) -> BerResult<'a, Vec<Asn1TemplateMatch<'t, 'a>>> {
+    let mut values = Vec::new();
+    let mut i = i;
+    for element in elements {
+        if element.optional {
+            let matches = !i.is_empty() && {
+                let (_, hdr) = ber_read_element_header(i)?;
+                element.matches(&hdr)
+            };
+            if !matches {
+                continue;
+            }
+        }
+        custom_check!(i, i.is_empty(), BerError::ObjectTooShort)?;
+        let (rem, value) = template_parse_recursive(element, i, max_depth)?;
+        values.push(value);
+        i = rem;
+    }
+    Ok((i, values))
+}
+
+fn template_parse_set<'t, 'a>(
+    elements: &'t [Asn1Template],
+    i: &'a [u8],
+    max_depth: usize,
+) -> BerResult<'a, Vec<Asn1TemplateMatch<'t, 'a>>> {
+    let mut values = Vec::new();
+    let mut found = vec![false; elements.len()];
+    let mut i = i;
+    while !i.is_empty() {
+        let (_, hdr) = ber_read_element_header(i)?;
+        let idx = (0..elements.len())
+            .find(|&idx| !found[idx] && elements[idx].matches(&hdr))
+            .ok_or(Err::Error(BerError::UnexpectedTag(hdr.tag)))?;
+        let (rem, value) = template_parse_recursive(&elements[idx], i, max_depth)?;
+        found[idx] = true;
+        values.push(value);
+        i = rem;
+    }
+    let missing = elements
+        .iter()
+        .zip(found.iter())
+        .any(|(element, &found)| !element.optional && !found);
+    custom_check!(i, missing, BerError::ObjectTooShort)?;
+    Ok((i, values))
+}
@@ -0,0 +1,323 @@
+use crate::error::BerError;
+
+/// Timezone of an ASN.1 time value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ASN1TimeZone {
+    /// Coordinated Universal Time (`Z` suffix)
+    Z,
+    /// Offset from UTC, as hours and minutes (both values have the same sign)
+    Offset(i8, i8),
+    /// No timezone (local time), only allowed in `GeneralizedTime`
+    Undefined,
+}
+
+/// Decoded value of a `UTCTime` object (X.680 section 47)
+///
+/// The year is normalized to 4 digits using the 1950-2049 window from RFC 5280 (4.1.2.5.1).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UtcTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: u8,
+    /// Seconds are optional in the UTCTime encoding
+    pub second: Option<u8>,
+    pub tz: ASN1TimeZone,
+}
+
+/// Decoded value of a `GeneralizedTime` object (X.680 section 46)
+///
+/// The fraction, if present, applies to the smallest unit present (seconds in most cases), and
+/// is stored as an integer numerator and a number of decimal digits, to avoid losing precision:
+/// the fractional part is `fraction / 10^fraction_digits`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GeneralizedTime {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+    pub hour: u8,
+    pub minute: Option<u8>,
+    pub second: Option<u8>,
+    /// Numerator of the fractional part (0 if absent)
+    pub fraction: u64,
+    /// Number of digits of the fractional part (0 if absent)
+    pub fraction_digits: u8,
+    pub tz: ASN1TimeZone,
+}
+
+/// Decoded value of a `Time` object, a CHOICE of `UTCTime` and `GeneralizedTime` (RFC 5280)
+///
+/// See [`parse_ber_time`](fn.parse_ber_time.html).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ASN1Time {
+    UtcTime(UtcTime),
+    GeneralizedTime(GeneralizedTime),
+}
+
+impl ASN1Time {
+    /// Return the year (4 digits)
+    pub fn year(&self) -> u16 {
+        match self {
+            ASN1Time::UtcTime(t) => t.year,
+            ASN1Time::GeneralizedTime(t) => t.year,
+        }
+    }
+
+    /// Return the timezone
+    pub fn tz(&self) -> ASN1TimeZone {
+        match self {
+            ASN1Time::UtcTime(t) => t.tz,
+            ASN1Time::GeneralizedTime(t) => t.tz,
+        }
+    }
+}
+
+impl From<UtcTime> for ASN1Time {
+    fn from(t: UtcTime) -> Self {
+        ASN1Time::UtcTime(t)
+    }
+}
+
+impl From<GeneralizedTime> for ASN1Time {
+    fn from(t: GeneralizedTime) -> Self {
+        ASN1Time::GeneralizedTime(t)
+    }
+}
+
+/// Decode 2 ASCII digits
+#[inline]
+fn decode_decimal(s: &[u8]) -> Result<u8, BerError> {
+    if s.len() != 2 || !s.iter().all(u8::is_ascii_digit) {
+        return Err(BerError::InvalidTime);
+    }
+    Ok((s[0] - b'0') * 10 + (s[1] - b'0'))
+}
+
+#[inline]
+fn is_leap_year(year: u16) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// Verify the ranges of the date and time fields
+pub(crate) fn check_date_time(
+    year: u16,
+    month: u8,
+    day: u8,
+    hour: u8,
+    minute: u8,
+    second: u8,
+) -> Result<(), BerError> {
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        _ => return Err(BerError::InvalidTime),
+    };
+    if day == 0 || day > days_in_month || hour > 23 || minute > 59 || second > 59 {
+        return Err(BerError::InvalidTime);
+    }
+    Ok(())
+}
+
+/// Decode the timezone suffix (`Z`, `+hhmm` or `-hhmm`)
+///
+/// The input must be exactly the suffix, trailing bytes are rejected.
+pub(crate) fn decode_timezone(s: &[u8]) -> Result<ASN1TimeZone, BerError> {
+    if s == b"Z" {
+        return Ok(ASN1TimeZone::Z);
+    }
+    if s.len() != 5 || (s[0] != b'+' && s[0] != b'-') {
+        return Err(BerError::InvalidTime);
+    }
+    let hh = decode_decimal(&s[1..3])?;
+    let mm = decode_decimal(&s[3..5])?;
+    if hh > 23 || mm > 59 {
+        return Err(BerError::InvalidTime);
+    }
+    let (hh, mm) = (hh as i8, mm as i8);
+    if s[0] == b'-' {
+        Ok(ASN1TimeZone::Offset(-hh, -mm))
+    } else {
+        Ok(ASN1TimeZone::Offset(hh, mm))
+    }
+}
+
+/// Decode `UTCTime` content: `YYMMDDhhmm[ss]` followed by `Z` or `(+|-)hhmm`
+pub(crate) fn decode_utctime(s: &[u8]) -> Result<UtcTime, BerError> {
+    if s.len() < 11 {
+        return Err(BerError::InvalidTime);
+    }
+    let yy = decode_decimal(&s[0..2])?;
+    let month = decode_decimal(&s[2..4])?;
+    let day = decode_decimal(&s[4..6])?;
+    let hour = decode_decimal(&s[6..8])?;
+    let minute = decode_decimal(&s[8..10])?;
+    let (second, rem) = match s[10] {
+        b'0'..=b'9' => {
+            let ss = s.get(10..12).ok_or(BerError::InvalidTime)?;
+            (Some(decode_decimal(ss)?), &s[12..])
+        }
+        _ => (None, &s[10..]),
+    };
+    let tz = decode_timezone(rem)?;
+    // RFC 5280 4.1.2.5.1
+    let year = if yy >= 50 {
+        1900 + u16::from(yy)
+    } else {
+        2000 + u16::from(yy)
+    };
+    check_date_time(year, month, day, hour, minute, second.unwrap_or(0))?;
+    Ok(UtcTime {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        tz,
+    })
+}
+
+/// Decode `GeneralizedTime` content: `YYYYMMDDhh[mm[ss]][(.|,)f+]`, followed by `Z`,
+/// `(+|-)hh[mm]` or nothing (local time)
+pub(crate) fn decode_generalizedtime(s: &[u8]) -> Result<GeneralizedTime, BerError> {
+    if s.len() < 10 {
+        return Err(BerError::InvalidTime);
+    }
+    let year = u16::from(decode_decimal(&s[0..2])?) * 100 + u16::from(decode_decimal(&s[2..4])?);
+    let month = decode_decimal(&s[4..6])?;
+    let day = decode_decimal(&s[6..8])?;
+    let hour = decode_decimal(&s[8..10])?;
+    let mut rem = &s[10..];
+    let mut minute = None;
+    let mut second = None;
+    if rem.first().map_or(false, u8::is_ascii_digit) {
+        minute = Some(decode_decimal(rem.get(0..2).ok_or(BerError::InvalidTime)?)?);
+        rem = &rem[2..];
+        if rem.first().map_or(false, u8::is_ascii_digit) {
+            second = Some(decode_decimal(rem.get(0..2).ok_or(BerError::InvalidTime)?)?);
+            rem = &rem[2..];
+        }
+    }
+    let mut fraction = 0u64;
+    let mut fraction_digits = 0u8;
+    if rem.first() == Some(&b'.') || rem.first() == Some(&b',') {
+        rem = &rem[1..];
+        let n = rem.iter().take_while(|c| c.is_ascii_digit()).count();
+        // 19 digits always fit in a u64
+        if n == 0 || n > 19 {
+            return Err(BerError::InvalidTime);
+        }
+        fraction = rem[..n]
+            .iter()
+            .fold(0, |acc, &c| acc * 10 + u64::from(c - b'0'));
+        fraction_digits = n as u8;
+        rem = &rem[n..];
+    }
+    let tz = match rem.len() {
+        0 => ASN1TimeZone::Undefined,
+        3 if rem[0] == b'+' || rem[0] == b'-' => {
+            let hh = decode_decimal(&rem[1..3])?;
+            if hh > 23 {
+                return Err(BerError::InvalidTime);
+            }
+            let hh = hh as i8;
+            ASN1TimeZone::Offset(if rem[0] == b'-' { -hh } else { hh }, 0)
+        }
+        _ => decode_timezone(rem)?,
+    };
+    check_date_time(
+        year,
+        month,
+        day,
+        hour,
+        minute.unwrap_or(0),
+        second.unwrap_or(0),
+    )?;
+    Ok(GeneralizedTime {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+        fraction,
+        fraction_digits,
+        tz,
+    })
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_conv {
+    use super::*;
+    use chrono::{DateTime, Duration, NaiveDate, TimeZone, Utc};
+
+    fn to_chrono_utc(
+        year: u16,
+        month: u8,
+        day: u8,
+        hms: (u8, u8, u8),
+        nanos: i64,
+        tz: ASN1TimeZone,
+    ) -> Result<DateTime<Utc>, BerError> {
+        let (offset_h, offset_m) = match tz {
+            ASN1TimeZone::Z => (0, 0),
+            ASN1TimeZone::Offset(h, m) => (h, m),
+            // local time cannot be converted to UTC
+            ASN1TimeZone::Undefined => return Err(BerError::InvalidTime),
+        };
+        let naive = NaiveDate::from_ymd_opt(i32::from(year), u32::from(month), u32::from(day))
+            .and_then(|d| d.and_hms_opt(u32::from(hms.0), u32::from(hms.1), u32::from(hms.2)))
+            .ok_or(BerError::InvalidTime)?;
+        // local time = UTC + offset
+        let offset = Duration::minutes(i64::from(offset_h) * 60 + i64::from(offset_m));
+        let naive = naive
+            .checked_add_signed(Duration::nanoseconds(nanos))
+            .and_then(|t| t.checked_sub_signed(offset))
+            .ok_or(BerError::InvalidTime)?;
+        Ok(Utc.from_utc_datetime(&naive))
+    }
+
+    impl UtcTime {
+        /// Convert to a `chrono::DateTime<Utc>`, applying the timezone offset
+        pub fn to_chrono_utc(&self) -> Result<DateTime<Utc>, BerError> {
+            let hms = (self.hour, self.minute, self.second.unwrap_or(0));
+            to_chrono_utc(self.year, self.month, self.day, hms, 0, self.tz)
+        }
+    }
+
+    impl GeneralizedTime {
+        /// Convert to a `chrono::DateTime<Utc>`, applying the timezone offset
+        ///
+        /// Precision of the fraction is truncated to nanoseconds. Local times (no timezone)
+        /// cannot be converted, and return an error.
+        pub fn to_chrono_utc(&self) -> Result<DateTime<Utc>, BerError> {
+            // the fraction applies to the smallest unit present
+            let unit_nanos: u128 = match (self.minute, self.second) {
+                (_, Some(_)) => 1_000_000_000,
+                (Some(_), None) => 60_000_000_000,
+                (None, None) => 3_600_000_000_000,
+            };
+            let nanos = u128::from(self.fraction) * unit_nanos
+                / 10u128.pow(u32::from(self.fraction_digits));
+            let hms = (
+                self.hour,
+                self.minute.unwrap_or(0),
+                self.second.unwrap_or(0),
+            );
+            to_chrono_utc(self.year, self.month, self.day, hms, nanos as i64, self.tz)
+        }
+    }
+
+    impl ASN1Time {
+        /// Convert to a `chrono::DateTime<Utc>`, applying the timezone offset
+        pub fn to_chrono_utc(&self) -> Result<DateTime<Utc>, BerError> {
+            match self {
+                ASN1Time::UtcTime(t) => t.to_chrono_utc(),
+                ASN1Time::GeneralizedTime(t) => t.to_chrono_utc(),
+            }
+        }
+    }
+}
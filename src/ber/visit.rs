@@ -0,0 +1,129 @@
+use crate::ber::*;
+
+/// Read-only visitor for `BerObject` trees
+///
+/// Objects are visited in encoding order, using `BerObject::accept`. The `depth` argument is 0
+/// for the root object, and is incremented for the children of constructed objects.
+///
+/// All methods have a default (empty) implementation, so visitors only have to implement the
+/// methods they need.
+///
+/// ```rust
+/// use der_parser::ber::{BerObject, BerVisitor};
+///
+/// struct Indent(String);
+///
+/// impl<'a> BerVisitor<'a> for Indent {
+///     fn visit_primitive(&mut self, obj: &BerObject<'a>, depth: usize) {
+///         self.0 += &format!("{:1$}{2:?}\n", "", depth * 2, obj.header.tag);
+///     }
+///     fn enter_constructed(&mut self, obj: &BerObject<'a>, depth: usize) {
+///         self.0 += &format!("{:1$}{2:?}\n", "", depth * 2, obj.header.tag);
+///     }
+/// }
+///
+/// let obj = BerObject::from_seq(vec![
+///     BerObject::from_int_slice(b"\x01"),
+///     BerObject::from_set(vec![BerObject::from_bool(true)]),
+/// ]);
+/// let mut visitor = Indent(String::new());
+/// obj.accept(&mut visitor);
+/// assert_eq!(visitor.0, "Sequence\n  Integer\n  Set\n    Boolean\n");
+/// ```
+pub trait BerVisitor<'a> {
+    /// Called for each primitive object (including flattened constructed strings)
+    fn visit_primitive(&mut self, _obj: &BerObject<'a>, _depth: usize) {}
+
+    /// Called for each constructed object (Sequence, Set or Tagged), before visiting the children
+    fn enter_constructed(&mut self, _obj: &BerObject<'a>, _depth: usize) {}
+
+    /// Called for each constructed object (Sequence, Set or Tagged), after visiting the children
+    fn leave_constructed(&mut self, _obj: &BerObject<'a>, _depth: usize) {}
+}
+
+fn ber_visit<'a, V: BerVisitor<'a>>(obj: &BerObject<'a>, visitor: &mut V, depth: usize) {
+    match obj.content {
+        BerObjectContent::Sequence(ref v) | BerObjectContent::Set(ref v) => {
+            visitor.enter_constructed(obj, depth);
+            for child in v {
+                ber_visit(child, visitor, depth + 1);
+            }
+            visitor.leave_constructed(obj, depth);
+        }
+        BerObjectContent::Tagged(_, _, ref inner) => {
+            visitor.enter_constructed(obj, depth);
+            ber_visit(inner, visitor, depth + 1);
+            visitor.leave_constructed(obj, depth);
+        }
+        // optional values are transparent
+        BerObjectContent::Optional(Some(ref inner)) => ber_visit(inner, visitor, depth),
+        BerObjectContent::Optional(None) => (),
+        _ => visitor.visit_primitive(obj, depth),
+    }
+}
+
+impl<'a> BerObject<'a> {
+    /// Walk the object tree (depth-first), calling the visitor methods for each object
+    ///
+    /// See `BerVisitor` for details.
+    pub fn accept<V: BerVisitor<'a>>(&self, visitor: &mut V) {
+        ber_visit(self, visitor, 0)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use alloc::borrow::Cow;
+
+    #[derive(Default)]
+    struct Collect<'a> {
+        events: Vec<(&'static str, BerTag, usize)>,
+        octetstrings: Vec<&'a [u8]>,
+    }
+
+    impl<'a> BerVisitor<'a> for Collect<'a> {
+        fn visit_primitive(&mut self, obj: &BerObject<'a>, depth: usize) {
+            if let BerObjectContent::OctetString(Cow::Borrowed(s)) = obj.content {
+                self.octetstrings.push(s);
+            }
+            self.events.push(("primitive", obj.header.tag, depth));
+        }
+        fn enter_constructed(&mut self, obj: &BerObject<'a>, depth: usize) {
+            self.events.push(("enter", obj.header.tag, depth));
+        }
+        fn leave_constructed(&mut self, obj: &BerObject<'a>, depth: usize) {
+            self.events.push(("leave", obj.header.tag, depth));
+        }
+    }
+
+    #[test]
+    fn test_visitor() {
+        let tagged = BerObject::from_obj(BerObjectContent::Tagged(
+            BerClass::ContextSpecific,
+            BerTag(0),
+            Box::new(BerObject::from_octetstring(b"abc")),
+        ));
+        let obj = BerObject::from_seq(vec![
+            BerObject::from_obj(BerObjectContent::Optional(None)),
+            BerObject::from_set(vec![BerObject::from_bool(true)]),
+            tagged,
+        ]);
+        let mut visitor = Collect::default();
+        obj.accept(&mut visitor);
+        assert_eq!(
+            visitor.events,
+            vec![
+                ("enter", BerTag::Sequence, 0),
+                ("enter", BerTag::Set, 1),
+                ("primitive", BerTag::Boolean, 2),
+                ("leave", BerTag::Set, 1),
+                ("enter", BerTag(0), 1),
+                ("primitive", BerTag::OctetString, 2),
+                ("leave", BerTag(0), 1),
+                ("leave", BerTag::Sequence, 0),
+            ]
+        );
+        assert_eq!(visitor.octetstrings, vec![&b"abc"[..]]);
+    }
+}
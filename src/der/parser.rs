@@ -1,10 +1,13 @@
 use crate::ber::*;
 use crate::der::DerObject;
 use crate::error::*;
+use crate::oid::Oid;
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use nom::bytes::streaming::take;
 use nom::number::streaming::be_u8;
 use nom::*;
-use rusticata_macros::custom_check;
-use std::convert::TryFrom;
 
 use crate::ber::MAX_RECURSION;
 
@@ -30,6 +33,57 @@ pub fn parse_der(i: &[u8]) -> DerResult {
     parse_der_recursive(i, MAX_RECURSION)
 }
 
+/// Parse DER object recursively, and check that input contains exactly one object
+///
+/// Return an `UnexpectedData` error, with the number of remaining bytes, if data remains after the
+/// object. On success, the remaining input is always empty.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::der::parse_der_complete;
+/// use der_parser::error::BerError;
+///
+/// let bytes = &[0x02, 0x03, 0x01, 0x00, 0x01, 0x00];
+/// let res = parse_der_complete(bytes);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::UnexpectedData(1))));
+/// ```
+pub fn parse_der_complete(i: &[u8]) -> DerResult {
+    let (rem, obj) = parse_der(i)?;
+    custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+    Ok((rem, obj))
+}
+
+/// Parse DER object recursively, using the provided parser options
+///
+/// Return a tuple containing the remaining (unparsed) bytes and the DER Object, or an error.
+///
+/// See `parse_ber_with_options` for the meaning of the options.
+///
+/// ### Example
+///
+/// ```
+/// use der_parser::ber::BerParserOptions;
+/// use der_parser::der::parse_der_with_options;
+///
+/// let bytes = &[0x30, 0x05, 0x30, 0x03, 0x02, 0x01, 0x01];
+/// let options = BerParserOptions::default().with_max_depth(2);
+/// assert!(parse_der_with_options(bytes, &options).is_err());
+/// ```
+#[inline]
+pub fn parse_der_with_options<'a>(i: &'a [u8], options: &BerParserOptions) -> DerResult<'a> {
+    parse_with_max_size(i, options.max_size, |i| {
+        parse_der_recursive(i, options.max_depth)
+    })
+}
+
+/// Parse DER object recursively, returning the position of the object where parsing failed
+///
+/// See `parse_ber_with_offset`.
+pub fn parse_der_with_offset(i: &[u8]) -> IResult<&[u8], DerObject, BerErrorWithOffset> {
+    with_error_offset(i, parse_der)
+}
+
 /// Parse DER object recursively, specifying the maximum recursion depth
 ///
 /// Return a tuple containing the remaining (unparsed) bytes and the DER Object, or an error.
@@ -46,12 +100,13 @@ pub fn parse_der(i: &[u8]) -> DerResult {
 /// assert_eq!(obj.header.tag, BerTag::Integer);
 /// ```
 pub fn parse_der_recursive(i: &[u8], max_depth: usize) -> DerResult {
-    let (i, hdr) = der_read_element_header(i)?;
+    let (data, hdr) = der_read_element_header(i)?;
     // safety check: length cannot be more than 2^32 bytes
     if let BerSize::Definite(l) = hdr.len {
-        custom_check!(i, l > MAX_OBJECT_SIZE, BerError::InvalidLength)?;
+        custom_check!(data, l > MAX_OBJECT_SIZE, BerError::InvalidLength)?;
     }
-    der_read_element_content_recursive(i, hdr, max_depth)
+    let (rem, obj) = der_read_element_content_recursive(data, hdr, max_depth)?;
+    Ok((rem, with_raw_encoding(i, data, rem, obj)))
 }
 
 #[doc(hidden)]
@@ -83,13 +138,14 @@ macro_rules! der_constraint_fail_if(
 /// ```
 pub fn parse_der_with_tag<Tag: Into<BerTag>>(i: &[u8], tag: Tag) -> DerResult {
     let tag = tag.into();
-    let (i, hdr) = der_read_element_header(i)?;
+    let (data, hdr) = der_read_element_header(i)?;
     if hdr.tag != tag {
         return Err(nom::Err::Error(BerError::InvalidTag));
     }
-    let (i, content) =
-        der_read_element_content_as(i, hdr.tag, hdr.len, hdr.is_constructed(), MAX_RECURSION)?;
-    Ok((i, BerObject::from_header_and_content(hdr, content)))
+    let (rem, content) =
+        der_read_element_content_as(data, hdr.tag, hdr.len, hdr.is_constructed(), MAX_RECURSION)?;
+    let obj = BerObject::from_header_and_content(hdr, content);
+    Ok((rem, with_raw_encoding(i, data, rem, obj)))
 }
 
 /// Read end of content marker
@@ -127,9 +183,10 @@ pub fn parse_der_bool(i: &[u8]) -> DerResult {
 /// ```rust
 /// # use der_parser::der::{parse_der_integer, DerObject};
 /// # use der_parser::ber::BerObjectContent;
+/// # use std::borrow::Cow;
 /// let empty = &b""[..];
 /// let bytes = [0x02, 0x03, 0x01, 0x00, 0x01];
-/// let expected  = DerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+/// let expected  = DerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
 /// assert_eq!(
 ///     parse_der_integer(&bytes),
 ///     Ok((empty, expected))
@@ -179,6 +236,16 @@ pub fn parse_der_oid(i: &[u8]) -> DerResult {
     parse_der_with_tag(i, BerTag::Oid)
 }
 
+/// Read a real value
+///
+/// In addition to the BER rules, the DER constraints of X.690 11.3 are verified: binary
+/// encodings must use base 2, no scaling factor, an odd mantissa, and a minimally encoded
+/// exponent, while decimal encodings must use the NR3 form.
+#[inline]
+pub fn parse_der_real(i: &[u8]) -> DerResult {
+    parse_der_with_tag(i, BerTag::RealType)
+}
+
 /// Read an enumerated value
 #[inline]
 pub fn parse_der_enum(i: &[u8]) -> DerResult {
@@ -274,6 +341,23 @@ pub fn parse_der_generalizedtime(i: &[u8]) -> DerResult {
     parse_der_with_tag(i, BerTag::GeneralizedTime)
 }
 
+/// Read a Time value: a CHOICE of UTCTime and GeneralizedTime, as used in X.509 certificates
+///
+/// See [`parse_ber_time`](../ber/fn.parse_ber_time.html).
+pub fn parse_der_time(i: &[u8]) -> BerResult<ASN1Time> {
+    let (_, hdr) = peek_ber_header(i)?;
+    let (rem, obj) = match hdr.tag {
+        BerTag::UtcTime => parse_der_utctime(i)?,
+        BerTag::GeneralizedTime => parse_der_generalizedtime(i)?,
+        tag => return Err(Err::Error(BerError::UnexpectedTag(tag))),
+    };
+    let t = match obj.content {
+        BerObjectContent::UTCTime(_) => obj.as_utctime()?.into(),
+        _ => obj.as_generalizedtime()?.into(),
+    };
+    Ok((rem, t))
+}
+
 /// Read a ObjectDescriptor value
 #[inline]
 pub fn parse_der_objectdescriptor(i: &[u8]) -> DerResult {
@@ -338,6 +422,193 @@ where
     parse_der_explicit_optional(i, tag, f)
 }
 
+/// Combinator for DEFAULT values, enforcing DER restrictions
+///
+/// This is the same as [`parse_ber_default`](../ber/fn.parse_ber_default.html), except that
+/// if the value is present and equal to `default`, `BerError::DerConstraintFailed` is returned:
+/// DER requires that a value equal to the default is not encoded (X.690 section 11.5).
+///
+/// Values are compared using `PartialEq`, so `f` should usually return a decoded value (for ex.
+/// using `parse_der_u32`) rather than a `DerObject`, which also contains the header.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::ber::{BerClassTag, BerTag};
+/// # use der_parser::der::*;
+/// # use der_parser::error::BerError;
+/// #
+/// // critical BOOLEAN DEFAULT FALSE
+/// let parser = parse_der_default(BerClassTag::universal(BerTag::Boolean), |i| {
+///     parse_der_bool(i).and_then(|(rem, obj)| Ok((rem, obj.as_bool()?)))
+/// }, false);
+/// let (_, critical) = parser(&[0x01, 0x01, 0xff]).expect("parsing failed");
+/// assert_eq!(critical, true);
+///
+/// let res = parser(&[0x01, 0x01, 0x00]);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::DerConstraintFailed)));
+/// ```
+pub fn parse_der_default<'a, O, F>(
+    tag: BerClassTag,
+    f: F,
+    default: O,
+) -> impl Fn(&'a [u8]) -> BerResult<'a, O>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+    O: Clone + PartialEq,
+{
+    let parser = parse_ber_optional_with_tag(tag, f);
+    move |i: &[u8]| match parser(i)? {
+        (_, Some(ref o)) if *o == default => Err(Err::Error(BerError::DerConstraintFailed)),
+        (rem, Some(o)) => Ok((rem, o)),
+        (rem, None) => Ok((rem, default.clone())),
+    }
+}
+
+/// Read the header of an object using DER rules, and apply `f` to its content
+///
+/// This is the DER version of [`parse_ber_container`](../ber/fn.parse_ber_container.html): a
+/// non-minimal or indefinite length is rejected with `BerError::DerConstraintFailed`.
+fn parse_der_container<'a, O, F>(f: F) -> impl Fn(&'a [u8]) -> BerResult<'a, O>
+where
+    F: Fn(BerObjectHeader<'a>, &'a [u8]) -> BerResult<'a, O>,
+{
+    move |input: &'a [u8]| {
+        let (i, hdr) = der_read_element_header(input)?;
+        let len = match hdr.len {
+            BerSize::Definite(len) => len,
+            BerSize::Indefinite => return Err(Err::Error(BerError::DerConstraintFailed)),
+        };
+        let (i, data) = take(len)(i)?;
+        let raw_header = &input[..input.len() - i.len() - data.len()];
+        let hdr = hdr.with_raw_encoding(raw_header, data);
+        let (_rest, v) = f(hdr, data)?;
+        Ok((i, v))
+    }
+}
+
+/// Parse a SET OF object, enforcing DER restrictions
+///
+/// Given a subparser for a DER type, parse a set of identical objects. DER requires the
+/// encodings of the elements to be sorted in ascending order (X.690 section 11.6): if an element
+/// is smaller than the previous one, `BerError::DerConstraintFailed` is returned. The header is
+/// read using DER rules, so the indefinite length form and non-minimal lengths are also rejected.
+///
+/// Use [`parse_ber_set_of`](../ber/fn.parse_ber_set_of.html) to accept elements in any order.
+///
+/// ### Example
+///
+/// ```
+/// # use der_parser::der::*;
+/// # use der_parser::error::BerError;
+/// #
+/// let parser = parse_der_set_of(parse_der_integer);
+/// let (_, obj) = parser(&[0x31, 0x06, 0x02, 0x01, 0x01, 0x02, 0x01, 0x02]).expect("parsing failed");
+/// assert_eq!(obj.len(), 2);
+///
+/// let res = parser(&[0x31, 0x06, 0x02, 0x01, 0x02, 0x02, 0x01, 0x01]);
+/// assert_eq!(res, Err(nom::Err::Error(BerError::DerConstraintFailed)));
+/// ```
+pub fn parse_der_set_of<'a, F>(f: F) -> impl Fn(&'a [u8]) -> DerResult<'a>
+where
+    F: Fn(&'a [u8]) -> DerResult<'a>,
+{
+    parse_der_container(move |hdr, data: &'a [u8]| {
+        custom_check!(data, hdr.tag != BerTag::Set, BerError::BerTypeError)?;
+        let mut l = Vec::new();
+        let mut previous: Option<&[u8]> = None;
+        let mut i = data;
+        while !i.is_empty() {
+            let (rem, obj) = f(i)?;
+            let encoding = &i[..i.len() - rem.len()];
+            match previous {
+                Some(p) if encoding < p => return Err(Err::Error(BerError::DerConstraintFailed)),
+                _ => (),
+            }
+            previous = Some(encoding);
+            l.push(obj);
+            i = rem;
+        }
+        Ok((i, DerObject::from_set(l)))
+    })
+}
+
+/// Parse a CMS (or PKCS#7) `ContentInfo` object, returning the content type and the content
+///
+/// This is the DER version of [`parse_ber_content_info`](../ber/fn.parse_ber_content_info.html):
+/// the headers and the content are parsed using DER rules, so the indefinite length form and
+/// non-minimal lengths are rejected with `BerError::DerConstraintFailed`.
+pub fn parse_der_content_info(i: &[u8]) -> BerResult<(Oid, DerObject)> {
+    parse_der_container(|hdr, i| {
+        custom_check!(i, hdr.tag != BerTag::Sequence, BerError::BerTypeError)?;
+        let (i, content_type) = parse_der_oid(i)?;
+        let content_type = content_type.as_oid_val()?;
+        let (i, content) = parse_der_container(|hdr, content| {
+            custom_check!(
+                content,
+                hdr.class == BerClass::Universal,
+                BerError::InvalidClass
+            )?;
+            custom_check!(content, hdr.tag != BerTag(0), BerError::InvalidTag)?;
+            custom_check!(content, !hdr.is_constructed(), BerError::ConstructExpected)?;
+            let (rem, content) = parse_der(content)?;
+            custom_check!(rem, !rem.is_empty(), BerError::UnexpectedData(rem.len()))?;
+            Ok((rem, content))
+        })(i)?;
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (content_type, content)))
+    })(i)
+}
+
+/// Parse an `AlgorithmIdentifier` object, returning the algorithm and the optional parameters
+///
+/// This is the DER version of
+/// [`parse_ber_algorithm_identifier`](../ber/fn.parse_ber_algorithm_identifier.html): the
+/// header and the parameters are parsed using DER rules, so the indefinite length form and
+/// non-minimal lengths are rejected with `BerError::DerConstraintFailed`.
+pub fn parse_der_algorithm_identifier(i: &[u8]) -> BerResult<(Oid, Option<DerObject>)> {
+    parse_der_container(|hdr, i| {
+        custom_check!(i, hdr.tag != BerTag::Sequence, BerError::BerTypeError)?;
+        let (i, algorithm) = parse_der_oid(i)?;
+        let algorithm = algorithm.as_oid_val()?;
+        let (i, params) = if i.is_empty() {
+            (i, None)
+        } else {
+            let (i, params) = parse_der(i)?;
+            (i, Some(params))
+        };
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (algorithm, params)))
+    })(i)
+}
+
+/// Parse a PKCS#8 `PrivateKeyInfo` object
+///
+/// This is the DER version of
+/// [`parse_ber_private_key_info`](../ber/fn.parse_ber_private_key_info.html): the headers and
+/// all the fields are parsed using DER rules, so the indefinite length form and non-minimal
+/// lengths are rejected with `BerError::DerConstraintFailed`.
+pub fn parse_der_private_key_info(i: &[u8]) -> BerResult<PrivateKeyInfo> {
+    parse_der_container(|hdr, i| {
+        custom_check!(i, hdr.tag != BerTag::Sequence, BerError::BerTypeError)?;
+        let (i, version) = parse_der_u32(i)?;
+        let (i, algorithm) = parse_der_algorithm_identifier(i)?;
+        let (i, private_key) = parse_der_octetstring(i)?;
+        let (i, attributes) = if i.is_empty() {
+            (i, None)
+        } else {
+            let (i, attributes) = parse_der_container(|hdr, content| {
+                custom_check!(content, hdr.tag != BerTag(0), BerError::InvalidTag)?;
+                let (rem, set) = parse_der_content(BerTag::Set)(content, &hdr, MAX_RECURSION)?;
+                Ok((rem, DerObject::from_header_and_content(hdr, set)))
+            })(i)?;
+            (i, Some(attributes))
+        };
+        custom_check!(i, !i.is_empty(), BerError::UnexpectedData(i.len()))?;
+        Ok((i, (version, algorithm, private_key, attributes)))
+    })(i)
+}
+
 /// Parse an implicit tagged object, applying function to read content
 ///
 /// Note: unlike explicit tagged functions, the callback must be a *content* parsing function,
@@ -391,7 +662,7 @@ where
 pub fn parse_der_u32(i: &[u8]) -> BerResult<u32> {
     match parse_ber_integer(i) {
         Ok((rem, ref obj)) => match obj.content {
-            BerObjectContent::Integer(i) => match i.len() {
+            BerObjectContent::Integer(ref i) => match i.len() {
                 1 => Ok((rem, u32::from(i[0]))),
                 2 => Ok((rem, u32::from(i[0]) << 8 | u32::from(i[1]))),
                 3 => Ok((
@@ -417,7 +688,7 @@ pub fn parse_der_u32(i: &[u8]) -> BerResult<u32> {
 pub fn parse_der_u64(i: &[u8]) -> BerResult<u64> {
     match parse_ber_integer(i) {
         Ok((rem, ref obj)) => match obj.content {
-            BerObjectContent::Integer(i) => match bytes_to_u64(i) {
+            BerObjectContent::Integer(ref i) => match bytes_to_u64(i) {
                 Ok(l) => Ok((rem, l)),
                 Err(_) => Err(Err::Error(BerError::IntegerTooLarge)),
             },
@@ -470,7 +741,7 @@ pub fn der_read_element_content_as(
 ) -> BerResult<BerObjectContent> {
     if let BerSize::Definite(l) = len {
         if i.len() < l {
-            return Err(Err::Incomplete(Needed::Size(l)));
+            return Err(Err::Incomplete(Needed::Size(l - i.len())));
         }
     }
     match tag {
@@ -479,12 +750,44 @@ pub fn der_read_element_content_as(
             custom_check!(i, len != 1, BerError::InvalidLength)?;
             der_constraint_fail_if!(i, i[0] != 0 && i[0] != 0xff);
         }
+        BerTag::Integer | BerTag::Enumerated => {
+            let len = len.primitive()?;
+            // X.690 8.3.2 (and 8.4 for ENUMERATED): the first 9 bits must not be all zeros or all ones
+            if len > 1 {
+                der_constraint_fail_if!(i, i[0] == 0 && i[1] & 0x80 == 0);
+                der_constraint_fail_if!(i, i[0] == 0xff && i[1] & 0x80 != 0);
+            }
+        }
+        BerTag::OctetString => {
+            // X.690 10.2: constructed encoding is not allowed in DER
+            der_constraint_fail_if!(i, constructed);
+        }
         BerTag::BitString => {
             der_constraint_fail_if!(i, constructed);
             let len = len.primitive()?;
             // exception: read and verify padding bits
             return der_read_content_bitstring(i, len);
         }
+        BerTag::RealType => {
+            der_constraint_fail_if!(i, constructed);
+            let len = len.primitive()?;
+            // exception: read and verify normalized encoding
+            return der_read_content_real(i, len);
+        }
+        BerTag::External => {
+            let (rem, content) = ber_read_element_content_as(i, tag, len, constructed, max_depth)?;
+            // X.690 10.2: the octet-aligned and arbitrary encodings must be primitive in DER
+            if let BerObjectContent::External(ref e) = content {
+                match e.encoding {
+                    BerExternalEncoding::OctetAlignedConstructed(_)
+                    | BerExternalEncoding::ArbitraryConstructed(_, _) => {
+                        return Err(Err::Error(BerError::DerConstraintFailed))
+                    }
+                    _ => (),
+                }
+            }
+            return Ok((rem, content));
+        }
         BerTag::NumericString
         | BerTag::VisibleString
         | BerTag::PrintableString
@@ -499,17 +802,37 @@ pub fn der_read_element_content_as(
         | BerTag::GeneralString => {
             der_constraint_fail_if!(i, constructed);
         }
-        BerTag::UtcTime | BerTag::GeneralizedTime => {
+        BerTag::UtcTime => {
             let len = len.primitive()?;
             if len == 0 || i.get(len - 1).cloned() != Some(b'Z') {
                 return Err(Err::Error(BerError::DerConstraintFailed));
             }
         }
+        BerTag::GeneralizedTime => {
+            let len = len.primitive()?;
+            if len == 0 || i.get(len - 1).cloned() != Some(b'Z') {
+                return Err(Err::Error(BerError::DerConstraintFailed));
+            }
+            der_check_generalizedtime(i, len)?;
+        }
         _ => (),
     }
     ber_read_element_content_as(i, tag, len, constructed, max_depth)
 }
 
+// X.690 section 11.7: the decimal point must be '.' and the fraction must not have trailing zeros
+// (the 'Z' suffix is checked by the caller)
+fn der_check_generalizedtime(i: &[u8], len: usize) -> BerResult<()> {
+    let (_, s) = take(len)(i)?;
+    der_constraint_fail_if!(i, s.contains(&b','));
+    if let Some(pos) = s.iter().position(|&c| c == b'.') {
+        // s ends with 'Z'
+        let fraction = &s[pos + 1..len - 1];
+        der_constraint_fail_if!(i, fraction.last().map_or(true, |&c| c == b'0'));
+    }
+    Ok((i, ()))
+}
+
 /// Parse DER object content recursively
 ///
 /// *Note: an error is raised if recursion depth exceeds `MAX_RECURSION`.
@@ -525,8 +848,8 @@ fn der_read_element_content_recursive<'a>(
     match hdr.class {
         BerClass::Universal | BerClass::Private => (),
         _ => {
-            let (i, content) = ber_get_object_content(i, &hdr)?;
-            let content = BerObjectContent::Unknown(hdr.tag, content);
+            let (i, content) = ber_get_object_content(i, &hdr, max_depth)?;
+            let content = BerObjectContent::Unknown(hdr.tag, Cow::Borrowed(content));
             let obj = BerObject::from_header_and_content(hdr, content);
             return Ok((i, obj));
         }
@@ -534,8 +857,8 @@ fn der_read_element_content_recursive<'a>(
     match der_read_element_content_as(i, hdr.tag, hdr.len, hdr.is_constructed(), max_depth) {
         Ok((rem, content)) => Ok((rem, DerObject::from_header_and_content(hdr, content))),
         Err(Err::Error(BerError::UnknownTag)) => {
-            let (rem, content) = ber_get_object_content(i, &hdr)?;
-            let content = BerObjectContent::Unknown(hdr.tag, content);
+            let (rem, content) = ber_get_object_content(i, &hdr, max_depth)?;
+            let content = BerObjectContent::Unknown(hdr.tag, Cow::Borrowed(content));
             let obj = BerObject::from_header_and_content(hdr, content);
             Ok((rem, obj))
         }
@@ -560,8 +883,43 @@ fn der_read_content_bitstring(i: &[u8], len: usize) -> BerResult<BerObjectConten
                           }
                           Ok((input,()))
                       }) >>
-        ( BerObjectContent::BitString(ignored_bits,BitStringObject{ data:s }) )
+        ( BerObjectContent::BitString(BitStringObject::new(ignored_bits, s)?) )
+    }
+}
+
+fn der_read_content_real(i: &[u8], len: usize) -> BerResult<BerObjectContent> {
+    let (rem, data) = take(len)(i)?;
+    // decode first, so the checks below only apply to well-formed values
+    let f = decode_real(data)?;
+    if let Some(&first) = data.first() {
+        match first & 0xc0 {
+            0x80 | 0xc0 => {
+                // binary encoding: base 2 and scaling factor 0 (11.3.1)
+                der_constraint_fail_if!(i, first & 0x3c != 0);
+                let (exp_len, start) = match first & 0b11 {
+                    0b11 => (usize::from(data[1]), 2),
+                    l => (usize::from(l) + 1, 1),
+                };
+                // 8.5.7.4: exponent must be encoded in the smallest possible number of octets
+                der_constraint_fail_if!(i, first & 0b11 == 0b11 && exp_len <= 3);
+                let (exp, mantissa) = data[start..].split_at(exp_len);
+                if exp_len > 1 {
+                    der_constraint_fail_if!(i, exp[0] == 0 && exp[1] & 0x80 == 0);
+                    der_constraint_fail_if!(i, exp[0] == 0xff && exp[1] & 0x80 != 0);
+                }
+                // mantissa must be 0 or odd (11.3.1), zero is encoded with no contents octets
+                der_constraint_fail_if!(i, mantissa[0] == 0);
+                der_constraint_fail_if!(i, mantissa[mantissa.len() - 1] & 1 == 0);
+            }
+            0x00 => {
+                // decimal encoding: NR3 form only (11.3.2)
+                der_constraint_fail_if!(i, first != 0x03);
+                der_constraint_fail_if!(i, data[1..].iter().any(|&b| b == b' ' || b == b','));
+            }
+            _ => (),
+        }
     }
+    Ok((rem, BerObjectContent::Real(f)))
 }
 
 /// Read an object header (DER)
@@ -569,6 +927,9 @@ pub fn der_read_element_header(i: &[u8]) -> BerResult<BerObjectHeader> {
     do_parse! {
         i,
         el:   parse_identifier >>
+              // X.690 8.1.2.4: high tag number form must be minimal, and only used for tags >= 31
+              custom_check!(el.3.len() > 1 && (el.3[1] == 0x80 || el.2 < 0x1f),
+                            BerError::DerConstraintFailed) >>
         len:  parse_ber_length_byte >>
         llen: cond!(len.0 == 1, take!(len.1)) >>
         ( {
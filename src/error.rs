@@ -1,11 +1,15 @@
 //! Error type for BER/DER parsers
 
-use crate::ber::BerObject;
+use crate::ber::{BerObject, BerTag};
 use crate::der::DerObject;
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
 use nom::error::{ErrorKind, ParseError};
 use nom::IResult;
+#[cfg(feature = "std")]
 use std::error::Error;
-use std::fmt;
 
 /// Holds the result of parsing functions
 ///
@@ -53,7 +57,17 @@ pub enum BerError {
     /// A DER constraint failed (object may be using BER encoding?)
     DerConstraintFailed,
 
+    /// UTCTime or GeneralizedTime value is malformed, or has out-of-range fields
+    InvalidTime,
+
+    /// String content could not be decoded using the charset of its type
+    StringInvalidCharset,
+
     UnknownTag,
+    /// The tag of the object does not match any of the expected tags
+    UnexpectedTag(BerTag),
+    /// Data remains after the object (the value is the number of remaining bytes)
+    UnexpectedData(usize),
     /// Feature is not yet implemented
     Unsupported,
 
@@ -63,6 +77,52 @@ pub enum BerError {
 
     /// Error raised by the underlying nom parser
     NomError(ErrorKind),
+
+    /// Error raised by a parser wrapped using [`context`](fn.context.html): the labels of the
+    /// enclosing contexts (outermost first), and the original error
+    Context(Vec<&'static str>, Box<BerError>),
+}
+
+impl BerError {
+    /// Add a context label to the error
+    ///
+    /// The label is added before any existing label, so the first label is the outermost context.
+    pub fn with_context(self, label: &'static str) -> BerError {
+        match self {
+            BerError::Context(mut labels, e) => {
+                labels.insert(0, label);
+                BerError::Context(labels, e)
+            }
+            e => BerError::Context(vec![label], Box::new(e)),
+        }
+    }
+
+    /// Return the context labels of the error (outermost first), or an empty slice
+    pub fn context(&self) -> &[&'static str] {
+        match self {
+            BerError::Context(labels, _) => labels,
+            _ => &[],
+        }
+    }
+
+    /// Return the error without its context labels
+    pub fn inner_error(&self) -> &BerError {
+        match self {
+            BerError::Context(_, e) => e,
+            e => e,
+        }
+    }
+}
+
+/// Error for BER/DER parsers, with the position of the object where parsing failed
+///
+/// See `parse_ber_with_offset` and `parse_der_with_offset`.
+#[derive(Debug, PartialEq)]
+pub struct BerErrorWithOffset {
+    /// The error returned by the parser
+    pub error: BerError,
+    /// Offset (relative to the original input) of the innermost object that could not be parsed
+    pub offset: usize,
 }
 
 impl From<BerError> for nom::Err<BerError> {
@@ -75,20 +135,96 @@ impl<I> ParseError<I> for BerError {
     fn from_error_kind(_input: I, kind: ErrorKind) -> Self {
         BerError::NomError(kind)
     }
-    fn append(_input: I, kind: ErrorKind, _other: Self) -> Self {
-        BerError::NomError(kind)
+    /// Keep the error of the inner parser, which is more specific than the nom combinator
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
     }
 }
 
 impl fmt::Display for BerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:?}", self)
+        let msg = match self {
+            BerError::BerTypeError => "object does not have the expected type",
+            BerError::BerValueError => "object does not have the expected value",
+            BerError::InvalidTag => "invalid tag",
+            BerError::InvalidClass => "invalid class",
+            BerError::InvalidLength => "invalid length",
+            BerError::IndefiniteLengthUnexpected => "unexpected indefinite length",
+            BerError::ConstructExpected => "object was expected to be constructed",
+            BerError::ConstructUnexpected => "object was expected to be primitive",
+            BerError::IntegerTooLarge => "integer too large to fit in target type",
+            BerError::BerMaxDepth => "maximum recursion depth reached",
+            BerError::ObjectTooShort => "object too short, some items could not be found",
+            BerError::DerConstraintFailed => "DER constraint failed",
+            BerError::InvalidTime => "invalid time value",
+            BerError::StringInvalidCharset => "string contains invalid characters for its type",
+            BerError::UnknownTag => "unknown tag",
+            BerError::UnexpectedTag(tag) => return write!(f, "unexpected tag {:?}", tag),
+            BerError::UnexpectedData(n) => {
+                return write!(f, "unexpected data after object ({} bytes)", n)
+            }
+            BerError::Unsupported => "feature not supported",
+            BerError::Custom(code) => return write!(f, "custom error {}", code),
+            BerError::NomError(kind) => return write!(f, "parser error ({:?})", kind),
+            BerError::Context(labels, e) => {
+                f.write_str("failed in ")?;
+                for (idx, label) in labels.iter().enumerate() {
+                    if idx > 0 {
+                        f.write_str(".")?;
+                    }
+                    f.write_str(label)?;
+                }
+                return write!(f, ": {}", e);
+            }
+        };
+        f.write_str(msg)
     }
 }
 
+#[cfg(feature = "std")]
 impl Error for BerError {}
 
-#[cfg(test)]
+/// Add a context label to the errors of parser `f`
+///
+/// Errors (and failures) returned by `f` are wrapped in `BerError::Context`, or have `label`
+/// added to their existing context. Nesting calls builds the path of the object where parsing
+/// failed, which is reported by the `Display` implementation. `Incomplete` is not modified.
+///
+/// This is similar to nom's `context` function, but integrated with `BerError`.
+///
+/// ### Example
+///
+/// ```rust
+/// # use der_parser::ber::*;
+/// # use der_parser::error::*;
+/// let inner = context("notAfter", parse_ber_utctime);
+/// let parser = context("validity", parse_ber_sequence_defined_g(|_, i| inner(i)));
+/// let bytes = &[0x30, 0x03, 0x02, 0x01, 0x00];
+/// let err = match parser(bytes) {
+///     Err(nom::Err::Error(e)) => e,
+///     _ => panic!("parsing should fail"),
+/// };
+/// assert_eq!(err.context(), &["validity", "notAfter"]);
+/// assert_eq!(err.inner_error(), &BerError::InvalidTag);
+/// assert_eq!(err.to_string(), "failed in validity.notAfter: invalid tag");
+/// ```
+pub fn context<'a, O, F>(label: &'static str, f: F) -> impl Fn(&'a [u8]) -> BerResult<'a, O>
+where
+    F: Fn(&'a [u8]) -> BerResult<'a, O>,
+{
+    move |i| f(i).map_err(|e| e.map(|e| e.with_context(label)))
+}
+
+impl fmt::Display for BerErrorWithOffset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.error, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BerErrorWithOffset {}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -98,4 +234,54 @@ mod tests {
         // println!("{}", e);
         let _: Result<(), Box<dyn Error>> = Err(Box::new(e));
     }
+
+    #[test]
+    fn test_display_bererror() {
+        assert_eq!(
+            BerError::IntegerTooLarge.to_string(),
+            "integer too large to fit in target type"
+        );
+        assert_eq!(BerError::Custom(42).to_string(), "custom error 42");
+        assert_eq!(
+            BerError::NomError(ErrorKind::Eof).to_string(),
+            "parser error (Eof)"
+        );
+        let e = BerErrorWithOffset {
+            error: BerError::InvalidLength,
+            offset: 12,
+        };
+        assert_eq!(e.to_string(), "invalid length at offset 12");
+        let e: Box<dyn Error> = Box::new(e);
+        assert_eq!(e.to_string(), "invalid length at offset 12");
+    }
+
+    #[test]
+    fn test_context_bererror() {
+        let e = BerError::InvalidTime.with_context("notAfter");
+        assert_eq!(e.context(), &["notAfter"]);
+        let e = e.with_context("validity").with_context("tbsCertificate");
+        assert_eq!(e.context(), &["tbsCertificate", "validity", "notAfter"]);
+        assert_eq!(e.inner_error(), &BerError::InvalidTime);
+        assert_eq!(
+            e.to_string(),
+            "failed in tbsCertificate.validity.notAfter: invalid time value"
+        );
+        assert!(BerError::InvalidTime.context().is_empty());
+        assert_eq!(BerError::InvalidTime.inner_error(), &BerError::InvalidTime);
+        // errors and failures are tagged, incomplete is not
+        let f = context("a", |_| -> BerResult<()> {
+            Err(nom::Err::Failure(BerError::Custom(1)))
+        });
+        assert_eq!(
+            f(&[]),
+            Err(nom::Err::Failure(BerError::Context(
+                vec!["a"],
+                Box::new(BerError::Custom(1))
+            )))
+        );
+        let f = context("a", |_| -> BerResult<()> {
+            Err(nom::Err::Incomplete(nom::Needed::Size(1)))
+        });
+        assert_eq!(f(&[]), Err(nom::Err::Incomplete(nom::Needed::Size(1))));
+    }
 }
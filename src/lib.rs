@@ -133,6 +133,19 @@
 //! - The DER constraints are verified if using `parse_der`.
 //! - `BerObject` and `DerObject` are the same objects (type alias). The only difference is the
 //!   verification of constraints *during parsing*.
+//! - This crate can be used in `no_std` environments (with `alloc`), by disabling the default
+//!   `std` feature. This requires a nightly compiler, because nom 5 uses the (unstable) `alloc`
+//!   feature without `std`. The `serialize` and `bigint` features require `std`.
+//! - The names of well-known OIDs (see `Oid::to_name`) are available if the `oid-names` feature
+//!   is enabled.
+//! - Objects implement `serde::Serialize` if the `serde` feature is enabled, to export parsed
+//!   objects in a readable form (for ex. JSON).
+//! - Parsers for structures encoded as SEQUENCE can be generated using `#[derive(BerSequence)]`
+//!   if the `derive` feature is enabled (see the `FromBer` trait).
+//! - BitString contents can be accessed as `bitvec::slice::BitSlice` (`as_bitslice`) if the
+//!   `bitvec` feature is enabled.
+//! - Content can be compared to a slice in constant time (`ct_eq_content`) if the `subtle`
+//!   feature is enabled.
 //!
 //! # Serialization
 //!
@@ -170,11 +183,19 @@
 // pragmas for doc
 #![deny(broken_intra_doc_links)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
+#![cfg_attr(not(feature = "std"), no_std)]
 #![doc(test(
     no_crate_inject,
     attr(deny(warnings/*, rust_2018_idioms*/), allow(dead_code, unused_variables))
 ))]
 
+#[cfg(not(feature = "std"))]
+#[macro_use]
+extern crate alloc;
+// `extern crate alloc` is only stable since 1.36, `Vec`, `String` etc. are re-exported by std
+#[cfg(feature = "std")]
+extern crate std as alloc;
+
 #[macro_use]
 mod macros;
 
@@ -183,11 +204,16 @@ pub mod ber;
 pub mod der;
 pub mod error;
 pub mod oid;
+#[cfg(feature = "oid-names")]
+mod oid_names;
 
 // compatibility: re-export at crate root
 pub use ber::parse_ber;
 pub use der::parse_der;
 
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+pub extern crate chrono;
 pub extern crate nom;
 #[cfg(feature = "bigint")]
 #[cfg_attr(docsrs, doc(cfg(feature = "bigint")))]
@@ -197,11 +223,16 @@ pub extern crate num_bigint;
 pub use nom::IResult;
 #[doc(hidden)]
 pub use nom::{alt, call, complete, do_parse, eof, many0, map, map_res, verify};
-#[doc(hidden)]
-pub use rusticata_macros::{custom_check, flat_take};
 
 use proc_macro_hack::proc_macro_hack;
 
 #[proc_macro_hack]
 /// Procedural macro to get encoded oids, see the [oid module](oid/index.html).
 pub use der_oid_macro::oid;
+
+/// Derive a BER parser for a structure (see [`FromBer`](ber/trait.FromBer.html))
+///
+/// *This macro is only available if the `derive` feature is enabled.*
+#[cfg(feature = "derive")]
+#[cfg_attr(docsrs, doc(cfg(feature = "derive")))]
+pub use der_parser_derive::BerSequence;
@@ -1,3 +1,51 @@
+// `custom_check` and `flat_take` are the same as the macros of `rusticata_macros`, but they do
+// not depend on std (and use nom from this crate), so they can be used without std
+
+/// Helper macro for nom parsers: raise error if the condition is false
+///
+/// This macro is used when using custom errors
+#[doc(hidden)]
+#[macro_export]
+macro_rules! custom_check (
+  ($i:expr, $cond:expr, $err:expr) => (
+    {
+      if $cond {
+        Err($crate::nom::Err::Error($err))
+      } else {
+        Ok(($i, ()))
+      }
+    }
+  );
+);
+
+/// Combination and flat_map! and take! as first combinator
+#[doc(hidden)]
+#[macro_export]
+macro_rules! flat_take (
+    ($i:expr, $len:expr, $f:ident) => ({
+        if $i.len() < $len { Err($crate::nom::Err::Incomplete($crate::nom::Needed::Size($len))) }
+        else {
+            let taken = &$i[0..$len];
+            let rem = &$i[$len..];
+            match $f(taken) {
+                Ok((_,res)) => Ok((rem,res)),
+                Err(e)      => Err(e)
+            }
+        }
+    });
+    ($i:expr, $len:expr, $submac:ident!( $($args:tt)*)) => ({
+        if $i.len() < $len { Err($crate::nom::Err::Incomplete($crate::nom::Needed::Size($len))) }
+        else {
+            let taken = &$i[0..$len];
+            let rem = &$i[$len..];
+            match $submac!(taken, $($args)*) {
+                Ok((_,res)) => Ok((rem,res)),
+                Err(e)      => Err(e)
+            }
+        }
+    });
+);
+
 /// Internal parser, do not use directly
 #[doc(hidden)]
 #[macro_export]
@@ -656,3 +704,59 @@ macro_rules! parse_der_application(
     });
     ($i:expr, $tag:expr, $($rest:tt)*) => ( parse_der_application!($i, APPLICATION $tag, $($rest)*) );
 );
+
+/// Internal parser, do not use directly
+#[doc(hidden)]
+#[macro_export]
+macro_rules! parse_ber_tuple_impl(
+    ($($f:expr => $v:ident),+) => (
+        $crate::ber::parse_ber_sequence_defined_g(|_, i| {
+            $( let (i, $v) = ($f)(i)?; )+
+            if !i.is_empty() {
+                return Err($crate::nom::Err::Error($crate::error::BerError::UnexpectedData(i.len())));
+            }
+            Ok((i, ($($v),+)))
+        })
+    );
+);
+
+/// Parse a SEQUENCE with a fixed number of elements, and return them as a tuple
+///
+/// Each parser is applied in order to the content of the SEQUENCE, and the results are returned
+/// as a tuple. All parsers must have the same error type (usually `BerError`). If some data
+/// remains in the SEQUENCE after the last element, `BerError::UnexpectedData` is returned.
+///
+/// This macro returns a parser function (it does not take the input as first argument), and
+/// accepts 2 to 6 parsers.
+///
+/// ```rust
+/// # #[macro_use] extern crate der_parser;
+/// use der_parser::ber::*;
+///
+/// # fn main() {
+/// let bytes = &[0x30, 0x08, 0x02, 0x01, 0x01, 0x04, 0x03, 0x61, 0x62, 0x63];
+/// let (rem, (version, name)) =
+///     parse_ber_tuple!(u32::from_ber, parse_ber_octetstring)(bytes).expect("parsing failed");
+/// assert!(rem.is_empty());
+/// assert_eq!(version, 1);
+/// assert_eq!(name.as_slice(), Ok(&b"abc"[..]));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! parse_ber_tuple(
+    ($f1:expr, $f2:expr $(,)?) => (
+        $crate::parse_ber_tuple_impl!($f1 => a, $f2 => b)
+    );
+    ($f1:expr, $f2:expr, $f3:expr $(,)?) => (
+        $crate::parse_ber_tuple_impl!($f1 => a, $f2 => b, $f3 => c)
+    );
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr $(,)?) => (
+        $crate::parse_ber_tuple_impl!($f1 => a, $f2 => b, $f3 => c, $f4 => d)
+    );
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr $(,)?) => (
+        $crate::parse_ber_tuple_impl!($f1 => a, $f2 => b, $f3 => c, $f4 => d, $f5 => e)
+    );
+    ($f1:expr, $f2:expr, $f3:expr, $f4:expr, $f5:expr, $f6:expr $(,)?) => (
+        $crate::parse_ber_tuple_impl!($f1 => a, $f2 => b, $f3 => c, $f4 => d, $f5 => e, $f6 => f)
+    );
+);
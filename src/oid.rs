@@ -47,27 +47,48 @@
 //! ```
 //! *Attention*, be aware that the latter version might not handle the case of a relative oid correctly. An
 //! extra check might be necessary.
-use std::borrow::Cow;
-use std::convert::From;
-use std::fmt;
-use std::iter::{ExactSizeIterator, FusedIterator, Iterator};
-use std::ops::Shl;
-use std::str::FromStr;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::From;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{ExactSizeIterator, FusedIterator, Iterator};
+use core::ops::Shl;
+use core::str::FromStr;
 
 #[cfg(feature = "bigint")]
 use num_bigint::BigUint;
 use num_traits::Num;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum ParseError {
     TooShort,
     /// Signalizes that the first or second component is too large.
-    /// The first must be within the range 0 to 6 (inclusive).
-    /// The second component must be less than 40.
+    /// The first must be within the range 0 to 2 (inclusive).
+    /// The second component must be less than 40 if the first is 0 or 1.
     FirstComponentsTooLarge,
     ParseIntError,
 }
 
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = match self {
+            ParseError::TooShort => "OID must have at least two components",
+            ParseError::FirstComponentsTooLarge => "first or second OID component is too large",
+            ParseError::ParseIntError => {
+                "OID components must be decimal integers separated by dots"
+            }
+        };
+        f.write_str(msg)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
 /// Object ID (OID) representation which can be relative or non-relative.
 /// An example for an oid in string representation is "1.2.840.113549.1.1.5".
 ///
@@ -76,7 +97,7 @@ pub enum ParseError {
 /// This library contains a procedural macro `oid` which can be used to
 /// create oids. For example `oid!(1.2.44.233)` or `oid!(rel 44.233)`
 /// for relative oids. See the [module documentation](index.html) for more information.
-#[derive(Hash, PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone)]
 pub struct Oid<'a> {
     asn1: Cow<'a, [u8]>,
     pub relative: bool,
@@ -116,8 +137,9 @@ impl<'a> Oid<'a> {
 
     /// Build an OID from an array of object identifier components.
     /// This method allocates memory on the heap.
-    // we do not use .copied() for compatibility with 1.34
-    #[allow(clippy::map_clone)]
+    ///
+    /// The first component must be 0, 1 or 2. The second component must be less than 40 if the
+    /// first is 0 or 1, and is not limited under arc 2 (X.690 8.19.4).
     pub fn from<'b>(s: &'b [u64]) -> Result<Oid<'static>, ParseError> {
         if s.len() < 2 {
             if s.len() == 1 && s[0] == 0 {
@@ -128,12 +150,12 @@ impl<'a> Oid<'a> {
             }
             return Err(ParseError::TooShort);
         }
-        if s[0] >= 7 || s[1] >= 40 {
-            return Err(ParseError::FirstComponentsTooLarge);
-        }
-        let asn1_encoded: Vec<u8> = [(s[0] * 40 + s[1]) as u8]
-            .iter()
-            .map(|&x| x)
+        let first = match (s[0], s[1]) {
+            (0, x) | (1, x) if x < 40 => s[0] * 40 + x,
+            (2, x) if x <= u64::max_value() - 80 => 80 + x,
+            _ => return Err(ParseError::FirstComponentsTooLarge),
+        };
+        let asn1_encoded: Vec<u8> = encode_relative(&[first])
             .chain(encode_relative(&s[2..]))
             .collect();
         Ok(Oid {
@@ -212,8 +234,94 @@ impl<'a> Oid<'a> {
             oid: &self,
             pos: 0,
             first: false,
-            n: std::marker::PhantomData,
+            second: None,
+        }
+    }
+
+    /// Return an iterator over the sub-identifiers (arcs), as `u64` values.
+    ///
+    /// Contrary to `iter`, this function does not fail if some arcs do not fit into `u64`: an
+    /// error containing the raw (base-128) encoding of the arc is returned for these arcs.
+    ///
+    /// ```rust
+    /// use der_parser::oid::Oid;
+    ///
+    /// let oid = Oid::from(&[1, 2, 840, 113_549]).unwrap();
+    /// let arcs: Result<Vec<u64>, _> = oid.iter_arcs().collect();
+    /// assert_eq!(arcs, Ok(vec![1, 2, 840, 113_549]));
+    /// ```
+    pub fn iter_arcs(&self) -> ArcIterator<'_> {
+        ArcIterator {
+            asn1: self.asn1.as_ref(),
+            relative: self.relative,
+            pos: 0,
+            first: false,
+            second: None,
+        }
+    }
+
+    /// Test if the OID starts with the arcs of `prefix` (for ex. if the OID is under `2.5.29`)
+    ///
+    /// Arcs are compared after decoding, not the encoded bytes. An OID starts with itself.
+    /// Relative and non-relative OIDs never match.
+    ///
+    /// ```rust
+    /// use der_parser::oid;
+    ///
+    /// let oid = oid!(2.5.29.17);
+    /// assert!(oid.starts_with(&oid!(2.5.29)));
+    /// assert!(!oid.starts_with(&oid!(2.5.2)));
+    /// ```
+    pub fn starts_with(&self, prefix: &Oid) -> bool {
+        if self.relative != prefix.relative {
+            return false;
+        }
+        let mut arcs = self.iter_arcs();
+        prefix.iter_arcs().all(|p| arcs.next() == Some(p))
+    }
+
+    /// Return the first two arcs, which are combined in the first sub-identifier
+    ///
+    /// The first arc is the root arc (0, 1 or 2). Following X.690 8.19.4, the second arc is less
+    /// than 40 if the first arc is 0 or 1, and can be any value under arc 2.
+    ///
+    /// Returns `None` for relative OIDs, empty OIDs, or if the second arc does not fit into `u64`.
+    ///
+    /// ```rust
+    /// use der_parser::oid;
+    ///
+    /// assert_eq!(oid!(1.2.840.113549).first_arcs(), Some((1, 2)));
+    /// assert_eq!(oid!(2.5.4.3).first_arcs(), Some((2, 5)));
+    /// assert_eq!(oid!(rel 1.2).first_arcs(), None);
+    /// ```
+    pub fn first_arcs(&self) -> Option<(u8, u64)> {
+        if self.relative {
+            return None;
         }
+        let mut arcs = self.iter_arcs();
+        let first = arcs.next()?.ok()?;
+        // the zero OID has a single arc
+        let second = arcs.next().unwrap_or(Ok(0)).ok()?;
+        Some((first as u8, second))
+    }
+
+    /// Return the name of the OID, if it is a well-known OID (for ex. "commonName" for
+    /// `2.5.4.3`)
+    ///
+    /// The registry contains common X.509 attributes, extensions and algorithms.
+    ///
+    /// ```rust
+    /// use der_parser::oid;
+    ///
+    /// assert_eq!(oid!(2.5.4.3).to_name(), Some("commonName"));
+    /// assert_eq!(oid!(1.2.3.4).to_name(), None);
+    /// ```
+    ///
+    /// *This function is only available if the `oid-names` feature is enabled.*
+    #[cfg(feature = "oid-names")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "oid-names")))]
+    pub fn to_name(&self) -> Option<&'static str> {
+        crate::oid_names::oid_name(self)
     }
 
     /// Return an iterator over the sub-identifiers (arcs).
@@ -222,14 +330,9 @@ impl<'a> Oid<'a> {
         &'b self,
     ) -> Option<impl Iterator<Item = u64> + FusedIterator + ExactSizeIterator + 'b> {
         // Check that every arc fits into u64
-        let bytes = if self.relative {
-            &self.asn1
-        } else if self.asn1.is_empty() {
-            &[]
-        } else {
-            &self.asn1[1..]
-        };
-        let max_bits = bytes
+        // The first sub-identifier of non-relative OIDs can also be larger than one byte
+        let max_bits = self
+            .asn1
             .iter()
             .fold((0usize, 0usize), |(max, cur), c| {
                 let is_end = (c >> 7) == 0u8;
@@ -248,44 +351,81 @@ impl<'a> Oid<'a> {
             oid: &self,
             pos: 0,
             first: false,
-            n: std::marker::PhantomData,
+            second: None,
         })
     }
 }
 
-trait Repr: Num + Shl<usize, Output = Self> + From<u8> {}
-impl<N> Repr for N where N: Num + Shl<usize, Output = N> + From<u8> {}
-
-struct SubIdentifierIterator<'a, N: Repr> {
-    oid: &'a Oid<'a>,
+/// Iterator over the arcs of an OID, see `Oid::iter_arcs`
+#[derive(Debug, Clone)]
+pub struct ArcIterator<'a> {
+    asn1: &'a [u8],
+    relative: bool,
     pos: usize,
     first: bool,
-    n: std::marker::PhantomData<&'a N>,
+    second: Option<Result<u64, &'a [u8]>>,
 }
 
-impl<'a, N: Repr> Iterator for SubIdentifierIterator<'a, N> {
-    type Item = N;
+impl<'a> Iterator for ArcIterator<'a> {
+    type Item = Result<u64, &'a [u8]>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        use num_traits::identities::Zero;
-
-        if self.pos == self.oid.asn1.len() {
+        if let Some(second) = self.second.take() {
+            return Some(second);
+        }
+        if self.pos == self.asn1.len() {
             return None;
         }
-        if !self.oid.relative {
-            if !self.first {
-                debug_assert!(self.pos == 0);
-                self.first = true;
-                return Some((self.oid.asn1[0] / 40).into());
-            } else if self.pos == 0 {
-                self.pos += 1;
-                if self.oid.asn1[0] == 0 && self.oid.asn1.len() == 1 {
-                    return None;
-                }
-                return Some((self.oid.asn1[0] % 40).into());
+        let start = self.pos;
+        let mut res = Some(0u64);
+        for o in self.asn1[start..].iter() {
+            self.pos += 1;
+            res = res
+                .and_then(|r| r.checked_mul(128))
+                .map(|r| r | u64::from(o & 0b111_1111));
+            if o >> 7 == 0u8 {
+                break;
             }
         }
-        // decode objet sub-identifier according to the asn.1 standard
+        let res = res.ok_or(&self.asn1[start..self.pos]);
+        if !self.relative && !self.first {
+            self.first = true;
+            if self.asn1 == [0] {
+                return Some(res);
+            }
+            // the first sub-identifier encodes the first two arcs (X.690 8.19.4)
+            let (first, second) = match res {
+                Ok(v) if v < 40 => (0, Ok(v)),
+                Ok(v) if v < 80 => (1, Ok(v - 40)),
+                Ok(v) => (2, Ok(v - 80)),
+                // values which do not fit into u64 are larger than 80
+                Err(raw) => (2, Err(raw)),
+            };
+            self.second = Some(second);
+            return Some(Ok(first));
+        }
+        Some(res)
+    }
+}
+
+impl<'a> FusedIterator for ArcIterator<'a> {}
+
+trait Repr: Num + Shl<usize, Output = Self> + From<u8> + PartialOrd {}
+impl<N> Repr for N where N: Num + Shl<usize, Output = N> + From<u8> + PartialOrd {}
+
+struct SubIdentifierIterator<'a, N: Repr> {
+    oid: &'a Oid<'a>,
+    pos: usize,
+    first: bool,
+    /// Second arc, decoded with the first one
+    second: Option<N>,
+}
+
+impl<'a, N: Repr> SubIdentifierIterator<'a, N> {
+    // decode objet sub-identifier according to the asn.1 standard
+    fn next_subidentifier(&mut self) -> N {
+        use num_traits::identities::Zero;
+
         let mut res = <N as Zero>::zero();
         for o in self.oid.asn1[self.pos..].iter() {
             self.pos += 1;
@@ -295,6 +435,41 @@ impl<'a, N: Repr> Iterator for SubIdentifierIterator<'a, N> {
                 break;
             }
         }
+        res
+    }
+
+    fn is_zero_oid(&self) -> bool {
+        !self.oid.relative && self.oid.asn1.as_ref() == [0]
+    }
+}
+
+impl<'a, N: Repr> Iterator for SubIdentifierIterator<'a, N> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(second) = self.second.take() {
+            return Some(second);
+        }
+        if self.pos == self.oid.asn1.len() {
+            return None;
+        }
+        let res = self.next_subidentifier();
+        if !self.oid.relative && !self.first {
+            self.first = true;
+            if self.is_zero_oid() {
+                return Some(res);
+            }
+            // the first sub-identifier encodes the first two arcs (X.690 8.19.4)
+            let (first, second) = if res < N::from(40) {
+                (0, res)
+            } else if res < N::from(80) {
+                (1, res - N::from(40))
+            } else {
+                (2, res - N::from(80))
+            };
+            self.second = Some(second);
+            return Some(N::from(first));
+        }
         Some(res)
     }
 }
@@ -303,27 +478,22 @@ impl<'a, N: Repr> FusedIterator for SubIdentifierIterator<'a, N> {}
 
 impl<'a, N: Repr> ExactSizeIterator for SubIdentifierIterator<'a, N> {
     fn len(&self) -> usize {
-        if self.oid.relative {
-            self.oid.asn1.iter().filter(|o| (*o >> 7) == 0u8).count()
-        } else if self.oid.asn1.len() == 0 {
-            0
-        } else if self.oid.asn1.len() == 1 {
-            if self.oid.asn1[0] == 0 {
-                1
-            } else {
-                2
-            }
+        let remaining = self.oid.asn1[self.pos..]
+            .iter()
+            .filter(|o| (*o >> 7) == 0u8)
+            .count();
+        let pending = if self.second.is_some() { 1 } else { 0 };
+        if !self.oid.relative && !self.first && remaining > 0 && !self.is_zero_oid() {
+            // the first sub-identifier has not been split yet
+            remaining + 1
         } else {
-            2 + self.oid.asn1[2..]
-                .iter()
-                .filter(|o| (*o >> 7) == 0u8)
-                .count()
+            remaining + pending
         }
     }
 
     #[cfg(feature = "exact_size_is_empty")]
     fn is_empty(&self) -> bool {
-        self.oid.asn1.is_empty()
+        self.len() == 0
     }
 }
 
@@ -344,21 +514,85 @@ impl<'a> fmt::Debug for Oid<'a> {
     }
 }
 
+/// Compare two arcs returned by `Oid::iter_arcs`
+fn cmp_arc(a: &Result<u64, &[u8]>, b: &Result<u64, &[u8]>) -> Ordering {
+    match (a, b) {
+        (Ok(a), Ok(b)) => a.cmp(b),
+        // arcs which do not fit into u64 are larger
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(a), Err(b)) => {
+            // ignore leading zero groups, then compare the lengths and the base-128 groups
+            let a = &a[a.iter().take_while(|&&o| o == 0x80).count()..];
+            let b = &b[b.iter().take_while(|&&o| o == 0x80).count()..];
+            a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+        }
+    }
+}
+
+/// OIDs are ordered by their arcs, compared numerically (relative OIDs are sorted after
+/// non-relative OIDs)
+impl<'a> Ord for Oid<'a> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let arcs = || {
+            let mut a = self.iter_arcs();
+            let mut b = other.iter_arcs();
+            loop {
+                match (a.next(), b.next()) {
+                    (None, None) => return Ordering::Equal,
+                    (None, Some(_)) => return Ordering::Less,
+                    (Some(_), None) => return Ordering::Greater,
+                    (Some(x), Some(y)) => match cmp_arc(&x, &y) {
+                        Ordering::Equal => (),
+                        o => return o,
+                    },
+                }
+            }
+        };
+        self.relative
+            .cmp(&other.relative)
+            .then_with(arcs)
+            // only differs for non-minimal encodings, to be consistent with Eq
+            .then_with(|| self.asn1.cmp(&other.asn1))
+    }
+}
+
+impl<'a> PartialOrd for Oid<'a> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<'a> Hash for Oid<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.relative.hash(state);
+        for arc in self.iter_arcs() {
+            arc.hash(state);
+        }
+    }
+}
+
 impl<'a> FromStr for Oid<'a> {
     type Err = ParseError;
 
+    /// Parse an OID from its dotted-decimal representation, for ex. "1.2.840.113549.1.1.11"
+    ///
+    /// The first component must be 0, 1 or 2, and the second component must be less than 40 if
+    /// the first is 0 or 1 (X.660).
+    // we do not use .copied() for compatibility with 1.34
+    #[allow(clippy::map_clone)]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let v: Result<Vec<_>, _> = s.split('.').map(|c| c.parse::<u64>()).collect();
-        v.map_err(|_| ParseError::ParseIntError)
-            .and_then(|v| Oid::from(&v))
+        let v = v.map_err(|_| ParseError::ParseIntError)?;
+        Oid::from(&v)
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use crate::oid::Oid;
-    use std::borrow::Cow;
-    use std::str::FromStr;
+    use alloc::borrow::Cow;
+    use core::str::FromStr;
 
     #[test]
     fn test_oid_fmt() {
@@ -420,6 +654,106 @@ mod tests {
         assert_eq!(oid_ref, oid);
     }
 
+    #[test]
+    fn test_oid_from_str_errors() {
+        use super::ParseError;
+        assert_eq!(Oid::from_str("1"), Err(ParseError::TooShort));
+        assert_eq!(Oid::from_str(""), Err(ParseError::ParseIntError));
+        assert_eq!(Oid::from_str("1..2"), Err(ParseError::ParseIntError));
+        assert_eq!(Oid::from_str("1.2.a"), Err(ParseError::ParseIntError));
+        assert_eq!(Oid::from_str("1.2.-3"), Err(ParseError::ParseIntError));
+        assert_eq!(
+            Oid::from_str("3.1"),
+            Err(ParseError::FirstComponentsTooLarge)
+        );
+        assert_eq!(
+            Oid::from_str("1.40"),
+            Err(ParseError::FirstComponentsTooLarge)
+        );
+        assert_eq!(
+            ParseError::ParseIntError.to_string(),
+            "OID components must be decimal integers separated by dots"
+        );
+    }
+
+    #[test]
+    fn test_oid_from_str_joint_iso_itu_t() {
+        let oid = Oid::from_str("2.5.29.17").unwrap();
+        assert_eq!(oid.bytes(), &[0x55, 0x1d, 0x11]);
+        // the second component can be larger than 39
+        let oid = Oid::from_str("2.999.3").unwrap();
+        assert_eq!(oid.bytes(), &[0x88, 0x37, 0x03]);
+        assert_eq!(oid.to_id_string(), "2.999.3");
+        assert_eq!(oid.iter().unwrap().len(), 3);
+        assert_eq!(oid.iter().unwrap().collect::<Vec<_>>(), vec![2, 999, 3]);
+    }
+
+    #[test]
+    fn test_iter_arcs() {
+        let oid = Oid::from(&[0, 0, 17, 773]).unwrap();
+        let arcs: Result<Vec<_>, _> = oid.iter_arcs().collect();
+        assert_eq!(arcs, Ok(vec![0, 0, 17, 773]));
+        let oid = Oid::from_relative(&[840, 1]).unwrap();
+        let arcs: Result<Vec<_>, _> = oid.iter_arcs().collect();
+        assert_eq!(arcs, Ok(vec![840, 1]));
+        let oid = Oid::from(&[0]).unwrap();
+        assert_eq!(oid.iter_arcs().collect::<Vec<_>>(), vec![Ok(0)]);
+        // u64::MAX uses 10 bytes
+        let oid = Oid::from(&[1, 2, u64::max_value()]).unwrap();
+        let arcs: Result<Vec<_>, _> = oid.iter_arcs().collect();
+        assert_eq!(arcs, Ok(vec![1, 2, u64::max_value()]));
+        // arc larger than u64
+        let raw = [0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+        let bytes: Vec<u8> = [42]
+            .iter()
+            .chain(raw.iter())
+            .chain([5].iter())
+            .cloned()
+            .collect();
+        let oid = Oid::new(Cow::Borrowed(&bytes));
+        assert!(oid.iter().is_none());
+        let arcs: Vec<_> = oid.iter_arcs().collect();
+        assert_eq!(arcs, vec![Ok(1), Ok(2), Err(&raw[..]), Ok(5)]);
+        // first sub-identifier larger than u64
+        let oid = Oid::new(Cow::Borrowed(&raw));
+        let arcs: Vec<_> = oid.iter_arcs().collect();
+        assert_eq!(arcs, vec![Ok(2), Err(&raw[..])]);
+    }
+
+    #[test]
+    fn test_oid_starts_with() {
+        let oid = Oid::from(&[2, 5, 29, 17]).unwrap();
+        assert!(oid.starts_with(&Oid::from(&[2, 5]).unwrap()));
+        assert!(oid.starts_with(&Oid::from(&[2, 5, 29]).unwrap()));
+        // prefix equal to the OID
+        assert!(oid.starts_with(&oid));
+        // prefix longer than the OID
+        assert!(!oid.starts_with(&Oid::from(&[2, 5, 29, 17, 1]).unwrap()));
+        assert!(!oid.starts_with(&Oid::from(&[2, 5, 2]).unwrap()));
+        assert!(!oid.starts_with(&Oid::from_relative(&[2, 5]).unwrap()));
+        // 840 is encoded as 86 48
+        let oid = Oid::from(&[1, 2, 840, 1]).unwrap();
+        assert!(oid.starts_with(&Oid::from(&[1, 2, 840]).unwrap()));
+        assert!(!oid.starts_with(&Oid::from(&[1, 2, 6]).unwrap()));
+        assert!(!oid.starts_with(&Oid::from(&[1, 2, 840, 13]).unwrap()));
+        let oid = Oid::from_relative(&[840, 1]).unwrap();
+        assert!(oid.starts_with(&Oid::from_relative(&[840]).unwrap()));
+    }
+
+    #[test]
+    fn test_iter_len_remaining() {
+        let oid = Oid::from(&[1, 2, 3]).unwrap();
+        let mut it = oid.iter().unwrap();
+        assert_eq!(it.len(), 3);
+        assert_eq!(it.next(), Some(1));
+        assert_eq!(it.len(), 2);
+        assert_eq!(it.next(), Some(2));
+        assert_eq!(it.len(), 1);
+        assert_eq!(it.next(), Some(3));
+        assert_eq!(it.len(), 0);
+        assert_eq!(it.next(), None);
+    }
+
     /// This test case will test an OID beginning with two zero
     /// subidentifiers (literally: "itu-t recommendation"), as
     /// used for example in the TCAP (Q.773) specification.
@@ -452,4 +786,38 @@ mod tests {
         let oid_from = Oid::from(&[0]).unwrap();
         assert_eq!(oid_from.asn1.as_ref(), &[0]);
     }
+
+    #[test]
+    fn test_oid_first_arcs() {
+        use super::ParseError;
+        assert_eq!(Oid::from(&[0, 9, 2342]).unwrap().first_arcs(), Some((0, 9)));
+        assert_eq!(Oid::from(&[0]).unwrap().first_arcs(), Some((0, 0)));
+        assert_eq!(Oid::from(&[1, 39]).unwrap().first_arcs(), Some((1, 39)));
+        assert_eq!(Oid::from(&[2, 39]).unwrap().first_arcs(), Some((2, 39)));
+        // the second arc is not limited under arc 2
+        let oid = Oid::from(&[2, 40]).unwrap();
+        assert_eq!(oid.bytes(), &[120]);
+        let oid = Oid::from(&[2, 999, 3]).unwrap();
+        assert_eq!(oid.bytes(), &[0x88, 0x37, 0x03]);
+        assert_eq!(oid, Oid::from_str("2.999.3").unwrap());
+        assert_eq!(oid.first_arcs(), Some((2, 999)));
+        assert_eq!(
+            Oid::from(&[1, 40]),
+            Err(ParseError::FirstComponentsTooLarge)
+        );
+        // 3.1 would be encoded as 2.41
+        assert_eq!(Oid::from(&[3, 1]), Err(ParseError::FirstComponentsTooLarge));
+        assert_eq!(Oid::from(&[6, 0]), Err(ParseError::FirstComponentsTooLarge));
+        assert_eq!(
+            Oid::from_str("3.1"),
+            Err(ParseError::FirstComponentsTooLarge)
+        );
+        // 120 is 2.40 (not 3.0)
+        assert_eq!(Oid::new(Cow::Borrowed(&[120])).first_arcs(), Some((2, 40)));
+        assert_eq!(Oid::new(Cow::Borrowed(&[])).first_arcs(), None);
+        // second arc too large for u64
+        let oid = Oid::new(Cow::Borrowed(&[0xff; 11][..]));
+        assert_eq!(oid.first_arcs(), None);
+        assert_eq!(Oid::new_relative(Cow::Borrowed(&[1, 2])).first_arcs(), None);
+    }
 }
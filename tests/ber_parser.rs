@@ -2,8 +2,9 @@ use der_parser::ber::*;
 use der_parser::error::*;
 use der_parser::oid::*;
 use hex_literal::hex;
-use nom::Err;
+use nom::{Err, Needed};
 use pretty_assertions::assert_eq;
+use std::borrow::Cow;
 
 #[test]
 fn test_ber_bool() {
@@ -15,11 +16,23 @@ fn test_ber_bool() {
         parse_ber_bool(&[0x01, 0x01, 0xff]),
         Ok((empty, b_true.clone()))
     );
-    assert_eq!(parse_ber_bool(&[0x01, 0x01, 0x7f]), Ok((empty, b_true)));
+    assert_eq!(
+        parse_ber_bool(&[0x01, 0x01, 0x7f]),
+        Ok((empty, b_true.clone()))
+    );
+    assert_eq!(parse_ber_bool(&[0x01, 0x01, 0x01]), Ok((empty, b_true)));
     assert_eq!(
         parse_ber_bool(&[0x01, 0x02, 0x12, 0x34]),
         Err(Err::Error(BerError::InvalidLength))
     );
+    assert_eq!(
+        parse_ber_bool(&[0x01, 0x00]),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(
+        parse_ber_bool(&[0x21, 0x01, 0xff]),
+        Err(Err::Error(BerError::ConstructUnexpected))
+    );
 }
 
 #[test]
@@ -31,7 +44,7 @@ fn test_seq_indefinite_length() {
         Ok((
             &data[9..],
             BerObject::from_seq(vec![BerObject::from_obj(BerObjectContent::OctetString(
-                &data[4..=6]
+                Cow::Borrowed(&data[4..=6])
             )),])
         ))
     );
@@ -41,12 +54,113 @@ fn test_seq_indefinite_length() {
         Ok((
             &data[9..],
             BerObject::from_seq(vec![BerObject::from_obj(BerObjectContent::OctetString(
-                &data[4..=6]
+                Cow::Borrowed(&data[4..=6])
             )),])
         ))
     );
 }
 
+#[test]
+fn test_seq_indefinite_length_nested() {
+    // SEQUENCE { INTEGER 1, INTEGER 2 }, end-of-content is not a child
+    let data = hex!("30 80 02 01 01 02 01 02 00 00");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert!(obj.header.is_indefinite());
+    assert_eq!(obj.len(), 2);
+    assert_eq!(obj[0].as_u32(), Ok(1));
+    assert_eq!(obj[1].as_u32(), Ok(2));
+    // nested indefinite length objects, each with its own end-of-content
+    let data = hex!("30 80 30 80 02 01 01 00 00 24 80 04 01 41 04 01 42 00 00 00 00");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    assert_eq!(obj[0].len(), 1);
+    assert_eq!(obj[0][0].as_u32(), Ok(1));
+    assert_eq!(obj[1].as_cow_slice(), Ok(Cow::Borrowed(&b"AB"[..])));
+    // explicit tagged value with indefinite length
+    let data = hex!("a0 80 02 01 05 00 00");
+    let (rem, obj) =
+        parse_ber_tagged_explicit(0, parse_ber_integer)(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    let (_, _, inner) = obj.as_tagged().expect("not tagged");
+    assert_eq!(inner.as_u32(), Ok(5));
+    // unknown objects: end-of-content is not part of the content
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(
+        obj.content,
+        BerObjectContent::Unknown(BerTag(0), Cow::Borrowed(&data[2..5]))
+    );
+    // missing end-of-content
+    let data = hex!("30 80 02 01 01 02 01 02");
+    assert!(parse_ber(&data).is_err());
+}
+
+#[test]
+fn test_ber_end_of_content() {
+    // an empty context-specific [0] is not an end-of-content marker
+    let data = hex!("30 80 80 00 02 01 05 00 00");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    assert_eq!(obj[0].header.class, BerClass::ContextSpecific);
+    assert_eq!(
+        obj[0].content,
+        BerObjectContent::Unknown(BerTag(0), Cow::Borrowed(&[][..]))
+    );
+    assert_eq!(obj[1].as_u32(), Ok(5));
+    // neither is tag 0 in the high tag number form
+    let data = hex!("30 80 1f 00 00 02 01 05 00 00");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    assert_eq!(obj[0].header.raw_tag, Some(Cow::Borrowed(&data[2..4])));
+    assert_eq!(obj[1].as_u32(), Ok(5));
+    // same when skipping the content of unknown objects
+    let data = hex!("a1 80 80 00 02 01 05 00 00 ff");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert_eq!(rem, &[0xff]);
+    assert_eq!(
+        obj.content,
+        BerObjectContent::Unknown(BerTag(1), Cow::Borrowed(&data[2..7]))
+    );
+    let data = hex!("a1 80 1f 00 00 00 00 ff");
+    let (rem, obj) = parse_ber(&data).expect("parsing failed");
+    assert_eq!(rem, &[0xff]);
+    assert_eq!(
+        obj.content,
+        BerObjectContent::Unknown(BerTag(1), Cow::Borrowed(&data[2..5]))
+    );
+    // the segments of a constructed OCTET STRING must be OCTET STRING objects
+    let data = hex!("24 80 04 01 61 80 00 04 01 62 00 00");
+    assert_eq!(parse_ber(&data), Err(Err::Error(BerError::InvalidTag)));
+    assert_eq!(
+        parse_ber_endofcontent(&hex!("80 00")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    assert_eq!(
+        parse_ber_endofcontent(&hex!("1f 00 00")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+}
+
+#[test]
+fn test_ber_iter() {
+    let bytes = hex!("30 0a 02 03 01 00 01 31 03 02 01 02");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(obj.len(), 2);
+    let ints: Vec<_> = obj.iter().map(|o| o.as_u32()).collect();
+    assert_eq!(ints, vec![Ok(65537), Err(BerError::BerTypeError)]);
+    let set = obj.iter().nth(1).expect("missing set");
+    assert_eq!(set.len(), 1);
+    assert_eq!(set.iter().next().map(|o| o.as_u32()), Some(Ok(2)));
+    // primitive objects have no children
+    let int = obj.iter().next().expect("missing int");
+    assert_eq!(int.iter().count(), 0);
+    assert!(int.is_empty());
+}
+
 #[test]
 fn test_ber_set_of() {
     let empty = &b""[..];
@@ -87,6 +201,26 @@ fn test_ber_set_of_v() {
     assert_eq!(parser(&[0x31, 0x00]), Ok((empty, vec![])));
 }
 
+#[test]
+fn test_ber_sequence_of_typed() {
+    fn parser(i: &[u8]) -> BerResult<Vec<u32>> {
+        parse_ber_sequence_of_v(parse_ber_u32)(i)
+    }
+    let empty = &b""[..];
+    let bytes = hex!("30 08 02 01 01 02 03 01 00 00");
+    assert_eq!(parser(&bytes), Ok((empty, vec![1, 0x10000])));
+    // indefinite length
+    let bytes = hex!("30 80 02 01 01 02 03 01 00 00 00 00");
+    assert_eq!(parser(&bytes), Ok((empty, vec![1, 0x10000])));
+    // element of a different type
+    let bytes = hex!("30 06 02 01 01 01 01 ff");
+    assert!(parser(&bytes).is_err());
+    // SET OF
+    let bytes = hex!("31 06 02 01 01 02 01 02");
+    let (_, v) = parse_ber_set_of_v(parse_ber_u32)(&bytes).expect("parsing failed");
+    assert_eq!(v, vec![1, 2]);
+}
+
 #[test]
 fn test_set_indefinite_length() {
     let data = hex!("31 80 04 03 56 78 90 00 00");
@@ -96,7 +230,7 @@ fn test_set_indefinite_length() {
         Ok((
             &data[9..],
             BerObject::from_set(vec![BerObject::from_obj(BerObjectContent::OctetString(
-                &data[4..=6]
+                Cow::Borrowed(&data[4..=6])
             )),])
         ))
     );
@@ -106,27 +240,85 @@ fn test_set_indefinite_length() {
         Ok((
             &data[9..],
             BerObject::from_set(vec![BerObject::from_obj(BerObjectContent::OctetString(
-                &data[4..=6]
+                Cow::Borrowed(&data[4..=6])
             )),])
         ))
     );
+    let (_, hdr) = ber_read_element_header(&data).expect("parsing failed");
+    assert!(hdr.is_indefinite());
+    assert_eq!(hdr.len, BerSize::Indefinite);
+    let (_, hdr) = ber_read_element_header(&data[2..]).expect("parsing failed");
+    assert!(!hdr.is_indefinite());
 }
 
 #[test]
 fn test_ber_int() {
     let empty = &b""[..];
     let bytes = [0x02, 0x03, 0x01, 0x00, 0x01];
-    let expected = BerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+    let expected = BerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
     assert_eq!(parse_ber_integer(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_ber_int_signed() {
+    let check = |bytes: &[u8], expected: i64| {
+        let obj = BerObject::from_int_slice(bytes);
+        assert_eq!(obj.as_i64(), Ok(expected));
+        assert_eq!(obj.as_i128(), Ok(i128::from(expected)));
+    };
+    check(&hex!("ff"), -1);
+    check(&hex!("80"), -128);
+    check(&hex!("7f"), 127);
+    check(&hex!("00 80"), 128);
+    check(&hex!("ff 7f"), -129);
+    check(&hex!("80 00"), -32768);
+    check(&hex!("fe dc ba 98"), -0x0123_4568);
+    check(&hex!("80 00 00 00 00 00 00 00"), std::i64::MIN);
+    check(&hex!("7f ff ff ff ff ff ff ff"), std::i64::MAX);
+    // non-minimal encoding (BER)
+    check(&hex!("ff ff ff ff ff ff ff ff ff ff"), -1);
+    check(&hex!("00 00 00 00 00 00 00 00 00 01"), 1);
+    // too large
+    let obj = BerObject::from_int_slice(&hex!("00 80 00 00 00 00 00 00 00"));
+    assert_eq!(obj.as_i64(), Err(BerError::IntegerTooLarge));
+    assert_eq!(obj.as_i128(), Ok(1 << 63));
+    let obj =
+        BerObject::from_int_slice(&hex!("ff 7f ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff"));
+    assert_eq!(obj.as_i128(), Err(BerError::IntegerTooLarge));
+    let obj = BerObject::from_int_slice(&hex!("80 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00"));
+    assert_eq!(obj.as_i128(), Ok(std::i128::MIN));
+}
+
+#[test]
+fn test_ber_int_tryfrom() {
+    use std::convert::{TryFrom, TryInto};
+    let (_, obj) = parse_ber_integer(&hex!("02 02 01 00")).expect("parsing failed");
+    assert_eq!(u64::try_from(&obj), Ok(256));
+    assert_eq!(u32::try_from(&obj), Ok(256));
+    assert_eq!(u16::try_from(&obj), Ok(256));
+    assert_eq!(u8::try_from(&obj), Err(BerError::IntegerTooLarge));
+    assert_eq!(i64::try_from(&obj), Ok(256));
+    let v: Result<i32, _> = (&obj).try_into();
+    assert_eq!(v, Ok(256));
+    // negative values
+    let (_, obj) = parse_ber_integer(&hex!("02 02 fe ff")).expect("parsing failed");
+    assert_eq!(i64::try_from(&obj), Ok(-257));
+    assert_eq!(i32::try_from(&obj), Ok(-257));
+    let (_, obj) = parse_ber_integer(&hex!("02 05 ff 7f ff ff ff")).expect("parsing failed");
+    assert_eq!(i64::try_from(&obj), Ok(-(1 << 31) - 1));
+    assert_eq!(i32::try_from(&obj), Err(BerError::IntegerTooLarge));
+    // wrong type
+    let (_, obj) = parse_ber_null(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(u8::try_from(&obj), Err(BerError::BerTypeError));
+    assert_eq!(i32::try_from(&obj), Err(BerError::BerTypeError));
+}
+
 #[test]
 fn test_ber_bitstring_primitive() {
     let empty = &b""[..];
     let bytes = &[0x03, 0x07, 0x04, 0x0a, 0x3b, 0x5f, 0x29, 0x1c, 0xd0];
     let expected = BerObject::from_obj(BerObjectContent::BitString(
-        4,
-        BitStringObject { data: &bytes[3..] },
+        BitStringObject::new(4, &bytes[3..]).unwrap(),
     ));
     assert_eq!(parse_ber_bitstring(bytes), Ok((empty, expected)));
     //
@@ -134,8 +326,7 @@ fn test_ber_bitstring_primitive() {
     //
     let bytes = &[0x03, 0x04, 0x06, 0x6e, 0x5d, 0xe0];
     let expected = BerObject::from_obj(BerObjectContent::BitString(
-        6,
-        BitStringObject { data: &bytes[3..] },
+        BitStringObject::new(6, &bytes[3..]).unwrap(),
     ));
     assert_eq!(parse_ber_bitstring(bytes), Ok((empty, expected)));
     //
@@ -143,8 +334,7 @@ fn test_ber_bitstring_primitive() {
     //
     let bytes = &[0x03, 0x81, 0x04, 0x06, 0x6e, 0x5d, 0xc0];
     let expected = BerObject::from_obj(BerObjectContent::BitString(
-        6,
-        BitStringObject { data: &bytes[4..] },
+        BitStringObject::new(6, &bytes[4..]).unwrap(),
     ));
     assert_eq!(parse_ber_bitstring(bytes), Ok((empty, expected)));
 }
@@ -155,25 +345,108 @@ fn test_ber_bitstring_constructed() {
         0x23, 0x80, 0x03, 0x03, 0x00, 0x0a, 0x3b, 0x03, 0x05, 0x04, 0x5f, 0x29, 0x1c, 0xd0, 0x00,
         0x00,
     ];
+    let (rem, obj) = parse_ber_bitstring(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(
+        obj.content,
+        BerObjectContent::BitStringConstructed(4, vec![0x0a, 0x3b, 0x5f, 0x29, 0x1c, 0xd0])
+    );
+    let b = obj.as_bitstring().expect("not a bitstring");
+    assert_eq!(&b.data[..], &[0x0a, 0x3b, 0x5f, 0x29, 0x1c, 0xd0]);
+    assert!(b.is_set(4));
+    //
+    // only the last segment can have unused bits
+    //
+    let bytes = hex!("23 08 03 02 04 a0 03 02 00 3b");
     assert_eq!(
-        parse_ber_bitstring(bytes),
-        Err(Err::Error(BerError::Unsupported))
-    ); // XXX valid encoding
+        parse_ber_bitstring(&bytes),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    //
+    // segments must be BIT STRING
+    //
+    let bytes = hex!("23 04 04 02 00 0a");
+    assert_eq!(
+        parse_ber_bitstring(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    //
+    // DER requires the primitive form
+    //
+    let bytes = hex!("23 04 03 02 00 0a");
+    assert_eq!(
+        der_parser::der::parse_der_bitstring(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
 }
 
 #[test]
 fn test_ber_octetstring_primitive() {
     let empty = &b""[..];
     let bytes = [0x04, 0x05, 0x41, 0x41, 0x41, 0x41, 0x41];
-    let expected = BerObject::from_obj(BerObjectContent::OctetString(b"AAAAA"));
+    let expected = BerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(b"AAAAA")));
     assert_eq!(parse_ber_octetstring(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_ber_octetstring_constructed() {
+    let empty = &b""[..];
+    // definite length, nested constructed segment
+    let bytes = hex!("24 0c 04 02 41 41 24 06 04 01 42 04 01 43");
+    let (rem, obj) = parse_ber_octetstring(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert!(obj.header.is_constructed());
+    assert_eq!(obj.as_slice(), Err(BerError::BerTypeError));
+    assert_eq!(obj.as_cow_slice(), Ok(Cow::Borrowed(&b"AABC"[..])));
+    // indefinite length
+    let bytes = hex!("24 80 04 02 41 41 04 01 42 00 00");
+    let (rem, obj) = parse_ber_octetstring(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(
+        obj.content,
+        BerObjectContent::OctetStringConstructed(b"AAB".to_vec())
+    );
+    // segments must be OCTET STRING
+    let bytes = hex!("24 05 04 01 41 02 00");
+    assert_eq!(
+        parse_ber_octetstring(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    // recursion limit (complete input, so the error is not Incomplete)
+    let mut bytes = vec![];
+    for _ in 0..100 {
+        bytes.extend_from_slice(&[0x24, 0x80]);
+    }
+    bytes.extend_from_slice(&[0x04, 0x00]);
+    for _ in 0..100 {
+        bytes.extend_from_slice(&[0x00, 0x00]);
+    }
+    assert_eq!(
+        parse_ber_octetstring(&bytes),
+        Err(Err::Error(BerError::BerMaxDepth))
+    );
+    // DER requires the primitive form
+    let bytes = hex!("24 04 04 02 41 41");
+    assert_eq!(
+        der_parser::der::parse_der_octetstring(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+}
+
 #[test]
 fn test_ber_null() {
     let empty = &b""[..];
     let expected = BerObject::from_obj(BerObjectContent::Null);
     assert_eq!(parse_ber_null(&[0x05, 0x00]), Ok((empty, expected)));
+    let (_, obj) = parse_ber_null(&[0x05, 0x00]).expect("parsing failed");
+    assert!(obj.is_null());
+    // content must be empty
+    assert_eq!(
+        parse_ber_null(&[0x05, 0x01, 0x00]),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    let (_, obj) = parse_ber_bool(&[0x01, 0x01, 0x00]).expect("parsing failed");
+    assert!(!obj.is_null());
 }
 
 #[test]
@@ -188,11 +461,151 @@ fn test_ber_oid() {
     assert_eq!(parse_ber_oid(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_ber_oid_first_arcs() {
+    let (_, obj) = parse_ber_oid(&hex!("06 03 88 37 03")).expect("parsing failed");
+    assert_eq!(obj.as_oid().map(|oid| oid.first_arcs()), Ok(Some((2, 999))));
+    let (_, obj) = parse_ber_oid(&hex!("06 02 78 01")).expect("parsing failed");
+    assert_eq!(obj.as_oid().map(|oid| oid.first_arcs()), Ok(Some((2, 40))));
+    // padded first sub-identifier
+    assert_eq!(
+        parse_ber_oid(&hex!("06 03 80 2a 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    // padded sub-identifier after the first one
+    assert_eq!(
+        parse_ber_oid(&hex!("06 03 2a 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_oid(&hex!("06 04 2a 01 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    // 0x80 in the middle of a sub-identifier is not padding
+    let (_, obj) = parse_ber_oid(&hex!("06 04 2a 81 80 01")).expect("parsing failed");
+    assert_eq!(
+        obj.as_oid().map(|oid| oid.to_id_string()),
+        Ok("1.2.16385".to_string())
+    );
+}
+
+#[test]
+fn test_ber_real() {
+    let empty = &b""[..];
+    let expected = BerObject::from_obj(BerObjectContent::Real(0.0));
+    assert_eq!(parse_ber_real(&hex!("09 00")), Ok((empty, expected)));
+    // binary encoding, base 2, negative exponent: 5 * 2^-5
+    let (rem, obj) = parse_ber_real(&hex!("09 03 80 fb 05")).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.as_f64(), Ok(0.15625));
+    // base 8: 1 * 8^-2
+    let (_, obj) = parse_ber_real(&hex!("09 03 90 fe 01")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(0.015625));
+    // base 16: 3 * 16^1
+    let (_, obj) = parse_ber_real(&hex!("09 03 a0 01 03")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(48.0));
+    // negative, scaling factor 1: -(3 * 2^1 * 2^1)
+    let (_, obj) = parse_ber_real(&hex!("09 03 c4 01 03")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(-12.0));
+    // two octets exponent: 1 * 2^256
+    let (_, obj) = parse_ber_real(&hex!("09 04 81 01 00 01")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(2f64.powi(256)));
+    // exponent length in the next octet: 1 * 2^-1
+    let (_, obj) = parse_ber_real(&hex!("09 04 83 01 ff 01")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(0.5));
+    // decimal encodings (NR1, NR2, NR3)
+    let (_, obj) = parse_ber_real(&hex!("09 04 01 20 31 32")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(12.0));
+    let (_, obj) = parse_ber_real(&hex!("09 05 02 2d 31 2c 35")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(-1.5));
+    let (_, obj) = parse_ber_real(&hex!("09 07 03 31 32 2e 35 45 31")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(125.0));
+    // special values
+    let (_, obj) = parse_ber_real(&hex!("09 01 40")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(std::f64::INFINITY));
+    let (_, obj) = parse_ber_real(&hex!("09 01 41")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(std::f64::NEG_INFINITY));
+    let (_, obj) = parse_ber_real(&hex!("09 01 42")).expect("parsing failed");
+    assert!(obj.as_f64().unwrap().is_nan());
+    // invalid encodings
+    assert_eq!(
+        parse_ber_real(&hex!("09 02 40 00")),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(
+        parse_ber_real(&hex!("09 03 b0 01 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_real(&hex!("09 02 80 01")),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(
+        parse_ber_real(&hex!("09 04 01 31 2e 35")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_real(&hex!("09 04 03 69 6e 66")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_ber_as_f64() {
+    assert_eq!(
+        BerObject::from_int_slice(b"\x01\x00\x01").as_f64(),
+        Ok(65537.0)
+    );
+    assert_eq!(BerObject::from_int_slice(b"\xff").as_f64(), Ok(-1.0));
+    assert_eq!(
+        BerObject::from_int_slice(b"\x01\x00\x00\x00\x00\x00\x00\x01").as_f64(),
+        Err(BerError::IntegerTooLarge)
+    );
+    // large values are accepted if they can be represented exactly: 2^60, -2^60 and 2^100
+    assert_eq!(
+        BerObject::from_int_slice(b"\x10\x00\x00\x00\x00\x00\x00\x00").as_f64(),
+        Ok(2f64.powi(60))
+    );
+    assert_eq!(
+        BerObject::from_int_slice(b"\xf0\x00\x00\x00\x00\x00\x00\x00").as_f64(),
+        Ok(-(2f64.powi(60)))
+    );
+    let mut bytes = vec![0x10];
+    bytes.extend_from_slice(&[0; 12]);
+    assert_eq!(
+        BerObject::from_int_slice(&bytes).as_f64(),
+        Ok(2f64.powi(100))
+    );
+    assert_eq!(
+        BerObject::from_obj(BerObjectContent::Null).as_f64(),
+        Err(BerError::BerTypeError)
+    );
+}
+
 #[test]
 fn test_ber_enum() {
     let empty = &b""[..];
     let expected = BerObject::from_obj(BerObjectContent::Enum(2));
     assert_eq!(parse_ber_enum(&[0x0a, 0x01, 0x02]), Ok((empty, expected)));
+    // ENUMERATED is encoded as a signed integer
+    let (_, obj) = parse_ber_enum(&[0x0a, 0x01, 0xff]).expect("parsing failed");
+    assert_eq!(obj.as_i64(), Ok(-1));
+    assert_eq!(obj.as_i128(), Ok(-1));
+    // negative values cannot be read as unsigned
+    assert_eq!(obj.as_u64(), Err(BerError::BerValueError));
+    assert_eq!(obj.as_u32(), Err(BerError::BerValueError));
+    // non-minimal encoding is accepted in BER
+    let (_, obj) = parse_ber_enum(&[0x0a, 0x02, 0x00, 0x05]).expect("parsing failed");
+    assert_eq!(obj.as_u32(), Ok(5));
+    assert_eq!(
+        parse_ber_enum(&[0x0a, 0x00]),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // ENUMERATED is not an INTEGER
+    assert_eq!(
+        parse_ber_integer(&[0x0a, 0x01, 0x02]),
+        Err(Err::Error(BerError::InvalidTag))
+    );
 }
 
 #[test]
@@ -201,10 +614,52 @@ fn test_ber_utf8string() {
     let bytes = [
         0x0c, 0x0a, 0x53, 0x6f, 0x6d, 0x65, 0x2d, 0x53, 0x74, 0x61, 0x74, 0x65,
     ];
-    let expected = BerObject::from_obj(BerObjectContent::UTF8String("Some-State"));
+    let expected = BerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed("Some-State")));
     assert_eq!(parse_ber_utf8string(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_ber_ia5string_visiblestring() {
+    let (_, obj) = parse_ber_ia5string(b"\x16\x0buser@ex.com").expect("parsing failed");
+    assert_eq!(obj.as_str(), Ok("user@ex.com"));
+    assert_eq!(
+        parse_ber_ia5string(b"\x16\x03\xc3\xa9a"),
+        Err(Err::Error(BerError::StringInvalidCharset))
+    );
+    let (_, obj) = parse_ber_visiblestring(b"\x1a\x03a b").expect("parsing failed");
+    assert_eq!(obj.as_str(), Ok("a b"));
+    assert_eq!(
+        parse_ber_visiblestring(b"\x1a\x03a\tb"),
+        Err(Err::Error(BerError::StringInvalidCharset))
+    );
+    // manually built objects are checked by the accessor
+    let obj = BerObject::from_obj(BerObjectContent::IA5String(Cow::Borrowed("\u{e9}")));
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+    let obj = BerObject::from_obj(BerObjectContent::VisibleString(Cow::Borrowed("a\nb")));
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+}
+
+#[test]
+fn test_ber_numericstring_printablestring() {
+    let (_, obj) = parse_ber_numericstring(b"\x12\x04 123").expect("parsing failed");
+    assert_eq!(obj.as_str(), Ok(" 123"));
+    assert_eq!(
+        parse_ber_numericstring(b"\x12\x0412.3"),
+        Err(Err::Error(BerError::StringInvalidCharset))
+    );
+    let (_, obj) = parse_ber_printablestring(b"\x13\x05Ab1:?").expect("parsing failed");
+    assert_eq!(obj.as_str(), Ok("Ab1:?"));
+    assert_eq!(
+        parse_ber_printablestring(b"\x13\x03a@b"),
+        Err(Err::Error(BerError::StringInvalidCharset))
+    );
+    // manually built objects are checked by the accessor
+    let obj = BerObject::from_obj(BerObjectContent::NumericString(Cow::Borrowed("12a")));
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+    let obj = BerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed("a@b")));
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+}
+
 #[test]
 fn test_ber_relativeoid() {
     let empty = &b""[..];
@@ -213,16 +668,301 @@ fn test_ber_relativeoid() {
         Oid::from_relative(&[8571, 3, 2]).unwrap(),
     ));
     assert_eq!(parse_ber_relative_oid(&bytes), Ok((empty, expected)));
+    // X.690 8.20.5: arcs are not combined
+    let (_, obj) = parse_ber_relative_oid(&bytes).expect("parsing failed");
+    let oid = obj.as_oid().expect("not an oid");
+    assert!(oid.relative);
+    assert_eq!(oid.to_id_string(), "8571.3.2");
+    assert_eq!(
+        oid.iter().map(|i| i.collect::<Vec<_>>()),
+        Some(vec![8571, 3, 2])
+    );
+    // padded sub-identifiers
+    assert_eq!(
+        parse_ber_relative_oid(&hex!("0d 02 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_relative_oid(&hex!("0d 03 01 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
 }
 
 #[test]
 fn test_ber_bmpstring() {
     let empty = &b""[..];
     let bytes = hex!("1e 08 00 55 00 73 00 65 00 72");
-    let expected = BerObject::from_obj(BerObjectContent::BmpString(b"\x00U\x00s\x00e\x00r"));
+    let expected = BerObject::from_obj(BerObjectContent::BmpString(Cow::Borrowed(
+        b"\x00U\x00s\x00e\x00r",
+    )));
     assert_eq!(parse_ber_bmpstring(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_ber_as_bmpstring() {
+    let bytes = hex!("1e 0a 00 48 00 65 00 6c 00 6c 00 6f");
+    let (_, obj) = parse_ber_bmpstring(&bytes).expect("parsing failed");
+    assert_eq!(obj.as_bmpstring(), Ok("Hello".to_string()));
+    // non-ASCII, and surrogate pair
+    let bytes = hex!("1e 08 00 e9 20 ac d8 3d de 00");
+    let (_, obj) = parse_ber_bmpstring(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_bmpstring(),
+        Ok("\u{e9}\u{20ac}\u{1f600}".to_string())
+    );
+    // odd length
+    let bytes = hex!("1e 03 00 48 00");
+    let (_, obj) = parse_ber_bmpstring(&bytes).expect("parsing failed");
+    assert_eq!(obj.as_bmpstring(), Err(BerError::StringInvalidCharset));
+    // unpaired surrogate
+    let bytes = hex!("1e 04 d8 3d 00 48");
+    let (_, obj) = parse_ber_bmpstring(&bytes).expect("parsing failed");
+    assert_eq!(obj.as_bmpstring(), Err(BerError::StringInvalidCharset));
+    let bytes = hex!("1e 02 de 00");
+    let (_, obj) = parse_ber_bmpstring(&bytes).expect("parsing failed");
+    assert_eq!(obj.as_bmpstring(), Err(BerError::StringInvalidCharset));
+}
+
+#[test]
+fn test_ber_utctime() {
+    let bytes = b"\x17\x0d991231235959Z";
+    let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    let t = obj.as_utctime().expect("invalid time");
+    assert_eq!(
+        t,
+        UtcTime {
+            year: 1999,
+            month: 12,
+            day: 31,
+            hour: 23,
+            minute: 59,
+            second: Some(59),
+            tz: ASN1TimeZone::Z,
+        }
+    );
+    // no seconds, with offset
+    let bytes = b"\x17\x0f4902281200-0130";
+    let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    let t = obj.as_utctime().expect("invalid time");
+    assert_eq!(t.year, 2049);
+    assert_eq!(t.second, None);
+    assert_eq!(t.tz, ASN1TimeZone::Offset(-1, -30));
+    // year window
+    let bytes = b"\x17\x0b5001010000Z";
+    let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    assert_eq!(obj.as_utctime().map(|t| t.year), Ok(1950));
+    // leap year
+    let bytes = b"\x17\x0b0002290000Z";
+    let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    assert!(obj.as_utctime().is_ok());
+    // invalid values
+    let invalid: &[&[u8]] = &[
+        b"\x17\x0b0102290000Z",     // Feb 29 (not a leap year)
+        b"\x17\x0b0113010000Z",     // month 13
+        b"\x17\x0b0101012400Z",     // hour 24
+        b"\x17\x0d010101000060Z",   // second 60
+        b"\x17\x0c0101010000Zx",    // trailing garbage
+        b"\x17\x0b0101010000+",     // truncated offset
+        b"\x17\x0f0101010000+2400", // invalid offset
+        b"\x17\x0a0101010000",      // missing timezone
+    ];
+    for bytes in invalid {
+        let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+        assert_eq!(obj.as_utctime(), Err(BerError::InvalidTime));
+    }
+    // wrong type
+    let (_, obj) = parse_ber_null(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(obj.as_utctime(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_ber_generalizedtime() {
+    let bytes = b"\x18\x1a20201231235959.1234567890Z";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_generalizedtime().expect("invalid time");
+    assert_eq!(
+        t,
+        GeneralizedTime {
+            year: 2020,
+            month: 12,
+            day: 31,
+            hour: 23,
+            minute: Some(59),
+            second: Some(59),
+            fraction: 1_234_567_890,
+            fraction_digits: 10,
+            tz: ASN1TimeZone::Z,
+        }
+    );
+    // BER allows ',' as decimal separator, trailing zeros, offsets and local time
+    let bytes = b"\x18\x1720200101120000,500+0130";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_generalizedtime().expect("invalid time");
+    assert_eq!((t.fraction, t.fraction_digits), (500, 3));
+    assert_eq!(t.tz, ASN1TimeZone::Offset(1, 30));
+    let bytes = b"\x18\x0d2020010112-05";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_generalizedtime().expect("invalid time");
+    assert_eq!((t.minute, t.second), (None, None));
+    assert_eq!(t.tz, ASN1TimeZone::Offset(-5, 0));
+    let bytes = b"\x18\x0e20200101120000";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_generalizedtime().map(|t| t.tz),
+        Ok(ASN1TimeZone::Undefined)
+    );
+    // invalid values
+    let invalid: &[&[u8]] = &[
+        b"\x18\x0f21000229000000Z",                      // not a leap year
+        b"\x18\x1020200101000000.Z",                     // empty fraction
+        b"\x18\x1020200101000000Zx",                     // trailing garbage
+        b"\x18\x0d2020010100000",                        // truncated
+        b"\x18\x2420200101000000.12345678901234567890Z", // fraction too long
+    ];
+    for bytes in invalid {
+        let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+        assert_eq!(obj.as_generalizedtime(), Err(BerError::InvalidTime));
+    }
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_ber_as_chrono_utc() {
+    use chrono::NaiveDate;
+    let ymd_hms_nano = |y, mo, d, h, mi, s, n| {
+        NaiveDate::from_ymd_opt(y, mo, d)
+            .and_then(|d| d.and_hms_nano_opt(h, mi, s, n))
+            .unwrap()
+    };
+    // offset crossing a day (and year) boundary
+    let bytes = b"\x17\x0f0001010030+0100";
+    let (_, obj) = parse_ber_utctime(bytes).expect("parsing failed");
+    let t = obj.as_chrono_utc().expect("conversion failed");
+    assert_eq!(t.naive_utc(), ymd_hms_nano(1999, 12, 31, 23, 30, 0, 0));
+    // fraction of seconds, truncated to nanoseconds
+    let bytes = b"\x18\x1a20201231235959.1234567891Z";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_chrono_utc().expect("conversion failed");
+    assert_eq!(
+        t.naive_utc(),
+        ymd_hms_nano(2020, 12, 31, 23, 59, 59, 123_456_789)
+    );
+    // fraction of hours
+    let bytes = b"\x18\x0d2020010112.5Z";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_chrono_utc().expect("conversion failed");
+    assert_eq!(t.naive_utc(), ymd_hms_nano(2020, 1, 1, 12, 30, 0, 0));
+    // local time cannot be converted
+    let bytes = b"\x18\x0e20200101120000";
+    let (_, obj) = parse_ber_generalizedtime(bytes).expect("parsing failed");
+    assert_eq!(obj.as_chrono_utc(), Err(BerError::InvalidTime));
+    // not a time object
+    let (_, obj) = parse_ber_null(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(obj.as_chrono_utc(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_ber_universalstring() {
+    let empty = &b""[..];
+    let bytes = hex!("1c 08 00 00 00 48 00 01 f6 00");
+    let expected = BerObject::from_obj(BerObjectContent::UniversalString(Cow::Borrowed(
+        &bytes[2..],
+    )));
+    assert_eq!(
+        parse_ber_universalstring(&bytes),
+        Ok((empty, expected.clone()))
+    );
+    assert_eq!(expected.as_universalstring(), Ok("H\u{1f600}".to_string()));
+    // length not a multiple of 4
+    let bytes = hex!("1c 03 00 00 48");
+    let (_, obj) = parse_ber_universalstring(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_universalstring(),
+        Err(BerError::StringInvalidCharset)
+    );
+    // code point above U+10FFFF
+    let bytes = hex!("1c 04 00 11 00 00");
+    let (_, obj) = parse_ber_universalstring(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_universalstring(),
+        Err(BerError::StringInvalidCharset)
+    );
+    // surrogates are not valid code points
+    let bytes = hex!("1c 04 00 00 d8 00");
+    let (_, obj) = parse_ber_universalstring(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_universalstring(),
+        Err(BerError::StringInvalidCharset)
+    );
+}
+
+#[test]
+fn test_ber_parser_options() {
+    // nested sequences (depth 60), containing NULL
+    let mut bytes = vec![0x05, 0x00];
+    for _ in 0..60 {
+        let mut v = vec![0x30, bytes.len() as u8];
+        v.extend_from_slice(&bytes);
+        bytes = v;
+    }
+    // default limit (MAX_RECURSION)
+    assert_eq!(BerParserOptions::default().max_depth, MAX_RECURSION);
+    assert_eq!(
+        parse_ber_with_options(&bytes, &BerParserOptions::default()),
+        Err(Err::Error(BerError::BerMaxDepth))
+    );
+    assert_eq!(parse_ber(&bytes), Err(Err::Error(BerError::BerMaxDepth)));
+    // raised limit
+    let options = BerParserOptions::default().with_max_depth(61);
+    let (rem, _) = parse_ber_with_options(&bytes, &options).expect("parsing failed");
+    assert!(rem.is_empty());
+    let (_, _) = der_parser::der::parse_der_with_options(&bytes, &options).expect("parsing failed");
+    // lowered limit
+    let options = BerParserOptions::default().with_max_depth(60);
+    assert_eq!(
+        parse_ber_with_options(&bytes, &options),
+        Err(Err::Error(BerError::BerMaxDepth))
+    );
+    // the limit also applies when skipping the content of unknown objects with indefinite length
+    let bytes = hex!("a1 80 a1 80 a1 80 a1 80 00 00 00 00 00 00 00 00");
+    let options = BerParserOptions::default().with_max_depth(3);
+    assert_eq!(
+        parse_ber_with_options(&bytes, &options),
+        Err(Err::Error(BerError::BerMaxDepth))
+    );
+    let options = BerParserOptions::default().with_max_depth(4);
+    let (rem, obj) = parse_ber_with_options(&bytes, &options).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.header.tag, BerTag(1));
+}
+
+#[test]
+fn test_ber_max_size() {
+    assert_eq!(BerParserOptions::default().max_size, MAX_OBJECT_SIZE);
+    let options = BerParserOptions::default().with_max_size(16);
+    // header declaring a 4 GB length on a small input
+    let bytes = hex!("04 84 ff ff ff ff 00 01 02 03");
+    assert_eq!(
+        parse_ber_with_options(&bytes, &options),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(
+        der_parser::der::parse_der_with_options(&bytes, &options),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // indefinite length object, larger than the limit
+    let bytes = hex!("30 80 04 10 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 00 00");
+    assert_eq!(
+        parse_ber_with_options(&bytes, &options),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // the object fits exactly
+    let bytes = hex!("30 0e 04 0c 00 01 02 03 04 05 06 07 08 09 0a 0b 05 00");
+    let (rem, obj) = parse_ber_with_options(&bytes, &options).expect("parsing failed");
+    assert_eq!(rem, &[0x05, 0x00]);
+    assert_eq!(obj.len(), 1);
+}
+
 #[test]
 fn test_ber_customtags() {
     let bytes = hex!("8f 02 12 34");
@@ -231,12 +971,828 @@ fn test_ber_customtags() {
         .1;
     // println!("{:?}", hdr);
     let expected: &[u8] = &[0x8f];
-    assert_eq!(hdr.raw_tag, Some(expected));
+    assert_eq!(hdr.raw_tag, Some(Cow::Borrowed(expected)));
     let bytes = hex!("9f 0f 02 12 34");
     let hdr = ber_read_element_header(&bytes)
         .expect("ber_read_element_header")
         .1;
     // println!("{:?}", hdr);
     let expected: &[u8] = &[0x9f, 0x0f];
-    assert_eq!(hdr.raw_tag, Some(expected));
+    assert_eq!(hdr.raw_tag, Some(Cow::Borrowed(expected)));
+}
+
+#[test]
+fn test_ber_error_offset() {
+    // valid object
+    let bytes = hex!("30 06 02 01 01 02 01 02");
+    let (rem, _) = parse_ber_with_offset(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    // invalid null in a nested sequence (indefinite length)
+    let bytes = hex!("30 80 02 01 01 30 80 02 01 02 05 01 00 00 00 00 00");
+    let res = parse_ber_with_offset(&bytes);
+    assert_eq!(
+        res,
+        Err(Err::Error(BerErrorWithOffset {
+            error: BerError::InvalidLength,
+            offset: 10
+        }))
+    );
+    // invalid header of the root object
+    let bytes = hex!("1f ff ff ff ff 7f 00");
+    match parse_ber_with_offset(&bytes) {
+        Err(Err::Error(e)) => assert_eq!(e.offset, 0),
+        e => panic!("unexpected result {:?}", e),
+    }
+}
+
+#[test]
+fn test_ber_optional_with_tag() {
+    // SEQUENCE { a BOOLEAN OPTIONAL, b INTEGER }
+    fn parse_struct(i: &[u8]) -> BerResult<(Option<BerObject>, u32)> {
+        parse_ber_sequence_defined_g(|_, content| {
+            let (rem, a) = parse_ber_optional_with_tag(
+                BerClassTag::universal(BerTag::Boolean),
+                parse_ber_bool,
+            )(content)?;
+            let (rem, b) = parse_ber_u32(rem)?;
+            Ok((rem, (a, b)))
+        })(i)
+    }
+    let (_, (a, b)) = parse_struct(&hex!("30 06 01 01 ff 02 01 02")).expect("parsing failed");
+    assert_eq!(a, Some(BerObject::from_bool(true)));
+    assert_eq!(b, 2);
+    let (_, (a, b)) = parse_struct(&hex!("30 03 02 01 02")).expect("parsing failed");
+    assert_eq!(a, None);
+    assert_eq!(b, 2);
+    // end of input
+    let (rem, res) = parse_ber_optional_with_tag(
+        BerClassTag::universal(BerTag::Integer),
+        parse_ber_integer,
+    )(&[])
+    .expect("parsing failed");
+    assert!(rem.is_empty());
+    assert!(res.is_none());
+    // errors are not hidden if the tag matches
+    let res = parse_ber_optional_with_tag(BerClassTag::universal(BerTag::Boolean), parse_ber_bool)(
+        &hex!("01 02 ff ff"),
+    );
+    assert_eq!(res, Err(Err::Error(BerError::InvalidLength)));
+    // [1] BOOLEAN OPTIONAL: a universal BOOLEAN (tag number 1) does not match
+    let bytes = hex!("01 01 ff");
+    let res = parse_ber_optional_with_tag(BerClassTag::context(1), parse_ber_bool)(&bytes);
+    assert_eq!(res, Ok((&bytes[..], None)));
+}
+
+#[test]
+fn test_ber_default() {
+    let parser = parse_ber_default(BerClassTag::universal(BerTag::Integer), parse_ber_u32, 0);
+    // present
+    let (rem, v) = parser(&hex!("02 01 03 05 00")).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    assert_eq!(v, 3);
+    // present and equal to the default is accepted in BER
+    let (_, v) = parser(&hex!("02 01 00")).expect("parsing failed");
+    assert_eq!(v, 0);
+    // absent
+    let (rem, v) = parser(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    assert_eq!(v, 0);
+    // [2] INTEGER DEFAULT 0: a universal INTEGER (tag number 2) does not match
+    let bytes = hex!("02 01 03");
+    let parser = parse_ber_default(BerClassTag::context(2), parse_ber_u32, 0);
+    let (rem, v) = parser(&bytes).expect("parsing failed");
+    assert_eq!(rem, &bytes[..]);
+    assert_eq!(v, 0);
+}
+
+#[test]
+fn test_peek_ber_header() {
+    let bytes = &hex!("a0 03 02 01 01");
+    let (rem, hdr) = peek_ber_header(bytes).expect("parsing failed");
+    assert_eq!(rem, bytes);
+    assert_eq!(hdr.class, BerClass::ContextSpecific);
+    assert!(hdr.is_constructed());
+    assert_eq!(hdr.tag, BerTag(0));
+    assert_eq!(hdr.len, BerSize::Definite(3));
+    assert_eq!(peek_ber_header(&[]), Err(Err::Incomplete(Needed::Size(1))));
+}
+
+#[test]
+fn test_ber_choice() {
+    // Time ::= CHOICE { utcTime UTCTime, generalTime GeneralizedTime }
+    let choices: &[(BerClassTag, &dyn Fn(&[u8]) -> BerResult)] = &[
+        (BerClassTag::universal(BerTag::UtcTime), &parse_ber_utctime),
+        (
+            BerClassTag::universal(BerTag::GeneralizedTime),
+            &parse_ber_generalizedtime,
+        ),
+    ];
+    let parser = parse_ber_choice(choices);
+    let bytes = &hex!("17 0d 30 32 31 32 31 33 31 34 32 39 32 33 5a 05 00");
+    let (rem, obj) = parser(bytes).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    assert_eq!(obj.header.tag, BerTag::UtcTime);
+    let res = parser(rem);
+    assert_eq!(res, Err(Err::Error(BerError::UnexpectedTag(BerTag::Null))));
+    assert_eq!(parser(&[]), Err(Err::Incomplete(Needed::Size(1))));
+    // Value ::= CHOICE { t [2] EXPLICIT BOOLEAN, i INTEGER }: the class is compared
+    fn parse_t(i: &[u8]) -> BerResult {
+        parse_ber_tagged_explicit(2, parse_ber_bool)(i)
+    }
+    let choices: &[(BerClassTag, &dyn Fn(&[u8]) -> BerResult)] = &[
+        (BerClassTag::context(2), &parse_t),
+        (BerClassTag::universal(BerTag::Integer), &parse_ber_integer),
+    ];
+    let parser = parse_ber_choice(choices);
+    let (_, obj) = parser(&hex!("02 01 05")).expect("parsing failed");
+    assert_eq!(obj.as_u32(), Ok(5));
+    let (_, obj) = parser(&hex!("a2 03 01 01 ff")).expect("parsing failed");
+    assert!(obj.header.is_contextspecific());
+}
+
+#[test]
+fn test_ber_raw_encoding() {
+    let bytes = hex!("30 08 02 01 05 04 03 41 42 43 ff");
+    let (rem, obj) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(rem, &hex!("ff"));
+    assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+    assert_eq!(obj.content_bytes(), Some(&bytes[2..10]));
+    assert_eq!(obj[1].raw_header(), Some(&hex!("04 03")[..]));
+    assert_eq!(obj[1].content_bytes(), Some(&b"ABC"[..]));
+    // indefinite length: content includes the end-of-content octets
+    let bytes = hex!("30 80 02 01 05 00 00");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+    assert_eq!(obj.content_bytes(), Some(&bytes[2..]));
+    // objects which were not parsed
+    assert_eq!(BerObject::from_int_slice(b"\x05").content_bytes(), None);
+}
+
+#[test]
+fn test_ber_content_with_len() {
+    // reinterpret the bytes of an OCTET STRING as the content of a SEQUENCE
+    let bytes = hex!("04 03 01 01 ff");
+    let (_, obj) = parse_ber_octetstring(&bytes).expect("parsing failed");
+    let data = obj.as_slice().expect("not an octetstring");
+    let (rem, content) =
+        parse_ber_content_with_len(BerTag::Sequence)(data, 3).expect("parsing failed");
+    assert!(rem.is_empty());
+    let seq = content.as_sequence().expect("not a sequence");
+    assert_eq!(seq[0].as_bool(), Ok(true));
+    // only the first `len` bytes are read
+    let (rem, content) =
+        parse_ber_content_with_len(BerTag::Integer)(&hex!("01 00 01"), 2).expect("parsing failed");
+    assert_eq!(rem, &hex!("01"));
+    assert_eq!(content.as_u32(), Ok(0x100));
+    let res = parse_ber_content_with_len(BerTag::Integer)(&hex!("01"), 2);
+    assert_eq!(res, Err(Err::Incomplete(Needed::Size(1))));
+}
+
+#[test]
+fn test_ber_complete() {
+    let bytes = hex!("30 80 02 01 01 02 01 02 00 00");
+    let (rem, obj) = parse_ber_complete(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    // stray end-of-content after a definite length object
+    let bytes = hex!("30 03 02 01 01 00 00");
+    assert_eq!(
+        parse_ber_complete(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+}
+
+#[test]
+fn test_seq_children_length() {
+    // SEQUENCE declares length 10, but its single child only uses 8 bytes
+    let bytes = hex!("30 0a 04 06 01 02 03 04 05 06 01 02");
+    assert_eq!(
+        parse_ber_sequence(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // content padded with end-of-content bytes
+    let bytes = hex!("30 0a 04 06 01 02 03 04 05 06 00 00");
+    assert_eq!(
+        parse_ber_sequence(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // invalid child
+    let bytes = hex!("31 03 01 02 ff");
+    assert!(parse_ber_set(&bytes).is_err());
+    // children exactly fill the parent
+    let bytes = hex!("30 0a 04 06 01 02 03 04 05 06 05 00");
+    let (rem, obj) = parse_ber_sequence(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    // context-specific tag 0 is not an end-of-content marker
+    let bytes = hex!("30 07 a0 03 02 01 02 80 00");
+    let (rem, obj) = parse_ber_sequence(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+}
+
+#[test]
+fn test_ber_value_eq() {
+    // BER: non-minimal INTEGER, constructed OCTET STRING, indefinite length, unordered SET
+    let ber = hex!("30 80 02 02 00 80 24 80 04 01 61 04 01 62 00 00 31 06 01 01 ff 02 01 05 00 00");
+    let der = hex!("30 10 02 02 00 80 04 02 61 62 31 06 02 01 05 01 01 ff");
+    let (_, a) = parse_ber(&ber).expect("parsing failed");
+    let (_, b) = parse_ber(&der).expect("parsing failed");
+    assert_ne!(a, b);
+    assert!(a.value_eq(&b));
+    assert!(b.value_eq(&a));
+    // non-minimal negative INTEGER
+    let (_, a) = parse_ber_integer(&hex!("02 02 ff 80")).expect("parsing failed");
+    let (_, b) = parse_ber_integer(&hex!("02 01 80")).expect("parsing failed");
+    assert!(a.value_eq(&b));
+    // different values
+    let (_, a) = parse_ber_integer(&hex!("02 02 00 80")).expect("parsing failed");
+    assert!(!a.value_eq(&b));
+    // BIT STRING: value of the unused bits is ignored
+    let (_, a) = parse_ber_bitstring(&hex!("03 02 04 f0")).expect("parsing failed");
+    let (_, b) = parse_ber_bitstring(&hex!("03 02 04 ff")).expect("parsing failed");
+    assert!(a.value_eq(&b));
+    let (_, b) = parse_ber_bitstring(&hex!("03 02 03 f0")).expect("parsing failed");
+    assert!(!a.value_eq(&b));
+}
+
+#[test]
+fn test_ber_lenient() {
+    // valid input: same as parse_ber
+    let bytes = hex!("30 80 02 01 01 31 03 01 01 ff 00 00");
+    let res = parse_ber_lenient(&bytes);
+    assert_eq!(res.error, None);
+    assert_eq!(
+        res.object,
+        Some(parse_ber(&bytes).expect("parsing failed").1)
+    );
+    // nested error: SEQUENCE { INTEGER 1, SET { BOOLEAN TRUE, <invalid BOOLEAN> } }
+    let bytes = hex!("30 0c 02 01 01 31 07 01 01 ff 01 02 00 00");
+    let res = parse_ber_lenient(&bytes);
+    let obj = res.object.expect("no object");
+    assert_eq!(obj.len(), 2);
+    assert_eq!(obj[1].len(), 1);
+    assert_eq!(obj[1][0].as_bool(), Ok(true));
+    assert_eq!(res.error, Some((10, BerError::InvalidLength)));
+    // truncated content
+    let bytes = hex!("30 0a 02 01 01");
+    let res = parse_ber_lenient(&bytes);
+    assert_eq!(res.object.expect("no object").len(), 1);
+    assert_eq!(res.error, Some((0, BerError::ObjectTooShort)));
+    // missing end-of-content
+    let bytes = hex!("30 80 02 01 01");
+    let res = parse_ber_lenient(&bytes);
+    assert_eq!(res.object.expect("no object").len(), 1);
+    assert_eq!(res.error, Some((0, BerError::ObjectTooShort)));
+    // invalid first object
+    let res = parse_ber_lenient(&hex!("01 02 00 00"));
+    assert_eq!(res.object, None);
+    assert_eq!(res.error, Some((0, BerError::InvalidLength)));
+}
+
+#[test]
+fn test_ber_content_len() {
+    let bytes = hex!("04 14 00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10 11 12 13");
+    let (_, obj) = parse_ber_octetstring(&bytes).expect("parsing failed");
+    assert_eq!(obj.content_len(), 20);
+    // constructed object: length of the content, not the number of children
+    let (_, obj) = parse_ber_sequence(&hex!("30 06 02 01 01 02 01 02")).expect("parsing failed");
+    assert_eq!(obj.content_len(), 6);
+    assert_eq!(obj.len(), 2);
+    // indefinite length
+    let bytes = hex!("24 80 04 02 01 02 04 01 03 00 00");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(obj.content_len(), 7);
+}
+
+#[test]
+fn test_ber_raw_integer() {
+    let (_, obj) = parse_ber_integer(&hex!("02 02 00 ff")).expect("parsing failed");
+    assert_eq!(obj.as_raw_integer(), Ok(&[0xff][..]));
+    // non-minimal encoding (BER)
+    let (_, obj) = parse_ber_integer(&hex!("02 03 00 00 7f")).expect("parsing failed");
+    assert_eq!(obj.as_raw_integer(), Ok(&[0x7f][..]));
+    // zero
+    let (_, obj) = parse_ber_integer(&hex!("02 02 00 00")).expect("parsing failed");
+    assert_eq!(obj.as_raw_integer(), Ok(&[0x00][..]));
+    // negative value: sign is lost
+    let (_, obj) = parse_ber_integer(&hex!("02 01 ff")).expect("parsing failed");
+    assert_eq!(obj.as_raw_integer(), Ok(&[0xff][..]));
+    let (_, obj) = parse_ber_octetstring(&hex!("04 01 00")).expect("parsing failed");
+    assert_eq!(obj.as_raw_integer(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_ber_time() {
+    let (rem, t) = parse_ber_time(b"\x17\x0d991231235959Z\x05\x00").expect("parsing failed");
+    assert_eq!(rem, &[0x05, 0x00]);
+    let expected = parse_ber_utctime(b"\x17\x0d991231235959Z")
+        .expect("parsing failed")
+        .1
+        .as_utctime()
+        .expect("invalid time");
+    assert_eq!(t, ASN1Time::UtcTime(expected));
+    assert_eq!(t.year(), 1999);
+    let (_, t) = parse_ber_time(b"\x18\x1320201231235959.125Z").expect("parsing failed");
+    match t {
+        ASN1Time::GeneralizedTime(g) => assert_eq!((g.fraction, g.fraction_digits), (125, 3)),
+        _ => panic!("wrong type"),
+    }
+    assert_eq!(t.tz(), ASN1TimeZone::Z);
+    // invalid time value
+    assert_eq!(
+        parse_ber_time(b"\x17\x0d991331235959Z"),
+        Err(Err::Error(BerError::InvalidTime))
+    );
+    // other type
+    assert_eq!(
+        parse_ber_time(&hex!("02 01 00")),
+        Err(Err::Error(BerError::UnexpectedTag(BerTag::Integer)))
+    );
+    // DER
+    let (_, t) =
+        der_parser::der::parse_der_time(b"\x18\x0f20501231235959Z").expect("parsing failed");
+    assert_eq!(t.year(), 2050);
+}
+
+#[cfg(feature = "subtle")]
+#[test]
+fn test_ber_ct_eq_content() {
+    let (_, obj) = parse_ber_octetstring(&hex!("04 03 01 02 03")).expect("parsing failed");
+    assert!(obj.ct_eq_content(&[1, 2, 3]));
+    assert!(!obj.ct_eq_content(&[1, 2, 0]));
+    assert!(!obj.ct_eq_content(&[1, 2]));
+    // constructed OCTET STRING
+    let (_, obj) = parse_ber(&hex!("24 80 04 02 01 02 04 01 03 00 00")).expect("parsing failed");
+    assert!(obj.ct_eq_content(&[1, 2, 3]));
+    // not a slice
+    let (_, obj) = parse_ber_bool(&hex!("01 01 ff")).expect("parsing failed");
+    assert!(!obj.ct_eq_content(&[0xff]));
+}
+
+#[test]
+fn test_ber_errors_not_nom() {
+    // errors of the content parsers are returned, not the nom error kinds
+    assert_eq!(
+        parse_ber_utf8string(&hex!("0c 02 ff fe")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_oid(&hex!("06 02 2b 85")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_utctime(&hex!("17 02 31 0a")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+    assert_eq!(
+        parse_ber_u32(&hex!("02 05 01 00 00 00 00")),
+        Err(Err::Error(BerError::IntegerTooLarge))
+    );
+    assert_eq!(
+        parse_ber_u64(&hex!("04 01 00")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    // errors of elements of indefinite length objects
+    assert_eq!(
+        parse_ber(&hex!("30 80 01 02 00 00 00 00")),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+}
+
+#[test]
+fn test_ber_oid_str() {
+    let (_, obj) = parse_ber(&hex!("06 09 2a 86 48 86 f7 0d 01 01 05")).expect("parsing failed");
+    assert_eq!(obj.as_oid_str(), Ok("1.2.840.113549.1.1.5".to_string()));
+    let (_, obj) = parse_ber(&hex!("0d 02 81 00")).expect("parsing failed");
+    assert_eq!(obj.as_oid_str(), Ok("128".to_string()));
+    let (_, obj) = parse_ber(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(obj.as_oid_str(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_ber_any_defined_by() {
+    fn parse_int(i: &[u8]) -> BerResult<Option<u32>> {
+        let (i, v) = parse_ber_u32(i)?;
+        Ok((i, Some(v)))
+    }
+    fn parse_absent(i: &[u8]) -> BerResult<Option<u32>> {
+        Ok((i, None))
+    }
+    let oid1 = Oid::from(&[1, 2, 3]).unwrap();
+    let oid2 = Oid::from(&[1, 2, 4]).unwrap();
+    let table: &[(Oid, fn(&[u8]) -> BerResult<Option<u32>>)] =
+        &[(oid1.clone(), parse_int), (oid2.clone(), parse_absent)];
+    let parser = parse_ber_any_defined_by(table);
+    let (rem, (oid, value)) = parser(&hex!("06 02 2a 03 02 01 05 05 00")).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    assert_eq!(oid, oid1);
+    assert_eq!(value, Some(5));
+    let (rem, (oid, value)) = parser(&hex!("06 02 2a 04 02 01 05")).expect("parsing failed");
+    assert_eq!(rem, &hex!("02 01 05"));
+    assert_eq!(oid, oid2);
+    assert_eq!(value, None);
+    // parser error
+    assert_eq!(
+        parser(&hex!("06 02 2a 03 05 00")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    // unknown OID
+    assert_eq!(
+        parser(&hex!("06 02 2a 05 02 01 05")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_ber_utf8_str() {
+    let bytes = hex!("0c 03 c3 a9 74");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    let s = obj.as_utf8_str().expect("not an UTF8String");
+    assert_eq!(s, "\u{e9}t");
+    // the str borrows the input
+    assert_eq!(s.as_ptr(), bytes[2..].as_ptr());
+    // invalid UTF-8 is rejected when parsing
+    assert!(parse_ber(&hex!("0c 02 c3 28")).is_err());
+    let (_, obj) = parse_ber(&hex!("13 02 61 62")).expect("parsing failed");
+    assert_eq!(obj.as_utf8_str(), Err(BerError::BerTypeError));
+    // only decoded strings are copied
+    assert_eq!(obj.as_cow_str(), Ok(Cow::Borrowed("ab")));
+    let (_, obj) = parse_ber(&hex!("1e 04 00 e9 00 74")).expect("parsing failed");
+    assert_eq!(
+        obj.as_cow_str(),
+        Ok(Cow::Owned::<str>("\u{e9}t".to_string()))
+    );
+    let (_, obj) = parse_ber(&hex!("14 02 e9 74")).expect("parsing failed");
+    assert_eq!(
+        obj.as_cow_str().map(|s| s.into_owned()),
+        Ok("\u{e9}t".to_string())
+    );
+    let (_, obj) = parse_ber(&hex!("04 01 61")).expect("parsing failed");
+    assert_eq!(obj.as_cow_str(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_ber_iter_end_of_content() {
+    let bytes = hex!("30 80 02 01 01 30 80 00 00 00 00 05 00");
+    let (rem, iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    let v = iter
+        .with_end_of_content(true)
+        .map(|r| r.map(|(_, obj)| obj))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("parsing failed");
+    assert_eq!(v.len(), 3);
+    // the EOC of the nested sequence is consumed by the recursive parser
+    assert_eq!(v[1].as_sequence().map(|v| v.len()), Ok(0));
+    assert_eq!(v[2].content, BerObjectContent::EndOfContent);
+    assert_eq!(v[2].raw_header(), Some(&hex!("00 00")[..]));
+    // consumed silently by default
+    let (_, iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    assert_eq!(iter.count(), 2);
+    // definite length
+    let (_, iter) = parse_ber_sequence_iter(&hex!("30 03 02 01 01")).expect("parsing failed");
+    assert_eq!(iter.with_end_of_content(true).count(), 1);
+}
+
+#[test]
+fn test_ber_any() {
+    // high tag number, content is not parsed
+    let bytes = hex!("bf 81 01 03 ff ff ff 05 00");
+    let (rem, (hdr, content)) = parse_ber_any(&bytes).expect("parsing failed");
+    assert_eq!(hdr.class, BerClass::ContextSpecific);
+    assert_eq!(hdr.tag, BerTag(0x81));
+    assert!(hdr.is_constructed());
+    assert_eq!(content, &hex!("ff ff ff"));
+    assert_eq!(rem, &hex!("05 00"));
+    // indefinite length, with nested indefinite length object
+    let bytes = hex!("30 80 30 80 02 01 01 00 00 00 00");
+    let (rem, (hdr, content)) = parse_ber_any(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert!(hdr.is_indefinite());
+    assert_eq!(content, &hex!("30 80 02 01 01 00 00"));
+    // the content stops at the end-of-content marker
+    let bytes = hex!("30 80 02 01 05 1f 00 00 00 00 ff");
+    let (rem, (_, content)) = parse_ber_any(&bytes).expect("parsing failed");
+    assert_eq!(content, &hex!("02 01 05 1f 00 00"));
+    assert_eq!(rem, &[0xff]);
+    let parser = parse_ber_container(|hdr, i| -> BerResult<_> { Ok((&i[i.len()..], (hdr, i))) });
+    let (rem, (hdr, content)) = parser(&bytes).expect("parsing failed");
+    assert_eq!(content, &hex!("02 01 05 1f 00 00"));
+    assert_eq!(hdr.raw_content, Some(Cow::Borrowed(&bytes[2..10])));
+    assert_eq!(rem, &[0xff]);
+    // incomplete content
+    assert_eq!(
+        parse_ber_any(&hex!("04 04 01 02")),
+        Err(Err::Incomplete(Needed::Size(2)))
+    );
+}
+
+#[test]
+fn test_ber_oid_max_arcs() {
+    // 1.2 followed by 126 arcs: MAX_OID_ARCS
+    let mut bytes = vec![0x06, 0x7f, 0x2a];
+    bytes.extend_from_slice(&[0x01; 126]);
+    let (_, obj) = parse_ber_oid(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_oid().map(|oid| oid.iter().unwrap().count()),
+        Ok(MAX_OID_ARCS)
+    );
+    assert!(parse_ber_oid_with_max_arcs(&bytes, MAX_OID_ARCS).is_ok());
+    assert_eq!(
+        parse_ber_oid_with_max_arcs(&bytes, 127),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // one more arc, using a multi-byte subidentifier
+    let mut bytes = vec![0x06, 0x81, 0x81, 0x2a];
+    bytes.extend_from_slice(&[0x01; 126]);
+    bytes.extend_from_slice(&[0x81, 0x00]);
+    assert_eq!(
+        parse_ber_oid(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(parse_ber(&bytes), Err(Err::Error(BerError::InvalidLength)));
+    assert_eq!(
+        der_parser::der::parse_der_oid(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // relative OID
+    let mut bytes = vec![0x0d, 0x81, 0x81];
+    bytes.extend_from_slice(&[0x01; 129]);
+    assert_eq!(
+        parse_ber_relative_oid(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+}
+
+#[test]
+fn test_skip_ber() {
+    let bytes = hex!("02 01 01 24 80 04 01 aa 24 80 00 00 00 00 9f 81 01 00 05 00");
+    let (rem, ()) = skip_ber(&bytes).expect("parsing failed");
+    assert_eq!(rem, &bytes[3..]);
+    // indefinite length, with nested indefinite length object
+    let (rem, ()) = skip_ber(rem).expect("parsing failed");
+    assert_eq!(rem, &bytes[14..]);
+    // high tag number
+    let (rem, ()) = skip_ber(rem).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    // an empty context-specific [0] is not an end-of-content marker
+    let bytes = hex!("a0 80 80 00 02 01 05 00 00 ff");
+    let (rem, ()) = skip_ber(&bytes).expect("parsing failed");
+    assert_eq!(rem, &[0xff]);
+    // incomplete
+    assert_eq!(
+        skip_ber(&hex!("30 80 02 01 01")),
+        Err(Err::Incomplete(Needed::Size(1)))
+    );
+    // nested indefinite length objects are limited to MAX_RECURSION levels
+    let bytes: Vec<u8> = [0x30, 0x80]
+        .iter()
+        .cycle()
+        .take(2 * (MAX_RECURSION + 1))
+        .cloned()
+        .collect();
+    assert_eq!(skip_ber(&bytes), Err(Err::Error(BerError::BerMaxDepth)));
+}
+
+#[test]
+fn test_ber_external() {
+    // indirect-reference, data-value-descriptor and single-ASN1-type
+    let bytes = hex!("28 0b 02 01 01 07 01 64 a0 03 02 01 05");
+    let (rem, obj) = parse_ber_external(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    let external = obj.as_external().expect("not an EXTERNAL");
+    assert_eq!(external.direct_reference, None);
+    assert_eq!(external.indirect_reference, Some(Cow::Borrowed(&[1][..])));
+    assert_eq!(
+        external.data_value_descriptor,
+        Some(Cow::Borrowed(&b"d"[..]))
+    );
+    match external.encoding {
+        BerExternalEncoding::SingleAsn1Type(ref obj) => assert_eq!(obj.as_u32(), Ok(5)),
+        _ => panic!("wrong encoding"),
+    }
+    // arbitrary
+    let bytes = hex!("28 05 82 03 04 a0 b0");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    let expected = BitStringObject::new(4, &hex!("a0 b0")).unwrap();
+    assert_eq!(
+        obj.as_external().map(|e| &e.encoding),
+        Ok(&BerExternalEncoding::Arbitrary(expected))
+    );
+    // constructed octet-aligned and arbitrary, accepted only in BER
+    let bytes = hex!("28 0b 06 02 51 01 a1 05 04 03 61 62 63");
+    let (_, obj) = parse_ber_external(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_external().map(|e| &e.encoding),
+        Ok(&BerExternalEncoding::OctetAlignedConstructed(
+            b"abc".to_vec()
+        ))
+    );
+    let bytes = hex!("28 0b 06 02 51 01 a2 05 03 03 04 a0 b0");
+    let (_, obj) = parse_ber_external(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_external().map(|e| &e.encoding),
+        Ok(&BerExternalEncoding::ArbitraryConstructed(
+            4,
+            hex!("a0 b0").to_vec()
+        ))
+    );
+    // indefinite length
+    let bytes = hex!("28 80 81 01 61 00 00");
+    let (rem, obj) = parse_ber_external(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(
+        obj.as_external().map(|e| &e.encoding),
+        Ok(&BerExternalEncoding::OctetAligned(Cow::Borrowed(b"a")))
+    );
+    // errors
+    assert_eq!(
+        parse_ber_external(&hex!("28 03 06 01 2a")),
+        Err(Err::Error(BerError::ObjectTooShort))
+    );
+    assert_eq!(
+        parse_ber_external(&hex!("08 00")),
+        Err(Err::Error(BerError::ConstructExpected))
+    );
+    assert_eq!(
+        parse_ber_external(&hex!("28 02 83 00")),
+        Err(Err::Error(BerError::UnexpectedTag(BerTag(3))))
+    );
+    assert_eq!(
+        parse_ber_external(&hex!("28 08 06 02 51 01 a1 02 05 00")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    assert_eq!(
+        parse_ber_external(&hex!("28 04 81 00 81 00")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_ber_embedded_pdv() {
+    let bytes = hex!("2b 0e a0 0a a0 08 80 02 51 01 81 02 51 01 82 00");
+    let (rem, obj) = parse_ber_embedded_pdv(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    let oid = Oid::from(&[2, 1, 1]).unwrap();
+    let expected = BerEmbeddedPdv {
+        identification: BerPdvIdentification::Syntaxes(oid.clone(), oid.clone()),
+        data_value: Cow::Borrowed(b""),
+    };
+    assert_eq!(obj.as_embedded_pdv(), Ok(&expected));
+    let bytes = hex!("2b 0e a0 09 a3 07 80 01 01 81 02 51 01 82 01 ff");
+    let (_, obj) = parse_ber(&bytes).expect("parsing failed");
+    let expected = BerEmbeddedPdv {
+        identification: BerPdvIdentification::ContextNegotiation(Cow::Borrowed(&[1]), oid),
+        data_value: Cow::Borrowed(&[0xff]),
+    };
+    assert_eq!(obj.as_embedded_pdv(), Ok(&expected));
+    let bytes = hex!("2b 06 a0 02 85 00 82 00");
+    let (_, obj) = parse_ber_embedded_pdv(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_embedded_pdv().map(|p| &p.identification),
+        Ok(&BerPdvIdentification::Fixed)
+    );
+    // errors
+    assert_eq!(
+        parse_ber_embedded_pdv(&hex!("2b 04 a0 02 85 00")),
+        Err(Err::Error(BerError::ObjectTooShort))
+    );
+    assert_eq!(
+        parse_ber_embedded_pdv(&hex!("2b 06 a0 02 86 00 82 00")),
+        Err(Err::Error(BerError::UnexpectedTag(BerTag(6))))
+    );
+    assert_eq!(
+        parse_ber_embedded_pdv(&hex!("2b 07 a0 03 85 01 00 82 00")),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert_eq!(
+        parse_ber_embedded_pdv(&hex!("2b 06 82 00 a0 02 85 00")),
+        Err(Err::Error(BerError::UnexpectedTag(BerTag(2))))
+    );
+    // data-value-descriptor is always absent
+    assert_eq!(
+        parse_ber_embedded_pdv(&hex!("2b 08 a0 02 85 00 81 00 82 00")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_ber_within() {
+    // SEQUENCE with a child that fits in the limit
+    let bytes = hex!("30 03 02 01 01 05 00");
+    let (rem, obj) = parse_ber_within(5, parse_ber_sequence)(&bytes).expect("parsing failed");
+    assert_eq!(rem, &[0x05, 0x00]);
+    assert_eq!(obj.len(), 1);
+    // the declared length exceeds the limit
+    assert_eq!(
+        parse_ber_within(4, parse_ber_sequence)(&bytes),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    // the input is smaller than the limit
+    let (rem, _) = parse_ber_within(100, parse_ber_sequence)(&bytes).expect("parsing failed");
+    assert_eq!(rem, &[0x05, 0x00]);
+}
+
+#[test]
+fn test_ber_legacy_strings() {
+    let (_, obj) = parse_ber_t61string(b"\x14\x04Test").expect("parsing failed");
+    assert_eq!(
+        obj.content,
+        BerObjectContent::T61String(Cow::Borrowed(b"Test"))
+    );
+    assert_eq!(obj.as_str(), Ok("Test"));
+    // non-ASCII content: not available as str, but can be decoded as Latin-1
+    let (_, obj) = parse_ber_t61string(b"\x14\x02\xe9t").expect("parsing failed");
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+    assert_eq!(obj.as_t61string(), Ok("\u{e9}t".to_string()));
+    let (_, obj) = parse_ber_graphicstring(b"\x19\x03abc").expect("parsing failed");
+    assert_eq!(
+        obj.content,
+        BerObjectContent::GraphicString(Cow::Borrowed(b"abc"))
+    );
+    assert_eq!(obj.as_str(), Ok("abc"));
+    let (_, obj) = parse_ber_generalstring(b"\x1b\x03abc").expect("parsing failed");
+    assert_eq!(
+        obj.content,
+        BerObjectContent::GeneralString(Cow::Borrowed(b"abc"))
+    );
+    assert_eq!(obj.as_str(), Ok("abc"));
+    assert_eq!(obj.as_t61string(), Err(BerError::BerTypeError));
+    // generic parsing
+    let (_, obj) = parse_ber(b"\x14\x01A").expect("parsing failed");
+    assert_eq!(obj.header.tag, BerTag::T61String);
+    assert_eq!(obj.as_slice(), Ok(&b"A"[..]));
+}
+
+#[test]
+fn test_ber_objectdescriptor() {
+    let bytes = b"\x07\x0bRSA Encrypt";
+    let (rem, obj) = parse_ber_objectdescriptor(bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.header.tag, BerTag::ObjDescriptor);
+    assert_eq!(obj.as_str(), Ok("RSA Encrypt"));
+    let (_, obj) = parse_ber(bytes).expect("parsing failed");
+    assert_eq!(
+        obj.content,
+        BerObjectContent::ObjectDescriptor(Cow::Borrowed(b"RSA Encrypt"))
+    );
+    // control characters are not allowed
+    let (_, obj) = parse_ber_objectdescriptor(b"\x07\x02a\n").expect("parsing failed");
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+    let (_, obj) = parse_ber_graphicstring(b"\x19\x02a\n").expect("parsing failed");
+    assert_eq!(obj.as_str(), Err(BerError::StringInvalidCharset));
+}
+
+// Parse from a temporary copy of the input, which is dropped before the object is used
+fn parse_ber_owned(bytes: &[u8]) -> BerObject<'static> {
+    let data = bytes.to_vec();
+    let (_, obj) = parse_ber(&data).expect("parsing failed");
+    obj.into_owned()
+}
+
+#[test]
+fn test_ber_into_owned() {
+    let bytes = hex!(
+        "30 1f 02 03 01 00 01 0c 03 61 62 63 03 02 07 80 06 03 2a 03 04 a0 03 02 01 05
+        28 05 81 03 61 62 63"
+    );
+    let obj = parse_ber_owned(&bytes);
+    let (_, expected) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(obj, expected);
+    assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+    assert_eq!(obj.content_bytes(), Some(&bytes[2..]));
+    assert_eq!(obj[0].as_u32(), Ok(0x10001));
+    assert_eq!(obj[1].as_str(), Ok("abc"));
+    assert!(obj[2].as_bitstring().expect("not a bitstring").is_set(0));
+    assert_eq!(obj[3].as_oid_str(), Ok("1.2.3.4".to_string()));
+    assert_eq!(obj[4].as_slice(), Ok(&bytes[23..26]));
+    assert_eq!(obj[4].raw_header(), Some(&bytes[21..23]));
+    let external = obj[5].as_external().expect("not an EXTERNAL");
+    assert_eq!(
+        external.encoding,
+        BerExternalEncoding::OctetAligned(Cow::Owned(b"abc".to_vec()))
+    );
+    // the borrowed content is copied
+    match obj[0].content {
+        BerObjectContent::Integer(Cow::Owned(ref v)) => assert_eq!(v, &[0x01, 0x00, 0x01]),
+        _ => panic!("content was not copied"),
+    }
+    match obj[0].header.raw_tag {
+        Some(Cow::Owned(ref v)) => assert_eq!(v, &[0x02]),
+        _ => panic!("raw tag was not copied"),
+    }
+    // EMBEDDED PDV
+    let bytes = hex!("2b 0b a0 04 81 02 51 01 82 03 61 62 63");
+    let obj = parse_ber_owned(&bytes);
+    let (_, expected) = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(obj, expected);
+    let pdv = obj.as_embedded_pdv().expect("not an EMBEDDED PDV");
+    assert_eq!(&pdv.data_value[..], b"abc");
+    // objects which were not parsed
+    let obj = BerObject::from_seq(vec![
+        BerObject::from_octetstring(b"\x01\x02"),
+        BerObject::from_obj(BerObjectContent::Optional(None)),
+    ]);
+    assert_eq!(obj.clone().into_owned(), obj);
 }
@@ -6,6 +6,7 @@ use nom::error::ErrorKind;
 use nom::*;
 use oid::Oid;
 use pretty_assertions::assert_eq;
+use std::borrow::Cow;
 
 #[derive(Debug, PartialEq)]
 struct MyStruct<'a> {
@@ -103,21 +104,25 @@ fn struct02() {
             Rdn {
                 a: Attr {
                     oid: Oid::from(&[2, 5, 4, 6]).unwrap(), // countryName
-                    val: BerObject::from_obj(BerObjectContent::PrintableString("FR")),
+                    val: BerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed(
+                        "FR",
+                    ))),
                 },
             },
             Rdn {
                 a: Attr {
                     oid: Oid::from(&[2, 5, 4, 8]).unwrap(), // stateOrProvinceName
-                    val: BerObject::from_obj(BerObjectContent::UTF8String("Some-State")),
+                    val: BerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed(
+                        "Some-State",
+                    ))),
                 },
             },
             Rdn {
                 a: Attr {
                     oid: Oid::from(&[2, 5, 4, 10]).unwrap(), // organizationName
-                    val: BerObject::from_obj(BerObjectContent::IA5String(
+                    val: BerObject::from_obj(BerObjectContent::IA5String(Cow::Borrowed(
                         "Internet Widgits Pty Ltd",
-                    )),
+                    ))),
                 },
             },
         ],
@@ -263,3 +268,227 @@ fn application() {
     assert!(rem.is_empty());
     assert_eq!(app, SimpleStruct { a: 0x10001 });
 }
+
+#[test]
+fn test_ber_sequence_iter() {
+    let bytes = hex!("30 0a 02 03 01 00 01 02 03 01 00 00 ff");
+    let (rem, iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    assert_eq!(rem, &[0xff]);
+    let v: Vec<_> = iter
+        .map(|r| r.expect("child").1.as_u32().unwrap())
+        .collect();
+    assert_eq!(v, vec![0x10001, 0x10000]);
+    // indefinite length
+    let bytes = hex!("30 80 02 01 01 04 01 41 00 00");
+    let (rem, iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    let v: Vec<_> = iter.map(|r| r.expect("child").1).collect();
+    assert_eq!(
+        v,
+        vec![
+            BerObject::from_int_slice(b"\x01"),
+            BerObject::from_octetstring(b"A")
+        ]
+    );
+    // the iterator stops on the first error
+    let bytes = hex!("30 08 02 01 01 05 01 00 02 00");
+    let (_, mut iter) = parse_ber_sequence_iter(&bytes).expect("parsing failed");
+    assert!(iter.next().unwrap().is_ok());
+    assert_eq!(
+        iter.next().unwrap(),
+        Err(Err::Error(BerError::InvalidLength))
+    );
+    assert!(iter.next().is_none());
+    // not a sequence
+    let bytes = hex!("31 00");
+    assert_eq!(
+        parse_ber_sequence_iter(&bytes).map(|_| ()),
+        Err(Err::Error(BerError::BerTypeError))
+    );
+}
+
+#[test]
+fn tagged_generic() {
+    // [0] EXPLICIT SEQUENCE { a INTEGER, b INTEGER }, into a native type
+    fn parse_explicit(i: &[u8]) -> BerResult<(u32, u32)> {
+        parse_ber_tagged_explicit_g(0, |hdr, content| {
+            assert_eq!(hdr.class, BerClass::ContextSpecific);
+            parse_ber_sequence_defined_g(|_, i| {
+                let (i, a) = parse_ber_u32(i)?;
+                let (i, b) = parse_ber_u32(i)?;
+                Ok((i, (a, b)))
+            })(content)
+        })(i)
+    }
+    let bytes = hex!("a0 08 30 06 02 01 01 02 01 02");
+    let (rem, res) = parse_explicit(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(res, (1, 2));
+    // [1] IMPLICIT SEQUENCE { a INTEGER, b INTEGER }, into a native type
+    fn parse_implicit(i: &[u8]) -> BerResult<(u32, u32)> {
+        parse_ber_tagged_implicit_g(1, |content, hdr, _depth| {
+            assert!(hdr.is_constructed());
+            let (i, a) = parse_ber_u32(content)?;
+            let (i, b) = parse_ber_u32(i)?;
+            Ok((i, (a, b)))
+        })(i)
+    }
+    let bytes = hex!("a1 06 02 01 01 02 01 02");
+    let (rem, res) = parse_implicit(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(res, (1, 2));
+    assert_eq!(
+        parse_implicit(&hex!("a2 06 02 01 01 02 01 02")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+}
+
+#[test]
+fn tagged_implicit_as() {
+    let parser = parse_ber_tagged_implicit_as(0, BerTag::Integer);
+    let (rem, obj) = parser(&hex!("80 02 ff 7f")).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.header.class, BerClass::ContextSpecific);
+    assert_eq!(obj.as_i64(), Ok(-129));
+    // constructed underlying types are parsed recursively
+    let parser = parse_ber_tagged_implicit_as(1, BerTag::Sequence);
+    let (_, obj) = parser(&hex!("a1 03 01 01 ff")).expect("parsing failed");
+    assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(1));
+    assert_eq!(obj[0].as_bool(), Ok(true));
+    assert_eq!(
+        parser(&hex!("a2 03 01 01 ff")),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+}
+
+#[test]
+fn tagged_class_tag() {
+    let bytes = hex!("a3 03 02 01 05");
+    let tag = BerClassTag::context(3);
+    let (_, obj) =
+        parse_ber_tagged_explicit_with_class(tag.class, tag.tag, parse_ber_integer)(&bytes)
+            .expect("parsing failed");
+    assert_eq!(obj.header.class_tag(), tag);
+    // the class is checked
+    let tag = BerClassTag::application(3);
+    assert_eq!(
+        parse_ber_tagged_explicit_with_class(tag.class, tag.tag, parse_ber_integer)(&bytes),
+        Err(Err::Error(BerError::InvalidClass))
+    );
+}
+
+#[test]
+fn tagged_with_class() {
+    // [APPLICATION 3] EXPLICIT INTEGER
+    let bytes = hex!("63 03 02 01 05");
+    let (_, obj) =
+        parse_ber_tagged_explicit_with_class(TagClass::Application, 3, parse_ber_integer)(&bytes)
+            .expect("parsing failed");
+    assert_eq!(obj.header.class_tag(), BerClassTag::application(3));
+    let (class, tag, inner) = obj.as_tagged().expect("not tagged");
+    assert_eq!((class, tag), (TagClass::Application, BerTag(3)));
+    assert_eq!(inner.as_u32(), Ok(5));
+    // other classes are rejected, even if the tag number matches
+    for &class in &[TagClass::ContextSpecific, TagClass::Private] {
+        assert_eq!(
+            parse_ber_tagged_explicit_with_class(class, 3, parse_ber_integer)(&bytes),
+            Err(Err::Error(BerError::InvalidClass))
+        );
+    }
+    assert_eq!(
+        parse_ber_tagged_explicit_with_class(TagClass::Application, 4, parse_ber_integer)(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    // [PRIVATE 1] IMPLICIT SEQUENCE
+    let bytes = hex!("e1 03 02 01 05");
+    let parser = parse_ber_tagged_implicit_with_class(
+        TagClass::Private,
+        1,
+        parse_ber_content(BerTag::Sequence),
+    );
+    let (_, obj) = parser(&bytes).expect("parsing failed");
+    assert_eq!(obj.header.class_tag(), BerClassTag::private(1));
+    assert_eq!(obj.as_sequence().map(|v| v.len()), Ok(1));
+    assert_eq!(
+        parser(&hex!("a1 03 02 01 05")),
+        Err(Err::Error(BerError::InvalidClass))
+    );
+}
+
+#[test]
+fn template_sequence_set() {
+    // Record ::= SEQUENCE {
+    //     version [0] IMPLICIT INTEGER OPTIONAL,
+    //     id      INTEGER,
+    //     attrs   SET { flag BOOLEAN, name UTF8String OPTIONAL } }
+    let template = Asn1Template::sequence(
+        "Record",
+        vec![
+            Asn1Template::primitive("version", BerTag::Integer)
+                .with_tag(BerClass::ContextSpecific, BerTag(0))
+                .optional(),
+            Asn1Template::primitive("id", BerTag::Integer),
+            Asn1Template::set(
+                "attrs",
+                vec![
+                    Asn1Template::primitive("flag", BerTag::Boolean),
+                    Asn1Template::primitive("name", BerTag::Utf8String).optional(),
+                ],
+            ),
+        ],
+    );
+    // version present, SET elements in reverse order
+    let bytes = hex!("30 0e 80 01 02 02 01 05 31 06 0c 01 61 01 01 ff");
+    let (rem, value) = parse_with_template(&template, &bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(value.name, "Record");
+    assert!(value.get("version").is_some());
+    let id = value.get("id").and_then(|m| m.as_object()).expect("no id");
+    assert_eq!(id.as_u32(), Ok(5));
+    let attrs = value.get("attrs").expect("no attrs");
+    let flag = attrs
+        .get("flag")
+        .and_then(|m| m.as_object())
+        .expect("no flag");
+    assert_eq!(flag.as_bool(), Ok(true));
+    assert!(attrs.get("name").is_some());
+    // optional elements absent, indefinite length
+    let bytes = hex!("30 80 02 01 05 31 03 01 01 00 00 00");
+    let (_, value) = parse_with_template(&template, &bytes).expect("parsing failed");
+    assert!(value.get("version").is_none());
+    assert!(value.get("attrs").and_then(|m| m.get("name")).is_none());
+    // empty version, indefinite length
+    let bytes = hex!("30 80 80 00 02 01 05 31 03 01 01 00 00 00 ff");
+    let (rem, value) = parse_with_template(&template, &bytes).expect("parsing failed");
+    assert_eq!(rem, &[0xff]);
+    assert!(value.get("version").is_some());
+    // missing required element in SET
+    let bytes = hex!("30 08 02 01 05 31 03 0c 01 61");
+    assert_eq!(
+        parse_with_template(&template, &bytes),
+        Err(Err::Error(BerError::ObjectTooShort))
+    );
+    // missing required element in SEQUENCE
+    let bytes = hex!("30 03 02 01 05");
+    assert_eq!(
+        parse_with_template(&template, &bytes),
+        Err(Err::Error(BerError::ObjectTooShort))
+    );
+    // unexpected element
+    let bytes = hex!("30 08 02 01 05 04 03 01 01 00");
+    assert_eq!(
+        parse_with_template(&template, &bytes),
+        Err(Err::Error(BerError::UnexpectedTag(BerTag::OctetString)))
+    );
+    // trailing data in the SEQUENCE
+    let bytes = hex!("30 0a 02 01 05 31 00 31 03 01 01 00");
+    assert_eq!(
+        parse_with_template(&template, &bytes),
+        Err(Err::Error(BerError::ObjectTooShort))
+    );
+    let bytes = hex!("30 0a 02 01 05 31 03 01 01 00 05 00");
+    assert_eq!(
+        parse_with_template(&template, &bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+}
@@ -6,9 +6,9 @@ use der_parser::error::*;
 use der_parser::oid::*;
 use der_parser::*;
 use hex_literal::hex;
-use nom::error::ErrorKind;
 use nom::Err;
 use pretty_assertions::assert_eq;
+use std::borrow::Cow;
 
 #[test]
 fn test_der_bool() {
@@ -21,13 +21,21 @@ fn test_der_bool() {
         parse_der_bool(&[0x01, 0x01, 0x7f]),
         Err(Err::Error(BerError::DerConstraintFailed))
     );
+    assert_eq!(
+        parse_der_bool(&[0x01, 0x01, 0x01]),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    assert_eq!(
+        parse_der_bool(&[0x01, 0x02, 0xff, 0xff]),
+        Err(Err::Error(BerError::InvalidLength))
+    );
 }
 
 #[test]
 fn test_der_int() {
     let empty = &b""[..];
     let bytes = hex!("02 03 01 00 01");
-    let expected = DerObject::from_obj(BerObjectContent::Integer(b"\x01\x00\x01"));
+    let expected = DerObject::from_obj(BerObjectContent::Integer(Cow::Borrowed(b"\x01\x00\x01")));
     assert_eq!(parse_der_integer(&bytes), Ok((empty, expected)));
     let res = parse_der_u64(&bytes);
     assert_eq!(res.expect("integer").1, 0x10001);
@@ -43,6 +51,21 @@ fn test_der_int() {
     assert!(res.is_ok());
     let res = parse_der_u64(&bytes);
     assert!(res.is_err());
+    // non-minimal encodings are rejected in DER, but accepted in BER
+    let bytes = hex!("02 01 01");
+    assert!(parse_der_integer(&bytes).is_ok());
+    for bytes in &[&hex!("02 02 00 01")[..], &hex!("02 02 ff 80")] {
+        assert_eq!(
+            parse_der(bytes),
+            Err(Err::Error(BerError::DerConstraintFailed))
+        );
+        assert!(parse_ber(bytes).is_ok());
+    }
+    // leading octet needed for sign
+    let bytes = hex!("02 02 00 80");
+    assert!(parse_der_integer(&bytes).is_ok());
+    let bytes = hex!("02 02 ff 7f");
+    assert!(parse_der_integer(&bytes).is_ok());
 }
 
 #[test]
@@ -53,8 +76,7 @@ fn test_der_bitstring_primitive() {
     //
     let bytes = &[0x03, 0x04, 0x06, 0x6e, 0x5d, 0xc0];
     let expected = DerObject::from_obj(BerObjectContent::BitString(
-        6,
-        BitStringObject { data: &bytes[3..] },
+        BitStringObject::new(6, &bytes[3..]).unwrap(),
     ));
     assert_eq!(parse_der_bitstring(bytes), Ok((empty, expected)));
     //
@@ -91,7 +113,7 @@ fn test_der_bitstring_constructed() {
 fn test_der_octetstring_primitive() {
     let empty = &b""[..];
     let bytes = [0x04, 0x05, 0x41, 0x41, 0x41, 0x41, 0x41];
-    let expected = DerObject::from_obj(BerObjectContent::OctetString(b"AAAAA"));
+    let expected = DerObject::from_obj(BerObjectContent::OctetString(Cow::Borrowed(b"AAAAA")));
     assert_eq!(parse_der_octetstring(&bytes), Ok((empty, expected)));
 }
 
@@ -100,6 +122,13 @@ fn test_der_null() {
     let empty = &b""[..];
     let expected = DerObject::from_obj(BerObjectContent::Null);
     assert_eq!(parse_der_null(&[0x05, 0x00]), Ok((empty, expected)));
+    let (_, obj) = parse_der_null(&[0x05, 0x00]).expect("parsing failed");
+    assert!(obj.is_null());
+    // content must be empty
+    assert_eq!(
+        parse_der_null(&[0x05, 0x01, 0x00]),
+        Err(Err::Error(BerError::InvalidLength))
+    );
 }
 
 #[test]
@@ -112,6 +141,55 @@ fn test_der_oid() {
         Oid::from(&[1, 2, 840, 113_549, 1, 1, 5]).unwrap(),
     ));
     assert_eq!(parse_der_oid(&bytes), Ok((empty, expected)));
+    // padded sub-identifier
+    assert_eq!(
+        parse_der_oid(&hex!("06 03 2a 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_der_real() {
+    let empty = &b""[..];
+    let expected = DerObject::from_obj(BerObjectContent::Real(0.15625));
+    assert_eq!(
+        parse_der_real(&hex!("09 03 80 fb 05")),
+        Ok((empty, expected))
+    );
+    let expected = DerObject::from_obj(BerObjectContent::Real(0.0));
+    assert_eq!(parse_der_real(&hex!("09 00")), Ok((empty, expected)));
+    let (_, obj) = parse_der_real(&hex!("09 07 03 31 32 2e 35 45 31")).expect("parsing failed");
+    assert_eq!(obj.as_f64(), Ok(125.0));
+    // base 8
+    assert_eq!(
+        parse_der_real(&hex!("09 03 90 fe 01")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // scaling factor
+    assert_eq!(
+        parse_der_real(&hex!("09 03 84 01 03")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // even mantissa
+    assert_eq!(
+        parse_der_real(&hex!("09 03 80 fc 0a")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // non-minimal exponent
+    assert_eq!(
+        parse_der_real(&hex!("09 04 81 00 01 01")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // NR1 decimal form
+    assert_eq!(
+        parse_der_real(&hex!("09 03 01 31 32")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // malformed encoding
+    assert_eq!(
+        parse_der_real(&hex!("09 03 b0 01 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
 }
 
 #[test]
@@ -119,6 +197,17 @@ fn test_der_enum() {
     let empty = &b""[..];
     let expected = DerObject::from_obj(BerObjectContent::Enum(2));
     assert_eq!(parse_der_enum(&[0x0a, 0x01, 0x02]), Ok((empty, expected)));
+    let (_, obj) = parse_der_enum(&[0x0a, 0x02, 0xff, 0x7f]).expect("parsing failed");
+    assert_eq!(obj.as_i64(), Ok(-129));
+    // same minimal encoding rules as INTEGER
+    assert_eq!(
+        parse_der_enum(&[0x0a, 0x02, 0x00, 0x05]),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    assert_eq!(
+        parse_der_enum(&[0x0a, 0x02, 0xff, 0x80]),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
 }
 
 #[test]
@@ -127,7 +216,7 @@ fn test_der_utf8string() {
     let bytes = [
         0x0c, 0x0a, 0x53, 0x6f, 0x6d, 0x65, 0x2d, 0x53, 0x74, 0x61, 0x74, 0x65,
     ];
-    let expected = DerObject::from_obj(BerObjectContent::UTF8String("Some-State"));
+    let expected = DerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed("Some-State")));
     assert_eq!(parse_der_utf8string(&bytes), Ok((empty, expected)));
 }
 
@@ -139,6 +228,30 @@ fn test_der_relativeoid() {
         Oid::from_relative(&[8571, 3, 2]).unwrap(),
     ));
     assert_eq!(parse_der_relative_oid(&bytes), Ok((empty, expected)));
+    assert_eq!(
+        parse_der_relative_oid(&hex!("0d 02 80 01")),
+        Err(Err::Error(BerError::BerValueError))
+    );
+}
+
+#[test]
+fn test_der_indefinite_length() {
+    let bytes = hex!("30 80 02 01 01 00 00");
+    assert!(parse_ber(&bytes).is_ok());
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    assert_eq!(
+        parse_der_sequence(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    let bytes = hex!("30 80 02 01 01 02 01 02 00 00");
+    assert_eq!(parse_ber(&bytes).map(|(_, obj)| obj.len()), Ok(2));
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
 }
 
 #[test]
@@ -157,6 +270,62 @@ fn test_der_set() {
     assert_eq!(parse_der_set(&bytes), Ok((empty, expected)));
 }
 
+#[test]
+fn test_parse_with_mode() {
+    // non-minimal length encoding
+    let bytes = hex!("04 81 02 01 02");
+    let (_, obj) = parse_with_mode(Mode::Ber, &bytes).expect("parsing failed");
+    assert_eq!(obj.as_slice(), Ok(&[0x01, 0x02][..]));
+    assert_eq!(parse_with_mode(Mode::Der, &bytes), parse_der(&bytes));
+    assert!(parse_with_mode(Mode::Der, &bytes).is_err());
+    // valid for both
+    let bytes = hex!("30 03 01 01 ff");
+    assert_eq!(parse_with_mode(Mode::Ber, &bytes), parse_ber(&bytes));
+    assert_eq!(parse_with_mode(Mode::Der, &bytes), parse_der(&bytes));
+}
+
+#[test]
+fn test_der_set_of_ordering() {
+    let empty = &b""[..];
+    // elements are sorted by their encodings: 02 01 05 < 02 02 00 80
+    let bytes = hex!("31 07 02 01 05 02 02 00 80");
+    let (rem, obj) = parse_der_set_of(parse_der_integer)(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(obj.as_set().map(|v| v.len()), Ok(2));
+    // duplicate elements are allowed
+    let bytes = hex!("31 06 02 01 05 02 01 05");
+    assert!(parse_der_set_of(parse_der_integer)(&bytes).is_ok());
+    // unsorted elements (accepted in BER)
+    let bytes = hex!("31 07 02 02 00 80 02 01 05");
+    assert_eq!(
+        parse_der_set_of(parse_der_integer)(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    assert!(parse_ber_set_of(parse_ber_integer)(&bytes).is_ok());
+    // indefinite length
+    let bytes = hex!("31 80 02 01 05 00 00");
+    assert_eq!(
+        parse_der_set_of(parse_der_integer)(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // non-minimal length
+    let bytes = hex!("31 81 03 02 01 01");
+    assert_eq!(
+        parse_der_set_of(parse_der_integer)(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    assert!(parse_ber_set_of(parse_ber_integer)(&bytes).is_ok());
+    // not a set
+    let bytes = hex!("30 03 02 01 01");
+    assert_eq!(
+        parse_der_set_of(parse_der_integer)(&bytes),
+        Err(Err::Error(BerError::BerTypeError))
+    );
+    // empty set
+    let (_, obj) = parse_der_set_of(parse_der_integer)(&[0x31, 0x00]).expect("parsing failed");
+    assert!(obj.is_empty());
+}
+
 #[test]
 fn test_der_seq_defined() {
     let empty = &b""[..];
@@ -226,10 +395,7 @@ fn test_der_seq_of_incomplete() {
     assert_eq!(parser2(&bytes), Err(Err::Failure(BerError::InvalidTag)));
     let h = &hex!("30 06 02 03 01 00 01 02");
     // eprintln!("remaining 02 at end (incomplete)");
-    assert_eq!(
-        parser2(h),
-        Err(Err::Error(BerError::NomError(ErrorKind::Eof)))
-    );
+    assert_eq!(parser2(h), Err(Err::Error(BerError::InvalidLength)));
 }
 
 #[test]
@@ -251,9 +417,9 @@ fn test_der_set_of() {
 #[test]
 fn test_der_utctime() {
     let bytes = hex!("17 0D 30 32 31 32 31 33 31 34 32 39 32 33 5A FF");
-    let expected = DerObject::from_obj(BerObjectContent::UTCTime(
+    let expected = DerObject::from_obj(BerObjectContent::UTCTime(Cow::Borrowed(
         std::str::from_utf8(&bytes[2..(2 + 0x0d)]).unwrap(),
-    ));
+    )));
     assert_eq!(parse_der_utctime(&bytes), Ok((&[0xff][..], expected)));
     let bytes = hex!("17 0c 30 32 31 32 31 33 31 34 32 39 32 33");
     parse_der_utctime(&bytes).err().expect("expected error");
@@ -265,17 +431,38 @@ fn test_der_generalizedtime() {
     let bytes = [
         0x18, 0x0D, 0x30, 0x32, 0x31, 0x32, 0x31, 0x33, 0x31, 0x34, 0x32, 0x39, 0x32, 0x33, 0x5A,
     ];
-    let expected = DerObject::from_obj(BerObjectContent::GeneralizedTime(
+    let expected = DerObject::from_obj(BerObjectContent::GeneralizedTime(Cow::Borrowed(
         std::str::from_utf8(&bytes[2..]).unwrap(),
-    ));
+    )));
     assert_eq!(parse_der_generalizedtime(&bytes), Ok((empty, expected)));
+    // fraction
+    let bytes = b"\x18\x1320201231235959.125Z";
+    let (_, obj) = parse_der_generalizedtime(bytes).expect("parsing failed");
+    let t = obj.as_generalizedtime().expect("invalid time");
+    assert_eq!((t.fraction, t.fraction_digits), (125, 3));
+    // DER constraints: 'Z' suffix, '.' separator, no trailing zeros in fraction
+    let invalid: &[&[u8]] = &[
+        b"\x18\x1320201231235959.120Z",
+        b"\x18\x1020201231235959.Z",
+        b"\x18\x1320201231235959,125Z",
+        b"\x18\x0e20201231235959",
+        b"\x18\x1320201231235959+0100",
+    ];
+    for bytes in invalid {
+        assert_eq!(
+            parse_der_generalizedtime(bytes),
+            Err(Err::Error(BerError::DerConstraintFailed))
+        );
+        // BER is lenient
+        assert!(parse_ber_generalizedtime(bytes).is_ok());
+    }
 }
 
 #[test]
 fn test_der_generalstring() {
     let empty = &b""[..];
     let bytes = [0x1b, 0x04, 0x63, 0x69, 0x66, 0x73];
-    let expected = DerObject::from_obj(BerObjectContent::GeneralString(b"cifs"));
+    let expected = DerObject::from_obj(BerObjectContent::GeneralString(Cow::Borrowed(b"cifs")));
     assert_eq!(parse_der_generalstring(&bytes), Ok((empty, expected)));
 }
 
@@ -286,7 +473,7 @@ fn test_der_contextspecific() {
     let expected = DerObject {
         header: BerObjectHeader::new(BerClass::ContextSpecific, 1, BerTag(0), 3)
             .with_raw_tag(Some(&[0xa0])),
-        content: BerObjectContent::Unknown(BerTag(0), &bytes[2..]),
+        content: BerObjectContent::Unknown(BerTag(0), Cow::Borrowed(&bytes[2..])),
     };
     assert_eq!(parse_der(&bytes), Ok((empty, expected)));
 }
@@ -326,7 +513,7 @@ fn test_der_implicit() {
     let expected = DerObject {
         header: BerObjectHeader::new(BerClass::ContextSpecific, 0, BerTag(1), 4)
             .with_raw_tag(Some(&[0x81])),
-        content: BerObjectContent::IA5String("pass"),
+        content: BerObjectContent::IA5String(Cow::Borrowed("pass")),
     };
     fn der_read_ia5string_content<'a>(
         i: &'a [u8],
@@ -352,7 +539,7 @@ fn test_der_implicit_long_tag() {
     let expected = DerObject {
         header: BerObjectHeader::new(BerClass::Application, 0, BerTag(0x52), 4)
             .with_raw_tag(Some(&[0x5f, 0x52])),
-        content: BerObjectContent::IA5String("pass"),
+        content: BerObjectContent::IA5String(Cow::Borrowed("pass")),
     };
     fn der_read_ia5string_content<'a>(
         i: &'a [u8],
@@ -374,9 +561,7 @@ fn test_der_implicit_long_tag() {
 #[test]
 fn test_der_optional() {
     let empty = &b""[..];
-    let bytes1 = [
-        0x30, 0x0a, 0x0a, 0x03, 0x00, 0x00, 0x01, 0x02, 0x03, 0x01, 0x00, 0x01,
-    ];
+    let bytes1 = [0x30, 0x08, 0x0a, 0x01, 0x01, 0x02, 0x03, 0x01, 0x00, 0x01];
     let bytes2 = [0x30, 0x05, 0x02, 0x03, 0x01, 0x00, 0x01];
     let expected1 = DerObject::from_seq(vec![
         DerObject::from_obj(BerObjectContent::Optional(Some(Box::new(
@@ -411,15 +596,17 @@ fn test_der_seq_dn() {
     let expected = DerObject::from_seq(vec![
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 6]).unwrap())), // countryName
-            DerObject::from_obj(BerObjectContent::PrintableString("FR")),
+            DerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed("FR"))),
         ])]),
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 8]).unwrap())), // stateOrProvinceName
-            DerObject::from_obj(BerObjectContent::UTF8String("Some-State")),
+            DerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed("Some-State"))),
         ])]),
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 10]).unwrap())), // organizationName
-            DerObject::from_obj(BerObjectContent::UTF8String("Internet Widgits Pty Ltd")),
+            DerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed(
+                "Internet Widgits Pty Ltd",
+            ))),
         ])]),
     ]);
     assert_eq!(parse_der(&bytes), Ok((empty, expected)));
@@ -438,15 +625,17 @@ fn test_der_seq_dn_defined() {
     let expected = DerObject::from_seq(vec![
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 6]).unwrap())), // countryName
-            DerObject::from_obj(BerObjectContent::PrintableString("FR")),
+            DerObject::from_obj(BerObjectContent::PrintableString(Cow::Borrowed("FR"))),
         ])]),
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 8]).unwrap())), // stateOrProvinceName
-            DerObject::from_obj(BerObjectContent::UTF8String("Some-State")),
+            DerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed("Some-State"))),
         ])]),
         DerObject::from_set(vec![DerObject::from_seq(vec![
             DerObject::from_obj(BerObjectContent::OID(Oid::from(&[2, 5, 4, 10]).unwrap())), // organizationName
-            DerObject::from_obj(BerObjectContent::UTF8String("Internet Widgits Pty Ltd")),
+            DerObject::from_obj(BerObjectContent::UTF8String(Cow::Borrowed(
+                "Internet Widgits Pty Ltd",
+            ))),
         ])]),
     ]);
     #[inline]
@@ -576,3 +765,299 @@ fn test_parse_u64() {
     let s = &[0x01, 0x01, 0xff];
     assert_eq!(parse_der_u64(s), Err(Err::Error(BerError::InvalidTag)));
 }
+
+#[test]
+fn test_der_error_offset() {
+    // non-canonical boolean
+    let bytes = hex!("01 01 01");
+    assert!(parse_ber_with_offset(&bytes).is_ok());
+    assert_eq!(
+        parse_der_with_offset(&bytes),
+        Err(Err::Error(BerErrorWithOffset {
+            error: BerError::DerConstraintFailed,
+            offset: 0
+        }))
+    );
+    // indefinite length is not allowed in DER
+    let bytes = hex!("30 80 02 01 01 00 00");
+    match parse_der_with_offset(&bytes) {
+        Err(Err::Error(e)) => assert_eq!(e.offset, 0),
+        e => panic!("unexpected result {:?}", e),
+    }
+}
+
+#[test]
+fn test_der_default() {
+    let parser = parse_der_default(BerClassTag::universal(BerTag::Integer), parse_der_u32, 0);
+    // present
+    let (rem, v) = parser(&hex!("02 01 03")).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(v, 3);
+    // absent
+    let (rem, v) = parser(&hex!("05 00")).expect("parsing failed");
+    assert_eq!(rem, &hex!("05 00"));
+    assert_eq!(v, 0);
+    // present and equal to the default
+    let res = parser(&hex!("02 01 00"));
+    assert_eq!(res, Err(Err::Error(BerError::DerConstraintFailed)));
+    // the class is compared: a universal INTEGER does not match [2]
+    let bytes = hex!("02 01 03");
+    let parser = parse_der_default(BerClassTag::context(2), parse_der_u32, 0);
+    assert_eq!(parser(&bytes), Ok((&bytes[..], 0)));
+}
+
+#[test]
+fn test_der_raw_encoding() {
+    let bytes = hex!("30 06 02 01 05 01 01 ff");
+    let (_, obj) = parse_der(&bytes).expect("parsing failed");
+    assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+    assert_eq!(obj.content_bytes(), Some(&bytes[2..]));
+    assert_eq!(obj[0].content_bytes(), Some(&hex!("05")[..]));
+    let (_, obj) = parse_der_with_tag(&bytes[2..], BerTag::Integer).expect("parsing failed");
+    assert_eq!(obj.raw_header(), Some(&hex!("02 01")[..]));
+    // the header of tagged values is also kept
+    let bytes = hex!("a0 03 02 01 05");
+    let (_, obj) = parse_ber_tagged_explicit(0, parse_der_integer)(&bytes).expect("parsing failed");
+    assert_eq!(obj.raw_header(), Some(&bytes[..2]));
+    assert_eq!(obj.content_bytes(), Some(&bytes[2..]));
+}
+
+#[test]
+fn test_der_nested() {
+    // basicConstraints extension value: OCTET STRING containing SEQUENCE { BOOLEAN TRUE }
+    let bytes = hex!("04 05 30 03 01 01 ff");
+    let (_, obj) = parse_der_octetstring(&bytes).expect("parsing failed");
+    let inner = obj.as_nested_der().expect("invalid nested object");
+    assert_eq!(inner.header.tag, BerTag::Sequence);
+    assert_eq!(inner[0].as_bool(), Ok(true));
+    // the nested object borrows the input, and outlives the OCTET STRING
+    let inner = {
+        let (_, obj) = parse_der_octetstring(&bytes).expect("parsing failed");
+        obj.as_nested_der().expect("invalid nested object")
+    };
+    assert_eq!(inner[0].as_bool(), Ok(true));
+    // owned content is copied
+    let inner = {
+        let (_, obj) = parse_der_octetstring(&bytes).expect("parsing failed");
+        obj.into_owned()
+            .as_nested_der()
+            .expect("invalid nested object")
+    };
+    assert_eq!(inner[0].as_bool(), Ok(true));
+    // BER-only encoding is accepted by as_nested_ber only
+    let bytes = hex!("04 03 01 01 01");
+    let (_, obj) = parse_der_octetstring(&bytes).expect("parsing failed");
+    assert!(obj.as_nested_ber().is_ok());
+    assert_eq!(obj.as_nested_der(), Err(BerError::DerConstraintFailed));
+    // trailing data, truncated content, and wrong type
+    let (_, obj) = parse_der_octetstring(&hex!("04 04 05 00 05 00")).expect("parsing failed");
+    assert_eq!(obj.as_nested_der(), Err(BerError::InvalidLength));
+    let (_, obj) = parse_der_octetstring(&hex!("04 02 04 05")).expect("parsing failed");
+    assert_eq!(obj.as_nested_der(), Err(BerError::ObjectTooShort));
+    let (_, obj) = parse_der_integer(&hex!("02 01 05")).expect("parsing failed");
+    assert_eq!(obj.as_nested_der(), Err(BerError::BerTypeError));
+}
+
+#[test]
+fn test_der_complete() {
+    let bytes = hex!("30 06 02 01 01 02 01 02");
+    let (rem, obj) = parse_der_complete(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(obj.len(), 2);
+    let bytes = hex!("30 06 02 01 01 02 01 02 ff ff ff");
+    assert!(parse_der(&bytes).is_ok());
+    assert_eq!(
+        parse_der_complete(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(3)))
+    );
+    // errors from the object itself are returned unchanged
+    assert_eq!(
+        parse_der_complete(&hex!("01 01 01")),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+}
+
+#[test]
+fn test_der_external() {
+    let bytes = hex!("28 09 06 02 51 01 81 03 61 62 63");
+    let (_, obj) = parse_der(&bytes).expect("parsing failed");
+    assert_eq!(
+        obj.as_external().map(|e| &e.encoding),
+        Ok(&BerExternalEncoding::OctetAligned(Cow::Borrowed(b"abc")))
+    );
+    // constructed octet-aligned and arbitrary encodings are not allowed
+    let bytes = hex!("28 0b 06 02 51 01 a1 05 04 03 61 62 63");
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    let bytes = hex!("28 0b 06 02 51 01 a2 05 03 03 04 a0 b0");
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+}
+
+#[test]
+fn test_content_info() {
+    let empty = &b""[..];
+    // id-data, [0] EXPLICIT OCTET STRING
+    let bytes = hex!("30 12 06 09 2a 86 48 86 f7 0d 01 07 01 a0 05 04 03 61 62 63");
+    let (rem, (oid, content)) = parse_ber_content_info(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(oid, oid!(1.2.840 .113549 .1 .7 .1));
+    assert_eq!(content.as_slice(), Ok(&b"abc"[..]));
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        parse_ber_content_info(&bytes)
+    );
+    // indefinite length, accepted only in BER
+    let bytes = hex!("30 80 06 09 2a 86 48 86 f7 0d 01 07 01 a0 80 04 03 61 62 63 00 00 00 00");
+    let (rem, (_, content)) = parse_ber_content_info(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(content.as_slice(), Ok(&b"abc"[..]));
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // non-minimal lengths, accepted only in BER
+    let bytes = hex!("30 81 12 06 09 2a 86 48 86 f7 0d 01 07 01 a0 05 04 03 61 62 63");
+    assert!(parse_ber_content_info(&bytes).is_ok());
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    let bytes = hex!("30 13 06 09 2a 86 48 86 f7 0d 01 07 01 a0 81 05 04 03 61 62 63");
+    assert!(parse_ber_content_info(&bytes).is_ok());
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // the content must be [0] EXPLICIT
+    let bytes = hex!("30 12 06 09 2a 86 48 86 f7 0d 01 07 01 80 05 04 03 61 62 63");
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        Err(Err::Error(BerError::ConstructExpected))
+    );
+    // missing content
+    let bytes = hex!("30 0b 06 09 2a 86 48 86 f7 0d 01 07 01");
+    assert!(parse_ber_content_info(&bytes).is_err());
+    // trailing data in the SEQUENCE
+    let bytes = hex!("30 14 06 09 2a 86 48 86 f7 0d 01 07 01 a0 05 04 03 61 62 63 05 00");
+    assert_eq!(
+        parse_ber_content_info(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+    // trailing data in the [0] tag
+    let bytes = hex!("30 14 06 09 2a 86 48 86 f7 0d 01 07 01 a0 07 04 03 61 62 63 05 00");
+    assert_eq!(
+        parse_ber_content_info(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+    assert_eq!(
+        parse_der_content_info(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+}
+
+#[test]
+fn test_algorithm_identifier() {
+    let empty = &b""[..];
+    // sha256WithRSAEncryption, NULL parameters
+    let bytes = hex!("30 0d 06 09 2a 86 48 86 f7 0d 01 01 0b 05 00");
+    let (rem, (oid, params)) = parse_ber_algorithm_identifier(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(oid, oid!(1.2.840 .113549 .1 .1 .11));
+    assert!(params.expect("missing parameters").is_null());
+    assert_eq!(
+        parse_der_algorithm_identifier(&bytes),
+        parse_ber_algorithm_identifier(&bytes)
+    );
+    // ed25519, absent parameters
+    let bytes = hex!("30 05 06 03 2b 65 70");
+    let (rem, (oid, params)) = parse_der_algorithm_identifier(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(oid, oid!(1.3.101 .112));
+    assert_eq!(params, None);
+    // ecPublicKey, OID parameters (prime256v1)
+    let bytes = hex!("30 13 06 07 2a 86 48 ce 3d 02 01 06 08 2a 86 48 ce 3d 03 01 07");
+    let (_, (_, params)) = parse_der_algorithm_identifier(&bytes).expect("parsing failed");
+    let params = params.expect("missing parameters");
+    assert_eq!(params.as_oid_val(), Ok(oid!(1.2.840 .10045 .3 .1 .7)));
+    // non-minimal length of parameters, accepted only in BER
+    let bytes = hex!("30 0e 06 09 2a 86 48 86 f7 0d 01 01 0b 05 81 00");
+    assert!(parse_ber_algorithm_identifier(&bytes).is_ok());
+    assert!(parse_der_algorithm_identifier(&bytes).is_err());
+    // non-minimal length of the SEQUENCE, accepted only in BER
+    let bytes = hex!("30 81 05 06 03 2b 65 70");
+    assert!(parse_ber_algorithm_identifier(&bytes).is_ok());
+    assert_eq!(
+        parse_der_algorithm_identifier(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // more than one parameter
+    let bytes = hex!("30 09 06 03 2b 65 70 05 00 05 00");
+    assert_eq!(
+        parse_ber_algorithm_identifier(&bytes),
+        Err(Err::Error(BerError::UnexpectedData(2)))
+    );
+}
+
+#[test]
+fn test_private_key_info() {
+    let empty = &b""[..];
+    // ed25519 private key, with attributes (RFC 8410)
+    let bytes = hex!(
+        "30 3f 02 01 00 30 05 06 03 2b 65 70 04 22 04 20
+         d4 ee 72 db f9 13 58 4a d5 b6 d8 f1 f7 69 f8 ad
+         3a fe 7c 28 cb f1 d4 fb e0 97 a8 8f 44 75 58 42
+         a0 0f 30 0d 06 03 55 04 03 31 06 0c 04 43 75 72 64"
+    );
+    let (rem, (version, (algorithm, params), private_key, attributes)) =
+        parse_der_private_key_info(&bytes).expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(version, 0);
+    assert_eq!(algorithm, oid!(1.3.101 .112));
+    assert_eq!(params, None);
+    assert_eq!(private_key.as_slice(), Ok(&bytes[14..48]));
+    let attributes = attributes.expect("missing attributes");
+    assert_eq!(attributes.header.tag, BerTag(0));
+    assert_eq!(attributes.as_set().map(|v| v.len()), Ok(1));
+    assert_eq!(
+        parse_ber_private_key_info(&bytes),
+        parse_der_private_key_info(&bytes)
+    );
+    // BER: indefinite length
+    let bytes = hex!("30 80 02 01 00 30 05 06 03 2b 65 70 04 02 04 00 00 00");
+    let (_, (_, _, private_key, attributes)) =
+        parse_ber_private_key_info(&bytes).expect("parsing failed");
+    assert_eq!(private_key.as_slice(), Ok(&hex!("04 00")[..]));
+    assert!(attributes.is_none());
+    assert_eq!(
+        parse_der_private_key_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // non-minimal lengths, accepted only in BER
+    let bytes = hex!("30 81 0e 02 01 00 30 05 06 03 2b 65 70 04 02 04 00");
+    assert!(parse_ber_private_key_info(&bytes).is_ok());
+    assert_eq!(
+        parse_der_private_key_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    let bytes = hex!("30 11 02 01 00 30 05 06 03 2b 65 70 04 02 04 00 a0 81 00");
+    assert!(parse_ber_private_key_info(&bytes).is_ok());
+    assert_eq!(
+        parse_der_private_key_info(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // unexpected field
+    let bytes = hex!("30 11 02 01 00 30 05 06 03 2b 65 70 04 02 04 00 81 01 00");
+    assert_eq!(
+        parse_ber_private_key_info(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+    assert_eq!(
+        parse_der_private_key_info(&bytes),
+        Err(Err::Error(BerError::InvalidTag))
+    );
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "derive")]
+
+use der_parser::ber::*;
+use der_parser::error::*;
+use der_parser::oid::Oid;
+use der_parser::BerSequence;
+use hex_literal::hex;
+use nom::{Err, Needed};
+
+/// AlgorithmIdentifier ::= SEQUENCE {
+///     algorithm   OBJECT IDENTIFIER,
+///     parameters  ANY DEFINED BY algorithm OPTIONAL }
+#[derive(Debug, PartialEq, BerSequence)]
+struct AlgorithmIdentifier<'a> {
+    algorithm: Oid<'a>,
+    #[ber(optional)]
+    parameters: Option<BerObject<'a>>,
+}
+
+/// SEQUENCE {
+///     version     [0] EXPLICIT INTEGER OPTIONAL,
+///     serial      INTEGER,
+///     flag        [1] IMPLICIT BOOLEAN,
+///     algorithm   AlgorithmIdentifier,
+///     data        [2] IMPLICIT OCTET STRING OPTIONAL }
+#[derive(Debug, PartialEq, BerSequence)]
+struct Record<'a> {
+    #[ber(tag = 0, explicit, optional)]
+    version: Option<u32>,
+    serial: u64,
+    #[ber(tag = 1)]
+    flag: bool,
+    algorithm: AlgorithmIdentifier<'a>,
+    #[ber(tag = 2, optional)]
+    data: Option<&'a [u8]>,
+}
+
+#[derive(Debug, PartialEq, BerSequence)]
+struct NoLifetime {
+    a: u32,
+    b: bool,
+}
+
+#[test]
+fn derive_algorithm_identifier() {
+    let bytes = hex!("30 0d 06 09 2a 86 48 86 f7 0d 01 01 0b 05 00");
+    let (rem, alg) = AlgorithmIdentifier::from_ber(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(alg.algorithm.to_id_string(), "1.2.840.113549.1.1.11");
+    let params = alg.parameters.expect("no parameters");
+    assert_eq!(params.header.tag, BerTag::Null);
+    // parameters are absent
+    let bytes = hex!("30 05 06 03 2b 65 70");
+    let (_, alg) = AlgorithmIdentifier::from_ber(&bytes).expect("parsing failed");
+    assert_eq!(alg.algorithm.to_id_string(), "1.3.101.112");
+    assert!(alg.parameters.is_none());
+    // parameters are truncated: the error is not hidden
+    let bytes = hex!("30 09 06 03 2b 65 70 04 05 41 42");
+    assert_eq!(
+        AlgorithmIdentifier::from_ber(&bytes),
+        Err(Err::Incomplete(Needed::Size(3)))
+    );
+}
+
+#[test]
+fn derive_tagged_fields() {
+    let bytes = hex!(
+        "30 16"
+        "a0 03 02 01 02"
+        "02 01 07"
+        "81 01 ff"
+        "30 05 06 03 2b 65 70"
+        "82 02 41 42"
+    );
+    let (rem, record) = Record::from_ber(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    assert_eq!(record.version, Some(2));
+    assert_eq!(record.serial, 7);
+    assert!(record.flag);
+    assert_eq!(record.algorithm.algorithm.to_id_string(), "1.3.101.112");
+    assert_eq!(record.data, Some(&b"AB"[..]));
+    // optional fields are absent
+    let bytes = hex!("30 0d 02 01 07 81 01 00 30 05 06 03 2b 65 70");
+    let (_, record) = Record::from_ber(&bytes).expect("parsing failed");
+    assert_eq!(record.version, None);
+    assert!(!record.flag);
+    assert_eq!(record.data, None);
+}
+
+#[test]
+fn derive_errors() {
+    // not a sequence
+    let bytes = hex!("31 06 02 01 01 01 01 ff");
+    assert_eq!(
+        NoLifetime::from_ber(&bytes),
+        Err(Err::Error(BerError::BerTypeError))
+    );
+    // missing field
+    let bytes = hex!("30 03 02 01 01");
+    assert!(NoLifetime::from_ber(&bytes).is_err());
+    // wrong type
+    let bytes = hex!("30 06 01 01 ff 01 01 ff");
+    assert!(NoLifetime::from_ber(&bytes).is_err());
+    let bytes = hex!("30 06 02 01 01 01 01 ff");
+    let (_, v) = NoLifetime::from_ber(&bytes).expect("parsing failed");
+    assert_eq!(v, NoLifetime { a: 1, b: true });
+}
@@ -125,3 +125,35 @@ fn oid_macro_edge_cases() {
     let spacing = oid!(5.2);
     assert_eq!(spacing.bytes(), [5 * 40 + 2].as_ref());
 }
+
+#[test]
+fn ber_tuple_macro() {
+    use der_parser::ber::{parse_ber_octetstring, FromBer};
+    use der_parser::error::BerError;
+
+    let empty = &b""[..];
+    let bytes = [
+        0x30, 0x0b, 0x02, 0x01, 0x01, 0x01, 0x01, 0xff, 0x04, 0x03, 0x61, 0x62, 0x63,
+    ];
+    let (rem, (a, b, c)) =
+        parse_ber_tuple!(u32::from_ber, bool::from_ber, parse_ber_octetstring)(&bytes)
+            .expect("parsing failed");
+    assert_eq!(rem, empty);
+    assert_eq!(a, 1);
+    assert_eq!(b, true);
+    assert_eq!(c.as_slice(), Ok(&b"abc"[..]));
+    // leftover element in the sequence
+    let res = parse_ber_tuple!(u32::from_ber, bool::from_ber)(&bytes);
+    assert_eq!(
+        res,
+        Err(der_parser::nom::Err::Error(BerError::UnexpectedData(5)))
+    );
+    // missing element
+    let res = parse_ber_tuple!(
+        u32::from_ber,
+        bool::from_ber,
+        parse_ber_octetstring,
+        u32::from_ber,
+    )(&bytes);
+    assert!(res.is_err());
+}
@@ -20,3 +20,54 @@ fn test_compare_oid() {
     let oid = Oid::from(&[1, 2, 840, 113_549, 1, 1, 1]).unwrap();
     assert!(compare_oid(&oid));
 }
+
+#[cfg(feature = "oid-names")]
+#[test]
+fn test_oid_to_name() {
+    assert_eq!(oid!(2.5.4.3).to_name(), Some("commonName"));
+    assert_eq!(oid!(2.5.29.32.0).to_name(), Some("anyPolicy"));
+    assert_eq!(
+        oid!(1.2.840.113549.1.1.11).to_name(),
+        Some("sha256WithRSAEncryption")
+    );
+    assert_eq!(oid!(2.5.4.3.1).to_name(), None);
+    assert_eq!(oid!(rel 2.5.4.3).to_name(), None);
+}
+
+#[test]
+fn test_oid_ord() {
+    use std::collections::{BTreeMap, HashSet};
+    // 16383 is encoded as ff 7f, and 16384 as 81 80 00
+    let a = oid!(1.2.16383);
+    let b = oid!(1.2.16384);
+    assert!(a.bytes() > b.bytes());
+    assert!(a < b);
+    let mut v = vec![
+        oid!(2.5.4.3),
+        oid!(1.2.840.113549),
+        oid!(1.2.16384),
+        oid!(1.2),
+        oid!(1.2.16383),
+        oid!(rel 1.2),
+    ];
+    v.sort();
+    assert_eq!(
+        v,
+        vec![
+            oid!(1.2),
+            oid!(1.2.840.113549),
+            oid!(1.2.16383),
+            oid!(1.2.16384),
+            oid!(2.5.4.3),
+            oid!(rel 1.2),
+        ]
+    );
+    assert_eq!(oid!(1.2.3).cmp(&oid!(1.2.3)), std::cmp::Ordering::Equal);
+    let mut map = BTreeMap::new();
+    map.insert(oid!(2.5.4.3), "CN");
+    map.insert(oid!(2.5.4.10), "O");
+    assert_eq!(map.get(&oid!(2.5.4.3)), Some(&"CN"));
+    let set: HashSet<_> = v.iter().cloned().collect();
+    assert!(set.contains(&oid!(1.2.16384)));
+    assert!(!set.contains(&oid!(1.2.16385)));
+}
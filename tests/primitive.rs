@@ -4,6 +4,7 @@ use der_parser::error::*;
 use der_parser::oid::Oid;
 use hex_literal::hex;
 use nom::*;
+use std::borrow::Cow;
 
 #[test]
 fn test_flat_take() {
@@ -87,13 +88,19 @@ fn test_unknown_tag() {
     assert!(res.0.is_empty());
     assert_eq!(
         res.1,
-        BerObject::from_obj(BerObjectContent::Unknown(BerTag(0x1d), &bytes[2..]))
+        BerObject::from_obj(BerObjectContent::Unknown(
+            BerTag(0x1d),
+            Cow::Borrowed(&bytes[2..])
+        ))
     );
     let res = parse_der(&bytes).expect("parsing failed");
     assert!(res.0.is_empty());
     assert_eq!(
         res.1,
-        BerObject::from_obj(BerObjectContent::Unknown(BerTag(0x1d), &bytes[2..]))
+        BerObject::from_obj(BerObjectContent::Unknown(
+            BerTag(0x1d),
+            Cow::Borrowed(&bytes[2..])
+        ))
     );
 }
 
@@ -107,7 +114,7 @@ fn test_unknown_context_specific() {
         BerObject {
             header: BerObjectHeader::new(BerClass::ContextSpecific, 0, BerTag(0), 1)
                 .with_raw_tag(Some(&[0x80])),
-            content: BerObjectContent::Unknown(BerTag(0x0), &bytes[2..]),
+            content: BerObjectContent::Unknown(BerTag(0x0), Cow::Borrowed(&bytes[2..])),
         }
     );
 }
@@ -122,7 +129,7 @@ fn test_unknown_long_tag() {
         BerObject {
             header: BerObjectHeader::new(BerClass::ContextSpecific, 0, BerTag(0x22), 1)
                 .with_raw_tag(Some(&[0x9f, 0x22])),
-            content: BerObjectContent::Unknown(BerTag(0x22), &bytes[3..]),
+            content: BerObjectContent::Unknown(BerTag(0x22), Cow::Borrowed(&bytes[3..])),
         }
     );
 }
@@ -137,11 +144,46 @@ fn test_unknown_longer_tag() {
         BerObject {
             header: BerObjectHeader::new(BerClass::ContextSpecific, 0, BerTag(0x1122), 1)
                 .with_raw_tag(Some(&[0x9f, 0xa2, 0x22])),
-            content: BerObjectContent::Unknown(BerTag(0x1122), &bytes[4..]),
+            content: BerObjectContent::Unknown(BerTag(0x1122), Cow::Borrowed(&bytes[4..])),
         }
     );
 }
 
+#[test]
+fn test_context_tag_42() {
+    let bytes = hex!("bf 2a 03 02 01 05");
+    let (rem, hdr) = ber_read_element_header(&bytes).expect("parsing failed");
+    assert_eq!(rem, &bytes[3..]);
+    assert_eq!(hdr.class, BerClass::ContextSpecific);
+    assert_eq!(hdr.tag, BerTag(42));
+    assert!(hdr.is_constructed());
+    let (rem, obj) =
+        parse_ber_tagged_explicit(42, parse_der_integer)(&bytes).expect("parsing failed");
+    assert!(rem.is_empty());
+    let (_, _, inner) = obj.as_tagged().expect("not tagged");
+    assert_eq!(inner.as_u32(), Ok(5));
+}
+
+#[test]
+fn test_der_long_tag_minimal() {
+    // leading 0x80 continuation byte
+    let bytes = hex!("9f 80 2a 01 00");
+    assert!(parse_ber(&bytes).is_ok());
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    // long form used for a tag < 31
+    let bytes = hex!("9f 0f 01 00");
+    assert!(parse_ber(&bytes).is_ok());
+    assert_eq!(
+        parse_der(&bytes),
+        Err(Err::Error(BerError::DerConstraintFailed))
+    );
+    let bytes = hex!("9f 1f 01 00");
+    assert!(parse_der(&bytes).is_ok());
+}
+
 #[test]
 fn test_incomplete_tag() {
     let bytes = hex!("9f a2 a2");
@@ -154,6 +196,13 @@ fn test_overflow_tag() {
     let bytes = hex!("9f a2 a2 a2 a2 a2 22 01 00");
     let res = parse_ber(&bytes);
     assert!(res.is_err());
+    // 5 bytes, but value does not fit in 32 bits
+    let bytes = hex!("9f 90 80 80 80 00 01 00");
+    let res = parse_ber(&bytes);
+    assert!(res.is_err());
+    let bytes = hex!("9f 8f ff ff ff 7f 01 00");
+    let res = parse_ber(&bytes).expect("parsing failed");
+    assert_eq!(res.1.header.tag, BerTag(std::u32::MAX));
 }
 
 #[test]
@@ -173,7 +222,24 @@ fn test_incomplete_length() {
     assert_eq!(res, Err::Incomplete(Needed::Size(5)));
     let bytes = hex!("02 85 ff");
     let res = parse_ber(&bytes).err().expect("expected error");
-    assert_eq!(res, Err::Incomplete(Needed::Size(5)));
+    assert_eq!(res, Err::Incomplete(Needed::Size(4)));
+}
+
+#[test]
+fn test_incomplete_content() {
+    // the number of missing bytes is computed from the declared length
+    let bytes = hex!("04 05 01 02");
+    let res = parse_ber(&bytes).err().expect("expected error");
+    assert_eq!(res, Err::Incomplete(Needed::Size(3)));
+    let res = parse_der(&bytes).err().expect("expected error");
+    assert_eq!(res, Err::Incomplete(Needed::Size(3)));
+    let bytes = hex!("04 82 01 00 01 02");
+    let res = parse_ber(&bytes).err().expect("expected error");
+    assert_eq!(res, Err::Incomplete(Needed::Size(254)));
+    // context-specific object, content is not parsed
+    let bytes = hex!("a0 04 01");
+    let res = parse_ber(&bytes).err().expect("expected error");
+    assert_eq!(res, Err::Incomplete(Needed::Size(3)));
 }
 
 #[test]
@@ -194,7 +260,7 @@ fn test_invalid_length() {
     }
     let bytes = hex!("02 02 00");
     let res = parse_der(&bytes).err().expect("expected error");
-    assert_eq!(res, Err::Incomplete(Needed::Size(2)));
+    assert_eq!(res, Err::Incomplete(Needed::Size(1)));
 }
 
 #[test]